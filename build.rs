@@ -1,14 +1,92 @@
 //! This build script handles:
+//! - Checking the board and test number features are consistent.
 //! - Exposing build-time information to the application.
 //! - Copying `memory.x` to the output directory to allow the firmware to be
 //!   created.
+//! - Reserving flash for the result log, with the `result-log` feature.
+//! - Generating the drive pattern, with the `pattern` feature.
+//! - Linking the `riscv-toggle` binary, with the `riscv` feature.
+//!
+//! ## Feature checks
+//!
+//! Exactly one of the `pico` and `pico2` board features must be enabled,
+//! and at most one test number feature.  The Pico W and Pico 2 W features,
+//! `pico-w` and `pico2-w`, enable `pico` and `pico2`, as the boards have
+//! the same chips.  With `single-gpio`, and without
+//! `runtime-select`, exactly one test number is required.  Otherwise the
+//! build fails here, rather than producing firmware with the wrong
+//! `memory.x` or timing constants, or running an unexpected test.
+//!
+//! With `quiet`, which promises that nothing logs while a test's loop runs,
+//! the features which log from within the loop, `measure` and `histogram`,
+//! are rejected, as is a `DEFMT_LOG` filter enabling debug or trace logging
+//! for any crate, as embassy's crates log at those levels from their
+//! executor and interrupt handlers.  `DEFMT_LOG` is read here as defmt reads
+//! it, so this follows `.cargo/config.toml` or the environment, and the
+//! check re-runs when it changes.
+//!
+//! Exactly one of `rtt`, a default feature which brings in `defmt-rtt` and
+//! `panic-probe`, and `no-defmt`, which replaces them, must be enabled, so
+//! a `--no-default-features` build needs one of them adding back.  With
+//! `no-defmt`, `DEFMT_LOG` is overridden to `off` for this crate, so its
+//! logging compiles to nothing.
+//!
+//! The `riscv` feature, which enables `pico2`, must be used exactly when
+//! building for RISC-V, `riscv32imac-unknown-none-elf`, and needs `no-defmt`,
+//! as `defmt-rtt` and `panic-probe` are Arm only.
 //!
 //! ## Build-time information
 //!
+//! The build profile and opt-level are exposed as `BUILD_PROFILE` and
+//! `BUILD_OPT_LEVEL`, the short git hash as `GIT_HASH`, and the UTC time
+//! the build script ran as `BUILD_TIME`, so tests can report what they were
+//! built with.  Outside a git checkout, `GIT_HASH` is "unknown".
+//! `BUILD_TIME` is taken from `SOURCE_DATE_EPOCH` if set, for reproducible
+//! builds.  As the build script only re-runs when git or its inputs change,
+//! `BUILD_TIME` is when it last ran, not necessarily the latest build.
+//!
+//! The enabled features are exposed as `BUILD_FEATURES`, sorted and comma
+//! separated, such as "1,default,measure,pico,rtt,single-gpio", so a capture
+//! records exactly what it was built with.  Cargo only passes them as
+//! `CARGO_FEATURE_*` variables, uppercased with `-` replaced by `_`, so
+//! they're lowercased and `_` is mapped back to `-`, which every feature in
+//! this crate uses.
+//!
 //! ## `memory.x` file handling
 //!
 //! This build script copies the appropriate `memory.x` file from the `link/`
-//! into a directory where the linker can always find it at build time.
+//! into a directory where the linker can always find it at build time.  The
+//! W boards use their chip's file, as their flash is the same too.  For a
+//! board with a different memory layout, set `MEMORY_X_PATH` to a
+//! replacement file, relative to the crate's root if not absolute.  It
+//! mustn't be the crate root's `memory.x`, which the linker would find
+//! first, bypassing the result log's reservation.
+//!
+//! With the `result-log` feature, the end of flash is removed from the
+//! `FLASH` region, so the firmware can never be linked into it, and its start
+//! is exported as `__result_log_start`.  The flash size the region implies,
+//! from the board's `memory.x` or `MEMORY_X_PATH` - its original end,
+//! relative to the start of flash - is exposed as `FLASH_SIZE`, in bytes,
+//! for the result log's flash driver.  Its `ORIGIN` and `LENGTH` must be
+//! sums of numbers, optionally hex and with `K` or `M` suffixes, such as
+//! `2048K - 0x100`.
+//!
+//! ## RISC-V
+//!
+//! With the `riscv` feature, the only binary, `riscv-toggle`, doesn't use
+//! cortex-m-rt or embassy, so is linked with `link/link-riscv.x`, copied to
+//! the output directory alongside `memory.x`, in place of the Arm linker
+//! files.  Its `memory.x` is `link/memory.rp235x-riscv.x`, which has only
+//! the `MEMORY` regions, as the Arm file adds sections relative to
+//! cortex-m-rt's.
+//!
+//! ## Drive pattern
+//!
+//! With the `pattern` feature, the pattern file - `pattern.txt`, or the file
+//! given by the `PATTERN_FILE` environment variable - is parsed into a
+//! `PATTERN` array in `pattern.rs` in the output directory, which the pattern
+//! test includes.  The file's format is described in `pattern.txt`.  Any
+//! error in the file, including an empty pattern, fails the build.
 
 // Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
 //
@@ -20,50 +98,447 @@ use std::env;
 use std::fs::File;
 use std::io::Write;
 use std::path::PathBuf;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Size of the flash reserved for the result log - two 4K sectors.  Must match
+// the result log's SECTORS.
+#[cfg(feature = "result-log")]
+const RESULT_LOG_SIZE: &str = "8K";
+
+// The board's memory.x file, and its contents.  The fallback, with no board
+// feature, is never used, as check_features() fails the build first, but
+// lets this compile so that it can.
+#[cfg(feature = "pico")]
+const BOARD_MEMORY_X: (&str, &[u8]) = (
+    "link/memory.rp2040.x",
+    include_bytes!("link/memory.rp2040.x"),
+);
+#[cfg(all(feature = "pico2", not(feature = "pico"), not(feature = "riscv")))]
+const BOARD_MEMORY_X: (&str, &[u8]) = (
+    "link/memory.rp235x.x",
+    include_bytes!("link/memory.rp235x.x"),
+);
+#[cfg(all(feature = "riscv", not(feature = "pico")))]
+const BOARD_MEMORY_X: (&str, &[u8]) = (
+    "link/memory.rp235x-riscv.x",
+    include_bytes!("link/memory.rp235x-riscv.x"),
+);
+#[cfg(not(any(feature = "pico", feature = "pico2")))]
+const BOARD_MEMORY_X: (&str, &[u8]) = ("", &[]);
+
+// Default drive pattern file, and the longest step it may contain, in ns.
+// Each step is timed with SysTick, so must be under 2^24 cycles at the
+// fastest clk_sys the build can run at - its board's default, or the
+// sysclk feature's overclock.  That's rounded down to whole ms, which
+// leaves the wait loop ample margin to notice the deadline: 134ms at
+// 125MHz, 111ms at 150MHz, 83ms at 200MHz and 67ms at 250MHz.
+#[cfg(feature = "pattern")]
+const DEFAULT_PATTERN_FILE: &str = "pattern.txt";
+#[cfg(feature = "pattern")]
+const FASTEST_CLK_SYS_HZ: u64 = if cfg!(feature = "sysclk-250") {
+    250_000_000
+} else if cfg!(feature = "sysclk-200") {
+    200_000_000
+} else if cfg!(feature = "pico2") {
+    150_000_000
+} else {
+    125_000_000
+};
+#[cfg(feature = "pattern")]
+const MAX_STEP_NS: u64 = (1 << 24) * 1_000 / FASTEST_CLK_SYS_HZ * 1_000_000;
+
+// The highest test number feature.  Must match TestNum in main.rs.
+const MAX_TEST_NUM: u8 = 28;
 
 fn main() {
+    check_features();
+
     // Expose build-time information to the application.
 
     // Re-run this build script if anything in git changes.
     println!("cargo:rerun-if-changed=.git/HEAD");
     println!("cargo:rerun-if-changed=.git/refs/");
 
-    // Re-run this build script of DEFMT_LOG changes.
+    // Re-run this build script if DEFMT_LOG changes, for the quiet check and
+    // defmt's log level.
     println!("cargo:rerun-if-env-changed=DEFMT_LOG");
 
-    // RP2040 and RP235X use different memory.x files.  Ensure the build
-    // script is re-run if the appropriate memory.x file changes.  Note that
-    // neither file should be called memory.x, as then the linker will pick up
-    // that file from our root directory, instead of the version we put in
-    // OUT_DIR, below.
-    #[cfg(feature = "pico")]
-    let memory_x = {
-        println!("cargo:rerun-if-changed=link/memory.rp2040.x");
-        include_bytes!("link/memory.rp2040.x")
-    };
-    #[cfg(feature = "pico2")]
-    let memory_x = {
-        println!("cargo:rerun-if-changed=link/memory.rp235x.x");
-        include_bytes!("link/memory.rp235x.x")
+    // With no-defmt, turn off all of this crate's logging, whatever
+    // DEFMT_LOG is set to.
+    if env::var_os("CARGO_FEATURE_NO_DEFMT").is_some() {
+        println!("cargo:rustc-env=DEFMT_LOG=off");
+    }
+
+    // Expose the build profile and opt-level.
+    println!(
+        "cargo:rustc-env=BUILD_PROFILE={}",
+        env::var("PROFILE").unwrap()
+    );
+    println!(
+        "cargo:rustc-env=BUILD_OPT_LEVEL={}",
+        env::var("OPT_LEVEL").unwrap()
+    );
+
+    // Expose the short git hash, or "unknown" if it can't be determined.
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short=8", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=GIT_HASH={}", git_hash);
+
+    // Expose the build time, in UTC.
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    let build_secs = env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|since| since.as_secs())
+                .unwrap_or(0)
+        });
+    println!("cargo:rustc-env=BUILD_TIME={}", utc_time(build_secs));
+
+    // Expose the enabled features.
+    println!("cargo:rustc-env=BUILD_FEATURES={}", enabled_features());
+
+    // RP2040 and RP235X use different memory.x files, and the W boards use
+    // their chip's, as pico-w and pico2-w enable pico and pico2.  Ensure the
+    // build script is re-run if the appropriate memory.x file changes.  Note
+    // that neither file should be called memory.x, as then the linker will
+    // pick up that file from our root directory, instead of the version we
+    // put in OUT_DIR, below.
+    let (board_memory_x_file, board_memory_x) = BOARD_MEMORY_X;
+    println!("cargo:rerun-if-changed={}", board_memory_x_file);
+
+    // MEMORY_X_PATH, if set, replaces the board's memory.x, for boards with
+    // other flash sizes.  The same caveat about the name applies.
+    println!("cargo:rerun-if-env-changed=MEMORY_X_PATH");
+    let memory_x = match env::var_os("MEMORY_X_PATH") {
+        Some(file) => {
+            let path = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap()).join(file);
+            println!("cargo:rerun-if-changed={}", path.display());
+            std::fs::read(&path).unwrap_or_else(|e| {
+                panic!("Failed to read MEMORY_X_PATH {}: {}", path.display(), e)
+            })
+        }
+        None => board_memory_x.to_vec(),
     };
 
+    #[cfg(feature = "result-log")]
+    let memory_x = reserve_result_log(&memory_x);
+
     // Put `memory.x` in our output directory and ensure it's on the linker
     // search path.
     let out = &PathBuf::from(env::var_os("OUT_DIR").unwrap());
     File::create(out.join("memory.x"))
         .unwrap()
-        .write_all(memory_x)
+        .write_all(&memory_x)
         .unwrap();
     println!("cargo:rustc-link-search={}", out.display());
 
-    // Set embassy linker arguments for the binary.
+    #[cfg(feature = "pattern")]
+    generate_pattern(out);
+
     println!("cargo:rustc-link-arg=-v");
-    println!("cargo:rustc-link-arg-bins=--nmagic");
-    println!("cargo:rustc-link-arg-bins=-Tlink.x");
-    println!("cargo:rustc-link-arg-bins=-Tdefmt.x");
-    println!("cargo:rustc-link-arg-bins=-Tdevice.x");
+
+    // The RISC-V binary has its own linker file, from the same directory.
+    #[cfg(feature = "riscv")]
+    {
+        println!("cargo:rerun-if-changed=link/link-riscv.x");
+        File::create(out.join("link-riscv.x"))
+            .unwrap()
+            .write_all(include_bytes!("link/link-riscv.x"))
+            .unwrap();
+        println!("cargo:rustc-link-arg-bins=--nmagic");
+        println!("cargo:rustc-link-arg-bins=-Tlink-riscv.x");
+    }
+
+    // Set embassy linker arguments for the binary.
+    #[cfg(not(feature = "riscv"))]
+    {
+        println!("cargo:rustc-link-arg-bins=--nmagic");
+        println!("cargo:rustc-link-arg-bins=-Tlink.x");
+        println!("cargo:rustc-link-arg-bins=-Tdefmt.x");
+        println!("cargo:rustc-link-arg-bins=-Tdevice.x");
+    }
 
     // Only RP2040 uses this linker file.
     #[cfg(feature = "pico")]
     println!("cargo:rustc-link-arg-bins=-Tlink-rp.x");
 }
+
+// Fails the build if the board or test number features are inconsistent.
+fn check_features() {
+    let enabled = |feature: &str| env::var_os(format!("CARGO_FEATURE_{}", feature)).is_some();
+
+    match (enabled("PICO"), enabled("PICO2")) {
+        (true, false) | (false, true) => (),
+        (true, true) => panic!(
+            "Both the pico and pico2 features are enabled (pico-w and pico2-w enable them) - use --no-default-features with pico2 or pico2-w, and rtt"
+        ),
+        (false, false) => {
+            panic!("One of the pico, pico2, pico-w and pico2-w features must be enabled")
+        }
+    }
+
+    match (enabled("RTT"), enabled("NO_DEFMT")) {
+        (true, false) | (false, true) => (),
+        (true, true) => panic!(
+            "no-defmt replaces defmt-rtt and panic-probe, so can't be used with rtt - use --no-default-features with no-defmt"
+        ),
+        (false, false) => panic!(
+            "Neither rtt nor no-defmt is enabled, so there is no logger or panic handler - add rtt, which --no-default-features leaves out"
+        ),
+    }
+
+    let riscv_target = env::var("CARGO_CFG_TARGET_ARCH").is_ok_and(|arch| arch == "riscv32");
+    match (enabled("RISCV"), riscv_target) {
+        (true, true) if !enabled("NO_DEFMT") => panic!(
+            "defmt-rtt and panic-probe are Arm only, so riscv needs no-defmt, in place of rtt"
+        ),
+        (true, true) | (false, false) => (),
+        (true, false) => panic!(
+            "The riscv feature is for the Pico 2's RISC-V cores - build with --target riscv32imac-unknown-none-elf"
+        ),
+        (false, true) => panic!(
+            "Building for RISC-V needs the riscv feature, and --bin riscv-toggle, as the timing binary is Arm only"
+        ),
+    }
+
+    let test_nums: Vec<u8> = (1..=MAX_TEST_NUM)
+        .filter(|num| enabled(&num.to_string()))
+        .collect();
+    if test_nums.len() > 1 {
+        panic!(
+            "Multiple test number features are enabled: {:?} - enable only one",
+            test_nums
+        );
+    }
+    if test_nums.is_empty() && enabled("SINGLE_GPIO") && !enabled("RUNTIME_SELECT") {
+        panic!(
+            "single-gpio needs a test number - enable one of features 1-{}, or use runtime-select",
+            MAX_TEST_NUM
+        );
+    }
+
+    if enabled("QUIET") {
+        for feature in ["measure", "histogram"] {
+            if enabled(&feature.to_uppercase()) {
+                panic!(
+                    "{} logs from within the test loop, so can't be used with quiet",
+                    feature
+                );
+            }
+        }
+        let filter = env::var("DEFMT_LOG").unwrap_or_default();
+        if let Some(entry) = verbose_defmt_log(&filter) {
+            panic!(
+                "DEFMT_LOG={} enables debug or trace logging ({}), which may log from within the test loop, so can't be used with quiet - use info or less",
+                filter, entry
+            );
+        }
+    }
+}
+
+// Returns the enabled features, sorted and comma separated, recovered from
+// their CARGO_FEATURE_* variables.
+fn enabled_features() -> String {
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(name, _)| {
+            name.strip_prefix("CARGO_FEATURE_")
+                .map(|feature| feature.to_lowercase().replace('_', "-"))
+        })
+        .collect();
+    features.sort();
+    features.join(",")
+}
+
+// Returns the first entry of a DEFMT_LOG filter, such as
+// "info,embassy_rp=debug", which enables debug or trace logging.  A bare
+// module path enables all of that module's logging.
+fn verbose_defmt_log(filter: &str) -> Option<&str> {
+    const LEVELS: [&str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+    filter
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .find(|entry| {
+            let level = match entry.rsplit_once('=') {
+                Some((_, level)) => level.trim(),
+                None if LEVELS.contains(entry) => *entry,
+                None => "trace",
+            };
+            matches!(level, "debug" | "trace")
+        })
+}
+
+// Formats seconds since the Unix epoch as a UTC time, such as
+// 2025-01-31 12:34:56Z.  Uses Howard Hinnant's civil_from_days algorithm,
+// to avoid a dependency.
+fn utc_time(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let secs = secs % 86_400;
+
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3_600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+// Shrinks the FLASH region in memory.x by RESULT_LOG_SIZE, and exports the
+// start of the freed space as __result_log_start.  Exposes the flash size
+// the region implies as FLASH_SIZE.
+#[cfg(feature = "result-log")]
+fn reserve_result_log(memory_x: &[u8]) -> Vec<u8> {
+    let memory_x = std::str::from_utf8(memory_x).unwrap();
+    let mut reserved = String::new();
+    let mut found = false;
+    for line in memory_x.lines() {
+        if line.trim_start().starts_with("FLASH :") {
+            println!("cargo:rustc-env=FLASH_SIZE={}", flash_size(line));
+            reserved.push_str(&format!("{} - {}\n", line, RESULT_LOG_SIZE));
+            found = true;
+        } else {
+            reserved.push_str(line);
+            reserved.push('\n');
+        }
+    }
+    assert!(found, "No FLASH region in memory.x");
+
+    reserved.push_str("\n/* Added by build.rs - the result log follows FLASH */\n");
+    reserved.push_str("__result_log_start = ORIGIN(FLASH) + LENGTH(FLASH);\n");
+    reserved.into_bytes()
+}
+
+// Returns the size of the flash chip a memory.x FLASH region line, such as
+// "FLASH : ORIGIN = 0x10000100, LENGTH = 2048K - 0x100", implies, in bytes:
+// the region's end, relative to the start of the XIP address space.
+#[cfg(feature = "result-log")]
+fn flash_size(line: &str) -> u64 {
+    const XIP_BASE: u64 = 0x10000000;
+    let error = || -> ! {
+        panic!(
+            "Can't parse the FLASH region in memory.x, which result-log needs as sums of numbers, such as LENGTH = 2048K - 0x100: {}",
+            line.trim()
+        )
+    };
+    let field = |name: &str| {
+        line.split_once(name)
+            .and_then(|(_, rest)| rest.trim_start().strip_prefix('='))
+            .map(|rest| rest.split(',').next().unwrap())
+            .and_then(sum)
+            .unwrap_or_else(|| error())
+    };
+    (field("ORIGIN") + field("LENGTH"))
+        .checked_sub(XIP_BASE)
+        .unwrap_or_else(|| error())
+}
+
+// Evaluates a linker script sum, such as "2048K - 0x100", of decimal or hex
+// numbers with optional K or M suffixes.
+#[cfg(feature = "result-log")]
+fn sum(expr: &str) -> Option<u64> {
+    let mut total: i64 = 0;
+    let mut sign = 1;
+    for token in expr
+        .replace('+', " + ")
+        .replace('-', " - ")
+        .split_whitespace()
+    {
+        match token {
+            "+" => sign = 1,
+            "-" => sign = -1,
+            _ => {
+                let (number, scale) = match token.as_bytes().last() {
+                    Some(b'K' | b'k') => (&token[..token.len() - 1], 1024),
+                    Some(b'M' | b'm') => (&token[..token.len() - 1], 1024 * 1024),
+                    _ => (token, 1),
+                };
+                let number = match number.strip_prefix("0x").or(number.strip_prefix("0X")) {
+                    Some(hex) => i64::from_str_radix(hex, 16),
+                    None => number.parse(),
+                }
+                .ok()?;
+                total += sign * number * scale;
+            }
+        }
+    }
+    u64::try_from(total).ok()
+}
+
+// Parses the drive pattern file into pattern.rs in the output directory.
+#[cfg(feature = "pattern")]
+fn generate_pattern(out: &std::path::Path) {
+    println!("cargo:rerun-if-env-changed=PATTERN_FILE");
+    let file = env::var("PATTERN_FILE").unwrap_or_else(|_| DEFAULT_PATTERN_FILE.to_string());
+    let path = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap()).join(&file);
+    println!("cargo:rerun-if-changed={}", path.display());
+    let pattern = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Failed to read pattern file {}: {}", path.display(), e));
+
+    let mut steps = String::new();
+    let mut count = 0;
+    for (num, line) in pattern.lines().enumerate() {
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|field| !field.is_empty())
+            .collect();
+        let error = |msg: &str| -> ! { panic!("{}:{}: {}", file, num + 1, msg) };
+        let [level, ns] = fields[..] else {
+            error("Expected a level and a duration");
+        };
+        let high = match level.to_ascii_lowercase().as_str() {
+            "high" | "1" => true,
+            "low" | "0" => false,
+            _ => error("Level must be high, low, 1 or 0"),
+        };
+        let ns: u64 = ns
+            .parse()
+            .unwrap_or_else(|_| error("Duration must be a whole number of ns"));
+        if ns == 0 || ns > MAX_STEP_NS {
+            error(&format!("Duration must be from 1 to {}ns", MAX_STEP_NS));
+        }
+        steps.push_str(&format!(
+            "    PatternStep {{ high: {}, ns: {} }},\n",
+            high, ns
+        ));
+        count += 1;
+    }
+    assert!(count > 0, "{}: Pattern is empty", file);
+
+    File::create(out.join("pattern.rs"))
+        .unwrap()
+        .write_all(
+            format!(
+                "// Generated by build.rs from {}\npub const PATTERN: &[PatternStep] = &[\n{}];\n",
+                file, steps
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+}
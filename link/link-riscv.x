@@ -0,0 +1,55 @@
+/*
+ * Linker script for the riscv-toggle binary, on the RP235X's Hazard3 cores.
+ *
+ * cortex-m-rt's link.x is Arm only, so this lays out the whole image:
+ * _start, then the IMAGE_DEF block, which the boot ROM needs in the first
+ * 4K of flash, then code, read-only data, and data and bss in RAM, for
+ * _start to initialise.  The stack grows down from the top of RAM.
+ */
+
+INCLUDE memory.x
+
+ENTRY(_start)
+
+SECTIONS {
+    .text : ALIGN(4)
+    {
+        KEEP(*(.text.start));
+        . = ALIGN(4);
+        KEEP(*(.start_block));
+        *(.text .text.*);
+    } > FLASH
+
+    .rodata : ALIGN(4)
+    {
+        *(.srodata .srodata.*);
+        *(.rodata .rodata.*);
+        . = ALIGN(4);
+    } > FLASH
+
+    .data : ALIGN(4)
+    {
+        __sdata = .;
+        *(.sdata .sdata.*);
+        *(.data .data.*);
+        . = ALIGN(4);
+        __edata = .;
+    } > RAM AT > FLASH
+    __sidata = LOADADDR(.data);
+
+    .bss (NOLOAD) : ALIGN(4)
+    {
+        __sbss = .;
+        *(.sbss .sbss.*);
+        *(.bss .bss.*);
+        . = ALIGN(4);
+        __ebss = .;
+    } > RAM
+
+    /DISCARD/ :
+    {
+        *(.eh_frame .eh_frame_hdr);
+    }
+}
+
+__stack_top = ORIGIN(RAM) + LENGTH(RAM);
@@ -0,0 +1,13 @@
+/* The RP235X's memory, for the riscv-toggle binary on its Hazard3 cores. */
+
+MEMORY {
+    /*
+     * 2 MiB is a safe default here, although a Pico 2 has 4 MiB - as for
+     * memory.rp235x.x.
+     */
+    FLASH : ORIGIN = 0x10000000, LENGTH = 2048K
+    /*
+     * SRAM0-SRAM7, with a striped mapping.  SRAM8 and SRAM9 are unused.
+     */
+    RAM : ORIGIN = 0x20000000, LENGTH = 512K
+}
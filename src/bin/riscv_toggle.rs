@@ -0,0 +1,132 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Toggles GPIO 2 as fast as possible from the Pico 2's Hazard3 RISC-V cores,
+// with the lib's RV32 asm helpers - the RISC-V counterpart of test 14.
+//
+// embassy-rp, cortex-m-rt and the timing binary are Arm only, so this is
+// bare metal: _start, below, sets up the stack and RAM, and main() brings
+// GPIO 2 out of reset and hands it to SIO with raw register writes.  The
+// boot ROM finds the IMAGE_DEF block marking this as a RISC-V image, and
+// link/link-riscv.x lays it out.
+//
+// clk_sys is left at the boot ROM's clock, and there is no logging, so
+// measure the frequency on GPIO 2 rather than relying on a cycle count.
+//
+// Build with the riscv feature, for riscv32imac-unknown-none-elf - see the
+// README.
+
+#![no_std]
+#![no_main]
+
+use core::arch::global_asm;
+use core::panic::PanicInfo;
+use core::ptr::{read_volatile, write_volatile};
+use core::sync::atomic::{Ordering, compiler_fence};
+use embassy_pico_test::gpio_asm_riscv::{asm_load_gpio_out_addr, set_gpio_high, set_gpio_low};
+use embassy_pico_test::sio::SIO_BASE;
+
+// Output pin, as for the timing binary's single GPIO tests.
+const OUTPUT_PIN: u8 = 2;
+
+// RESETS, with its atomic clear alias, and the blocks to take out of reset.
+const RESETS_BASE: u32 = 0x40020000;
+const RESETS_RESET_CLR: u32 = RESETS_BASE + 0x3000;
+const RESETS_RESET_DONE: u32 = RESETS_BASE + 0x008;
+const RESET_IO_BANK0: u32 = 1 << 6;
+const RESET_PADS_BANK0: u32 = 1 << 9;
+
+// The pin's IO_BANK0 control register, and its SIO function select.
+const IO_BANK0_BASE: u32 = 0x40028000;
+const GPIO_CTRL: u32 = IO_BANK0_BASE + 0x004 + 8 * OUTPUT_PIN as u32;
+const FUNCSEL_SIO: u32 = 5;
+
+// The pin's PADS_BANK0 register, and its value: input enabled, 4mA drive,
+// schmitt trigger and pull down, as at reset, but with isolation cleared.
+const PADS_BANK0_BASE: u32 = 0x40038000;
+const PADS_GPIO: u32 = PADS_BANK0_BASE + 0x004 + 4 * OUTPUT_PIN as u32;
+const PADS_GPIO_OUTPUT: u32 = 0x56;
+
+// SIO output enable atomic set register.
+const GPIO_OE_SET: u32 = SIO_BASE + 0x038;
+
+// The boot ROM's IMAGE_DEF block, marking this as a secure RISC-V RP2350
+// executable, with _start and the stack top as its entry point.  It must be
+// in the first 4K of flash, so link-riscv.x puts it straight after _start.
+// _start zeroes .bss, copies .data from flash, and jumps to main(), with any
+// trap spinning in place.
+global_asm!(
+    ".section .start_block, \"a\"",
+    ".p2align 2",
+    ".word 0xffffded3",  // PICOBIN_BLOCK_MARKER_START
+    ".word 0x11210142",  // IMAGE_TYPE: EXE, secure, RISC-V, RP2350
+    ".word 0x00000344",  // ENTRY_POINT, 3 words
+    ".word _start",      // Entry point
+    ".word __stack_top", // Initial stack pointer
+    ".word 0x000004ff",  // LAST, 4 words of items
+    ".word 0x00000000",  // No next block - this is the only one
+    ".word 0xab123579",  // PICOBIN_BLOCK_MARKER_END
+    "",
+    ".section .text.start, \"ax\"",
+    ".global _start",
+    "_start:",
+    "la t0, 5f",
+    "csrw mtvec, t0", // Direct mode, as 5f is aligned
+    "la sp, __stack_top",
+    "la t0, __sbss",
+    "la t1, __ebss",
+    "1:",
+    "bgeu t0, t1, 2f",
+    "sw zero, 0(t0)",
+    "addi t0, t0, 4",
+    "j 1b",
+    "2:",
+    "la t0, __sidata",
+    "la t1, __sdata",
+    "la t2, __edata",
+    "3:",
+    "bgeu t1, t2, 4f",
+    "lw t3, 0(t0)",
+    "sw t3, 0(t1)",
+    "addi t0, t0, 4",
+    "addi t1, t1, 4",
+    "j 3b",
+    "4:",
+    "j main",
+    ".p2align 2",
+    "5:",
+    "j 5b",
+);
+
+#[unsafe(no_mangle)]
+extern "C" fn main() -> ! {
+    // Safety: These are the registers' documented addresses, and nothing
+    // else is running to contend for them.
+    unsafe {
+        let resets = RESET_IO_BANK0 | RESET_PADS_BANK0;
+        write_volatile(RESETS_RESET_CLR as *mut u32, resets);
+        while read_volatile(RESETS_RESET_DONE as *const u32) & resets != resets {}
+
+        write_volatile(PADS_GPIO as *mut u32, PADS_GPIO_OUTPUT);
+        write_volatile(GPIO_CTRL as *mut u32, FUNCSEL_SIO);
+        write_volatile(GPIO_OE_SET as *mut u32, 1 << OUTPUT_PIN);
+    }
+
+    // Safety: The loop is made up only of the helpers, so nothing changes t0
+    // between them.
+    unsafe {
+        asm_load_gpio_out_addr();
+        loop {
+            set_gpio_high::<OUTPUT_PIN>();
+            set_gpio_low::<OUTPUT_PIN>();
+        }
+    }
+}
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    loop {
+        compiler_fence(Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// ADC sample rate test.  Continuously reads GPIO 26 (ADC0) with embassy's
+// blocking Adc::blocking_read(), toggling GPIO 2 after each sample, so GPIO
+// 2's frequency is half the achievable sample rate.  The rate is also
+// counted over each WINDOW, timed with Instant, and logged, along with how
+// close it is to the ADC's maximum.
+//
+// Each conversion takes 96 clk_adc cycles, so the maximum is clk_adc / 96,
+// 500k samples per second from the usual 48MHz.  As each blocking read
+// starts a conversion and waits for it, the rate achieved is lower, by the
+// read's overhead between conversions.  Instant is only read every
+// CHECK_SAMPLES samples, to keep its own overhead out of the rate.
+//
+// The RP2040 and the Pico 2's RP2350A have the same ADC, on GPIOs 26-29,
+// with the same conversion time.  Their pads differ - the RP2350's power up
+// isolated, which embassy clears when creating the channel - and the
+// RP2350's pull-downs are affected by erratum RP2350-E9, so no pull is
+// used on either.  Leave GPIO 26 unconnected, or connect anything 0-3.3V to
+// it - the value doesn't affect the rate.
+//
+// Interrupts are disabled for each window.  Conversion errors are counted
+// and logged.
+
+use defmt::{info, warn};
+use embassy_rp::adc::{self, Adc, Channel};
+use embassy_rp::gpio::{Level, Output, Pull};
+use embassy_time::{Duration, Instant};
+
+use crate::{GPIO_OUT_XOR, GPIO2_MASK, Test};
+
+// Time to count samples over, for each rate logged.
+const WINDOW: Duration = Duration::from_secs(1);
+
+// Samples between each check of the time.
+const CHECK_SAMPLES: u32 = 100;
+
+// clk_adc cycles per conversion.
+const CONVERSION_CYCLES: u32 = 96;
+
+impl Test {
+    pub async fn adc() -> ! {
+        let p = Self::init();
+
+        let max_rate = embassy_rp::clocks::clk_adc_freq() / CONVERSION_CYCLES;
+        info!("ADC sample rate test");
+        info!(": Sampling GPIO 26 (ADC0), toggling GPIO 2 each sample");
+        info!(
+            ": clk_adc: {} Hz, so at most {} samples/s",
+            embassy_rp::clocks::clk_adc_freq(),
+            max_rate
+        );
+        info!(": Window: {}ms", WINDOW.as_millis());
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+        let mut adc = Adc::new_blocking(p.ADC, adc::Config::default());
+        let mut channel = Channel::new_pin(p.PIN_26, Pull::None);
+
+        info!(": Starting");
+        loop {
+            let mut samples: u32 = 0;
+            let mut errors: u32 = 0;
+            let mut last = 0;
+            let elapsed = cortex_m::interrupt::free(|_| {
+                let start = Instant::now();
+                loop {
+                    for _ in 0..CHECK_SAMPLES {
+                        match adc.blocking_read(&mut channel) {
+                            Ok(value) => last = value,
+                            Err(_) => errors += 1,
+                        }
+                        Self::sio_write(GPIO_OUT_XOR, GPIO2_MASK);
+                    }
+                    samples += CHECK_SAMPLES;
+                    let elapsed = start.elapsed();
+                    if elapsed >= WINDOW {
+                        break elapsed;
+                    }
+                }
+            });
+
+            let rate = (samples as u64 * 1_000_000 / elapsed.as_micros()) as u32;
+            info!(
+                ": {} samples/s ({}% of maximum), GPIO 2 at {} Hz, last value {}",
+                rate,
+                rate as u64 * 100 / max_rate as u64,
+                rate / 2,
+                last
+            );
+            if errors != 0 {
+                warn!(": {}/{} conversions failed", errors, samples);
+            }
+        }
+    }
+}
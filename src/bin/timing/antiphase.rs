@@ -0,0 +1,58 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Antiphase test.  Toggles GPIO 2 and GPIO 3 as exact complements, for
+// differential signal experiments.  Each half period is a single store to
+// GPIO_OUT, of one of the two masks from sio::antiphase_masks(), so both
+// pins change on the same cycle, which separate SET and CLR stores can't
+// do.  As each store sets both levels, the pins can't end up in phase,
+// unlike toggling both with GPIO_OUT_XOR from the wrong starting levels.
+// Writing GPIO_OUT drives all other SIO outputs low, but this test has no
+// others.
+//
+// Each half period is a store then HALF_PERIOD_CYCLES of delay_cycles(),
+// with interrupts disabled.  The loop around them is compiled, so the
+// period is a few cycles longer than twice HALF_PERIOD_CYCLES, but that
+// doesn't affect the edges being simultaneous.
+
+use defmt::info;
+use embassy_pico_test::gpio_asm::delay_cycles;
+use embassy_pico_test::sio::{self, antiphase_masks};
+use embassy_rp::gpio::{Level, Output};
+
+use crate::Test;
+
+// The GPIO_OUT values for GPIO 2 high and GPIO 3 low, then the reverse.
+const MASKS: [u32; 2] = antiphase_masks(2, 3);
+
+// Cycles of delay after each store.
+const HALF_PERIOD_CYCLES: u32 = 100;
+
+impl Test {
+    pub async fn antiphase() -> ! {
+        let p = Self::init();
+
+        info!("Antiphase test");
+        info!(": Using GPIO 2 and GPIO 3");
+        info!(": Masks: {:#010x} and {:#010x}", MASKS[0], MASKS[1]);
+        info!(
+            ": Half period: {} cycles ({}ns) plus the loop",
+            HALF_PERIOD_CYCLES,
+            Self::cycles_to_ns(HALF_PERIOD_CYCLES)
+        );
+
+        let _gpio2 = Output::new(p.PIN_2, Level::Low);
+        let _gpio3 = Output::new(p.PIN_3, Level::Low);
+
+        info!(": Starting");
+        cortex_m::interrupt::disable();
+        let [first, second] = MASKS;
+        loop {
+            sio::write_gpios(first);
+            delay_cycles::<HALF_PERIOD_CYCLES>();
+            sio::write_gpios(second);
+            delay_cycles::<HALF_PERIOD_CYCLES>();
+        }
+    }
+}
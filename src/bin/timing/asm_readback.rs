@@ -0,0 +1,99 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Asm readback self-test.  Toggles GPIO 2 TOGGLES times with the raw asm
+// helpers, asm_load_gpio_out_addr(), set_gpio_high() and set_gpio_low(),
+// reading GPIO_IN back with asm_read_gpio_in() after each store, to check
+// the pin actually reached the level written.  The result is logged once,
+// as PASS or FAIL, and the test then idles.  Unlike the toggle-verify test,
+// which uses SET/CLR stores from Rust, this checks the helpers the asm
+// timing loops are built from.
+//
+// As in the toggle-verify test, each read is made SETTLE_CYCLES after the
+// store, here padded with delay_cycles(), to get past GPIO_IN's 2 cycle
+// synchroniser and the pad's slew.  Each mismatch is logged as a warning,
+// up to MAX_WARNINGS of them.
+//
+// GPIO_OUT's address is reloaded into r0 before every store, as the
+// compiler's handling of each value read back may change r0.  Interrupts
+// are disabled for each pair of stores.
+
+use defmt::{error, info, warn};
+use embassy_pico_test::gpio_asm::{
+    asm_load_gpio_out_addr, asm_read_gpio_in, delay_cycles, set_gpio_high, set_gpio_low,
+};
+use embassy_rp::gpio::{Level, Output};
+
+use crate::{GPIO2_MASK, Test};
+
+// Toggles to verify.
+const TOGGLES: u32 = 500;
+
+// Cycles between each store and its read back.
+const SETTLE_CYCLES: u32 = 16;
+
+// Most mismatches to log individually.
+const MAX_WARNINGS: u32 = 8;
+
+impl Test {
+    pub async fn asm_readback() -> ! {
+        let p = Self::init();
+
+        info!("Asm readback self-test");
+        info!(": Using GPIO 2");
+        info!(
+            ": {} toggles, reading back {} cycles after each store",
+            TOGGLES, SETTLE_CYCLES
+        );
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+
+        info!(": Starting");
+        let mut failures = 0;
+        for toggle in 0..TOGGLES {
+            let (high, low) = cortex_m::interrupt::free(|_| Self::readback_toggle());
+            for (level, expected, name) in [(high, GPIO2_MASK, "high"), (low, 0, "low")] {
+                if level & GPIO2_MASK != expected {
+                    if failures < MAX_WARNINGS {
+                        warn!(
+                            ": Toggle {}: set {}, GPIO_IN read {:#010x}",
+                            toggle, name, level
+                        );
+                    }
+                    failures += 1;
+                }
+            }
+        }
+
+        if failures == 0 {
+            info!(": PASS - {} toggles verified", TOGGLES);
+        } else {
+            error!(
+                ": FAIL - {}/{} reads back mismatched",
+                failures,
+                2 * TOGGLES
+            );
+        }
+        Self::idle()
+    }
+
+    // Sets GPIO 2 high and then low, returning GPIO_IN read back after each.
+    fn readback_toggle() -> (u32, u32) {
+        // Safety: r0 is loaded immediately before each store, with only
+        // asm between them.
+        unsafe {
+            asm_load_gpio_out_addr();
+            set_gpio_high::<2>();
+            delay_cycles::<SETTLE_CYCLES>();
+            let high = asm_read_gpio_in();
+
+            asm_load_gpio_out_addr();
+            set_gpio_low::<2>();
+            delay_cycles::<SETTLE_CYCLES>();
+            let low = asm_read_gpio_in();
+
+            (high, low)
+        }
+    }
+}
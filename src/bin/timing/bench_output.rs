@@ -0,0 +1,72 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Output::new() benchmark.  Times how long embassy's Output::new(PIN_2,
+// Level::Low) takes to configure the pin, which is part of the window from
+// reset to a test's first edge (see the reset-state test).
+//
+// Output::new() consumes the pin it is given, so can't normally be called
+// twice on PIN_2.  Instead, it's given &mut PIN_2, which embassy accepts as
+// a reborrow of the pin for the Output's lifetime.  Each Output is dropped
+// before the next is created, which returns the pin to its unconfigured
+// state, so every iteration runs the full configuration path on the same
+// pin, as the first Output::new() after reset does.  The drop is timed
+// separately, and isn't included.
+//
+// Each iteration is timed with SysTick, with interrupts disabled.  SysTick's
+// read overhead is measured with an empty iteration and subtracted.
+
+use defmt::info;
+use embassy_rp::gpio::{Level, Output};
+
+use crate::Test;
+use crate::measurement::{Measurement, MeasurementResult};
+
+// Number of Output::new() calls to time.
+pub const ITERATIONS: u32 = 1_000;
+
+impl Test {
+    pub async fn bench_output_new(iterations: u32) -> MeasurementResult {
+        let p = Self::init();
+
+        info!("Output::new() benchmark");
+        info!(": Using GPIO 2");
+        info!(": Iterations: {}", iterations);
+
+        let mut pin = p.PIN_2;
+
+        Self::systick_start();
+
+        let overhead = cortex_m::interrupt::free(|_| {
+            let start = Self::systick_now();
+            Self::systick_elapsed(start, Self::systick_now())
+        });
+
+        let mut creates = Measurement::new();
+        let mut drops = Measurement::new();
+        for _ in 0..iterations {
+            let (new_cycles, drop_cycles) = cortex_m::interrupt::free(|_| {
+                let start = Self::systick_now();
+                let output = Output::new(&mut pin, Level::Low);
+                let created = Self::systick_now();
+                core::mem::drop(output);
+                let dropped = Self::systick_now();
+                (
+                    Self::systick_elapsed(start, created),
+                    Self::systick_elapsed(created, dropped),
+                )
+            });
+            creates.add(new_cycles.saturating_sub(overhead));
+            drops.add(drop_cycles.saturating_sub(overhead));
+        }
+
+        let result = creates.result(Self::cycles_to_ns);
+        info!(": SysTick overhead: {} cycles", overhead);
+        info!(": Output::new(): {}", result);
+        info!(": Output drop: {}", drops.result(Self::cycles_to_ns));
+        info!(": Done");
+
+        result
+    }
+}
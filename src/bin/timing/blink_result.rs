@@ -0,0 +1,95 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Blinks a value out on the onboard LED, one decimal digit at a time, so a
+// result can be read by eye on a board with no debug probe or serial
+// connection.
+
+use defmt::{info, warn};
+use embassy_rp::gpio::{AnyPin, Level, Output};
+use embassy_time::{Duration, Timer};
+
+use crate::{BOARD, Test};
+
+// The value blinked by the blink-result test type.
+pub const VALUE: u32 = 2140;
+
+// The onboard LED is on GPIO 25 on both the Pico and Pico 2.  On the Pico W
+// and Pico 2 W, it's on the CYW43 wireless chip instead, and GPIO 25 is the
+// CYW43's SPI chip select, so isn't driven.
+const LED_PIN: u8 = 25;
+const LED_ON_CYW43: bool = cfg!(any(feature = "pico-w", feature = "pico2-w"));
+
+// Timings, chosen to be slow enough to count by eye.  Each digit is a number
+// of short blinks (1-9), except 0, which is a single long blink.
+const BLINK_ON: Duration = Duration::from_millis(250);
+const BLINK_OFF: Duration = Duration::from_millis(350);
+const ZERO_ON: Duration = Duration::from_millis(1200);
+const DIGIT_GAP: Duration = Duration::from_millis(1500);
+const REPEAT_GAP: Duration = Duration::from_secs(5);
+
+impl Test {
+    pub async fn blink_result_test(value: u32) -> ! {
+        let _p = Self::init();
+
+        info!("Blink result test");
+        info!(": Repeating every {}s", REPEAT_GAP.as_secs());
+        loop {
+            Self::blink_result(value).await;
+            Timer::after(REPEAT_GAP).await;
+        }
+    }
+
+    // Blinks value on the LED, most significant digit first, with a gap
+    // between digits.  Must be called after embassy_rp::init(), and, as it
+    // takes GPIO 25 for the duration of the call, not while anything else is
+    // using that pin.
+    pub async fn blink_result(value: u32) {
+        if LED_ON_CYW43 {
+            warn!(
+                ": Value {} - the {} LED is on the CYW43, so can't be blinked",
+                value, BOARD
+            );
+            return;
+        }
+        info!(": Blinking value {} on GPIO {}", value, LED_PIN);
+
+        // Split value into decimal digits, least significant first.  A u32
+        // has at most 10.
+        let mut digits = [0u8; 10];
+        let mut num_digits = 0;
+        let mut remaining = value;
+        loop {
+            digits[num_digits] = (remaining % 10) as u8;
+            num_digits += 1;
+            remaining /= 10;
+            if remaining == 0 {
+                break;
+            }
+        }
+
+        // Safety: GPIO 25 isn't otherwise used while this function runs, and
+        // the Output is dropped (deconfiguring the pin) before returning.
+        let mut led = Output::new(unsafe { AnyPin::steal(LED_PIN) }, Level::Low);
+
+        for (ii, &digit) in digits[..num_digits].iter().rev().enumerate() {
+            if ii > 0 {
+                Timer::after(DIGIT_GAP).await;
+            }
+            if digit == 0 {
+                led.set_high();
+                Timer::after(ZERO_ON).await;
+                led.set_low();
+                Timer::after(BLINK_OFF).await;
+            } else {
+                for _ in 0..digit {
+                    led.set_high();
+                    Timer::after(BLINK_ON).await;
+                    led.set_low();
+                    Timer::after(BLINK_OFF).await;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Busy task test.  Runs tests 1-3's yielding Timer loops, first as the only
+// task, and then alongside a background task which is always ready - it
+// busy-waits for a load of cycles, then yield_now()s, in a loop - and logs
+// how much each test's period degrades, for each load in LOAD_CYCLES.
+//
+// The single-task tests' Timer wakes are serviced as soon as the timer
+// fires, as the executor has nothing else to poll.  With the busy task, a
+// wake which lands while that task is working isn't polled until it
+// reaches its next yield, so each half period can be delayed by up to the
+// load, plus the executor's poll of the busy task.  The yield-tasks test
+// instead shows the cost of other tasks to a loop which yields itself.
+//
+// A task can't be stopped once spawned, so the baselines are measured
+// first, before it is spawned, and it then reads its load from LOAD before
+// each busy-wait, so the loads can be stepped through.  Each test runs for
+// MEASURE at each load, counting periods.  Reading Instant every period
+// adds a little to each, equally to the baselines.  Test 2's loop then
+// continues with the heaviest load.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_futures::yield_now;
+use embassy_rp::gpio::{Level, Output};
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::Test;
+
+// Cycles the background task busy-waits for before each yield, in turn.
+const LOAD_CYCLES: [u32; 3] = [100, 1_000, 10_000];
+
+// The tests run, and their Timer delays each half period.
+const TESTS: [(u8, u64); 3] = [(1, 100), (2, 10), (3, 1)];
+
+// How long each test runs for at each load.
+const MEASURE: Duration = Duration::from_millis(500);
+
+// The background task's current load, in cycles.
+static LOAD: AtomicU32 = AtomicU32::new(0);
+
+#[embassy_executor::task]
+async fn busy_loop() {
+    loop {
+        let cycles = LOAD.load(Ordering::Relaxed);
+        if cycles > 0 {
+            cortex_m::asm::delay(cycles);
+        }
+        yield_now().await;
+    }
+}
+
+impl Test {
+    pub async fn busy_task() -> ! {
+        let p = Self::init();
+
+        info!("Busy task test");
+        info!(": Using GPIO 2");
+        info!(": Tests 1-3's yielding Timer loops, alone and with a busy task");
+        info!(
+            ": Busy task loads: {} cycles per yield, {}ms per test and load",
+            LOAD_CYCLES,
+            MEASURE.as_millis()
+        );
+
+        let spawner = Spawner::for_current_executor().await;
+        let mut output = Output::new(p.PIN_2, Level::Low);
+
+        info!(": Starting");
+        let mut baselines_ns = [0; TESTS.len()];
+        for (baseline_ns, &(test, delay_us)) in baselines_ns.iter_mut().zip(&TESTS) {
+            *baseline_ns = Self::busy_task_period(&mut output, delay_us).await;
+            info!(": No busy task, test {}: period {}ns", test, *baseline_ns);
+        }
+
+        spawner.spawn(busy_loop()).unwrap();
+        for load in LOAD_CYCLES {
+            LOAD.store(load, Ordering::Relaxed);
+            info!(
+                ": Busy task load {} cycles ({}ns):",
+                load,
+                Self::cycles_to_ns(load)
+            );
+            for (&baseline_ns, &(test, delay_us)) in baselines_ns.iter().zip(&TESTS) {
+                let period_ns = Self::busy_task_period(&mut output, delay_us).await;
+                let added_ns = period_ns.saturating_sub(baseline_ns);
+                info!(
+                    ":   Test {}: period {}ns (+{}ns, +{}%)",
+                    test,
+                    period_ns,
+                    added_ns,
+                    added_ns as u64 * 100 / baseline_ns as u64
+                );
+            }
+        }
+
+        let (test, delay_us) = TESTS[1];
+        info!(
+            ": Continuing test {}'s loop with a {} cycle load",
+            test,
+            LOAD_CYCLES[LOAD_CYCLES.len() - 1]
+        );
+        loop {
+            Self::busy_task_toggle(&mut output, delay_us).await;
+        }
+    }
+
+    // Runs the yielding Timer loop for MEASURE, returning its mean period
+    // in ns.
+    async fn busy_task_period(output: &mut Output<'_>, delay_us: u64) -> u32 {
+        let start = Instant::now();
+        let mut periods = 0u64;
+        while Instant::now() - start < MEASURE {
+            Self::busy_task_toggle(output, delay_us).await;
+            periods += 1;
+        }
+        let elapsed = Instant::now() - start;
+        (elapsed.as_micros() * 1000 / periods) as u32
+    }
+
+    // One period of tests 1-3's loop, with a Timer delay each half period.
+    async fn busy_task_toggle(output: &mut Output<'_>, delay_us: u64) {
+        output.set_high();
+        Timer::after_micros(delay_us).await;
+        output.set_low();
+        Timer::after_micros(delay_us).await;
+    }
+}
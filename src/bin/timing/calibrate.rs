@@ -0,0 +1,133 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Self-calibrating toggle test.  Rather than hand-tuning a delay for each
+// board and clock speed, this measures how long the toggle loop actually
+// takes with two different delay loop counts, and from that computes the
+// count needed to hit the target period.
+//
+// The measurement is against embassy-time's Instant, which is driven by the
+// 1MHz TIMER tick rather than clk_sys.  The calibration is therefore in
+// terms of real time, and doesn't assume clk_sys_freq() is exactly right.
+//
+// With the serial-report feature, the calibrated period is also written to
+// UART0 as a line of key=value pairs.
+
+use defmt::{info, warn};
+use embassy_rp::gpio::{Level, Output};
+use embassy_time::{Duration, Instant};
+
+use crate::Test;
+use crate::measurement::{Measurement, MeasurementResult};
+
+// Target period.  Note embassy-time Durations have 1us resolution.
+pub const TARGET: Duration = Duration::from_micros(10);
+
+// Acceptable error from the target period, in parts per million.
+const TOLERANCE_PPM: u64 = 5_000;
+
+// Delay loop counts to calibrate with.
+const CAL_LOW_COUNT: u32 = 1;
+const CAL_HIGH_COUNT: u32 = 101;
+
+// Number of periods to time for each sample.  At 1us Instant resolution,
+// this times the low count sample to better than 0.1% on both the Pico and
+// Pico 2, before it is truncated to whole nanoseconds.
+const CAL_PERIODS: u32 = 10_000;
+
+// Number of samples to take for each measurement.
+const CAL_SAMPLES: u32 = 4;
+
+// Maximum number of single count adjustments to make after computing the
+// count.
+const MAX_ADJUSTMENTS: u32 = 4;
+
+impl Test {
+    pub async fn calibrate_and_toggle(target: Duration) -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        info!("Calibrate and toggle test");
+        info!(": Using GPIO 2");
+        info!(": Reported clock speed: {} Hz", speed);
+        info!(": Target period: {}us", target.as_micros());
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+
+        // Measure the period at two counts.  The period is linear in the
+        // count, so this gives the fixed loop overhead and the cost of each
+        // extra count.
+        let low_ps = Self::measure_toggle_period(CAL_LOW_COUNT).mean_ns as u64 * 1000;
+        let high_ps = Self::measure_toggle_period(CAL_HIGH_COUNT).mean_ns as u64 * 1000;
+        let per_count_ps = (high_ps - low_ps) / (CAL_HIGH_COUNT - CAL_LOW_COUNT) as u64;
+        let overhead_ps = low_ps - per_count_ps * CAL_LOW_COUNT as u64;
+        info!(
+            ": Measured overhead: {}ps, per count: {}ps",
+            overhead_ps, per_count_ps
+        );
+
+        // Compute the count, rounding to the nearest.
+        let target_ps = target.as_micros() * 1_000_000;
+        let mut count = if target_ps <= low_ps {
+            warn!(
+                ": Target {}us is shorter than the minimum achievable {}ps - using minimum",
+                target.as_micros(),
+                low_ps
+            );
+            CAL_LOW_COUNT
+        } else {
+            ((target_ps - overhead_ps + per_count_ps / 2) / per_count_ps) as u32
+        };
+
+        // Verify, and nudge the count if the loop isn't quite linear.
+        let mut error_ppm = 0;
+        let mut result = MeasurementResult::default();
+        for _ in 0..MAX_ADJUSTMENTS {
+            result = Self::measure_toggle_period(count);
+            let period_ps = result.mean_ns as u64 * 1000;
+            error_ppm = period_ps.abs_diff(target_ps) * 1_000_000 / target_ps;
+            if error_ppm <= TOLERANCE_PPM || count == CAL_LOW_COUNT {
+                break;
+            }
+            if period_ps > target_ps {
+                count -= 1;
+            } else {
+                count += 1;
+            }
+        }
+
+        info!(": Calibrated count: {}, error: {}ppm", count, error_ppm);
+        info!(": Calibrated period: {}", result);
+
+        #[cfg(feature = "serial-report")]
+        Self::report_kv_uart("calibrate", &result);
+        if error_ppm > TOLERANCE_PPM {
+            warn!(
+                ": Outside tolerance of {}ppm - each count changes the period by {}ps",
+                TOLERANCE_PPM, per_count_ps
+            );
+        }
+
+        // The same toggle loop is used for calibration and the test itself,
+        // so both have exactly the same overhead.
+        info!(": Starting");
+        loop {
+            Self::toggle_gpio2_periods(count, u32::MAX);
+        }
+    }
+
+    // Measures the toggle loop's period at count.  Each sample is the
+    // average period over CAL_PERIODS periods, so min and max show how much
+    // that varies between samples rather than between individual periods.
+    fn measure_toggle_period(count: u32) -> MeasurementResult {
+        let mut measurement = Measurement::new();
+        for _ in 0..CAL_SAMPLES {
+            let start = Instant::now();
+            Self::toggle_gpio2_periods(count, CAL_PERIODS);
+            let elapsed = start.elapsed();
+            measurement.add((elapsed.as_micros() * 1000 / CAL_PERIODS as u64) as u32);
+        }
+        measurement.result(|ns| ns)
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Padding calibration test.  Tunes the padding of the fast asm toggle loop,
+// GpioToggler::toggle_n_cycles(), at runtime, to hit a target period of
+// tens or hundreds of nanoseconds, such as single-gpio tests 14 and 15's
+// 80ns and 200ns.  Those pad each half period by a hand counted number of
+// cycles per board, which only hits the target at the nominal clock speed,
+// and relies on the Pico 2's cycle counts, which are estimates.
+//
+// A period's padding is every cycle over the loop's minimum, split between
+// the two halves, the high half getting the extra cycle of an odd count.
+// The padding has to be an asm constant, so PADDED_TOGGLES holds a copy of
+// the loop for each padding from 0 to MAX_PAD, and the calibration picks
+// one at runtime.
+//
+// The first guess is from the target and clk_sys_freq().  Each guess is
+// then timed against embassy-time's Instant, which runs from the 1MHz
+// TIMER tick rather than clk_sys, so doesn't assume clk_sys_freq() or the
+// cycle counts are right.  The padding is stepped a cycle at a time
+// towards the target, until the period is within TOLERANCE_PPM, or it
+// steps past the target, when whichever side is closer is used.  The
+// padding found is logged, then toggled with steadily.
+//
+// Interrupts are disabled while timing and toggling.
+
+use defmt::{info, warn};
+use embassy_pico_test::gpio_asm::{GpioToggler, cycles_for_period};
+use embassy_rp::gpio::{Level, Output};
+use embassy_time::Instant;
+
+use crate::{GPIO2_MASK, Test};
+
+// Target period.
+pub const TARGET_NS: u32 = 200;
+
+// Acceptable error from the target period, in parts per million.  A cycle
+// is 4% of 200ns on the Pico, so only targets that are close to a whole
+// number of cycles can be hit more closely than this.
+const TOLERANCE_PPM: u64 = 10_000;
+
+// Periods to time for each guess.  At 1us Instant resolution, this times
+// an 80ns period to about 0.01%.
+const CAL_PERIODS: u32 = 100_000;
+
+// Maximum number of single cycle steps from the first guess.
+const MAX_STEPS: u32 = 16;
+
+// The loop's shortest period, with no padding.
+const MIN_CYCLES: u32 = GpioToggler::MIN_HIGH_CYCLES + GpioToggler::MIN_LOW_CYCLES;
+
+// A copy of the toggle loop for each padding.
+type PaddedToggle = fn(&GpioToggler, u32);
+
+macro_rules! padded_toggles {
+    ($($pad:literal)*) => {
+        [$(|toggler: &GpioToggler, periods: u32| {
+            toggler.toggle_n_cycles::<
+                { GpioToggler::MIN_HIGH_CYCLES + $pad - $pad / 2 },
+                { GpioToggler::MIN_LOW_CYCLES + $pad / 2 },
+            >(periods)
+        }),*]
+    };
+}
+
+const PADDED_TOGGLES: [PaddedToggle; 64] = padded_toggles!(
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27
+    28 29 30 31 32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52
+    53 54 55 56 57 58 59 60 61 62 63
+);
+const MAX_PAD: u32 = PADDED_TOGGLES.len() as u32 - 1;
+
+impl Test {
+    pub async fn calibrate_pad(target_ns: u32) -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        info!("Padding calibration test");
+        info!(": Using GPIO 2");
+        info!(": Reported clock speed: {} Hz", speed);
+        info!(": Target period: {}ns", target_ns);
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+        let toggler = GpioToggler::new(GPIO2_MASK);
+
+        let guess = cycles_for_period(target_ns, speed).saturating_sub(MIN_CYCLES);
+        let mut pad = guess.min(MAX_PAD);
+        if pad != guess {
+            warn!(": Target needs over {} cycles of padding", MAX_PAD);
+        }
+        info!(": First guess: {} cycles of padding", pad);
+
+        // Step towards the target, remembering the closest so far.
+        let target_ps = target_ns as u64 * 1000;
+        let mut period_ps = Self::time_padded_toggle(&toggler, pad);
+        let mut best = (pad, period_ps);
+        for _ in 0..MAX_STEPS {
+            let error_ppm = period_ps.abs_diff(target_ps) * 1_000_000 / target_ps;
+            if error_ppm <= TOLERANCE_PPM {
+                break;
+            }
+            let next = if period_ps > target_ps {
+                pad.checked_sub(1)
+            } else {
+                Some(pad + 1).filter(|&next| next <= MAX_PAD)
+            };
+            let Some(next) = next else {
+                warn!(": Padding limit reached");
+                break;
+            };
+
+            let next_ps = Self::time_padded_toggle(&toggler, next);
+            if next_ps.abs_diff(target_ps) < best.1.abs_diff(target_ps) {
+                best = (next, next_ps);
+            }
+            // Stop once the target has been stepped past.
+            if (next_ps > target_ps) != (period_ps > target_ps) {
+                break;
+            }
+            (pad, period_ps) = (next, next_ps);
+        }
+
+        let (pad, period_ps) = best;
+        let error_ppm = period_ps.abs_diff(target_ps) * 1_000_000 / target_ps;
+        info!(
+            ": Calibrated padding: {} cycles, period {} cycles",
+            pad,
+            MIN_CYCLES + pad
+        );
+        info!(
+            ": Calibrated period: {}.{:03}ns, error: {}ppm",
+            period_ps / 1000,
+            period_ps % 1000,
+            error_ppm
+        );
+        if error_ppm > TOLERANCE_PPM {
+            warn!(": Outside tolerance of {}ppm", TOLERANCE_PPM);
+        }
+
+        info!(": Starting");
+        cortex_m::interrupt::disable();
+        let toggle = PADDED_TOGGLES[pad as usize];
+        loop {
+            toggle(&toggler, u32::MAX);
+        }
+    }
+
+    // Returns the period, in ps, of the toggle loop with pad cycles of
+    // padding, averaged over CAL_PERIODS periods.
+    fn time_padded_toggle(toggler: &GpioToggler, pad: u32) -> u64 {
+        let toggle = PADDED_TOGGLES[pad as usize];
+        let elapsed = cortex_m::interrupt::free(|_| {
+            let start = Instant::now();
+            toggle(toggler, CAL_PERIODS);
+            start.elapsed()
+        });
+        elapsed.as_micros() * 1_000_000 / CAL_PERIODS as u64
+    }
+}
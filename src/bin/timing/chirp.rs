@@ -0,0 +1,85 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Chirp test.  Toggles GPIO 2 with a frequency which ramps linearly from
+// START_HZ to END_HZ over RAMP_MS, then holds GPIO 2 low for GAP_MS and
+// repeats, for characterising a receiver's frequency response.
+//
+// The ramp is STEPS steps, each held for RAMP_MS / STEPS, at a frequency
+// stepped linearly from START_HZ to END_HZ.  At the start of each step, the
+// half period is converted to cycles at the current clk_sys with
+// cycles_for_period(), and the step's periods are then toggled with
+// delay_loop() timing each half, with interrupts disabled for the whole
+// ramp.  The loop around the delays, and the stores, are estimated at
+// LOOP_CYCLES per half period and subtracted, so the highest frequencies are
+// only approximate, and the Pico 2's delay loop is an estimate anyway -
+// check them with a scope or frequency counter.  Each step's arithmetic adds
+// a short gap between steps, so each ramp is a little longer than RAMP_MS,
+// and its measured duration is logged.
+
+use defmt::info;
+use embassy_pico_test::gpio_asm::{cycles_for_period, delay_loop};
+use embassy_pico_test::sio;
+use embassy_rp::gpio::{Level, Output};
+use embassy_time::{Instant, Timer};
+
+use crate::{GPIO2_MASK, Test};
+
+// Frequencies at the start and end of the ramp, in Hz, and its length.
+const START_HZ: u32 = 1_000;
+const END_HZ: u32 = 1_000_000;
+const RAMP_MS: u32 = 3_000;
+
+// Steps in the ramp, and the gap between ramps.
+const STEPS: u32 = 1_000;
+const GAP_MS: u64 = 10;
+
+// Estimated cycles of each half period other than the delay - the store
+// and the loop around it.
+const LOOP_CYCLES: u32 = 4;
+
+const _: () = assert!(START_HZ > 0 && END_HZ > 0 && STEPS > 1);
+const _: () = assert!(END_HZ <= 1_000_000, "END_HZ is too high for the loop");
+
+impl Test {
+    pub async fn chirp() -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        info!("Chirp test");
+        info!(": Using GPIO 2");
+        info!(
+            ": Ramp: {} Hz to {} Hz, linear, over {}ms in {} steps",
+            START_HZ, END_HZ, RAMP_MS, STEPS
+        );
+        info!(": Gap: {}ms low between ramps", GAP_MS);
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+        let step_us = RAMP_MS as u64 * 1000 / STEPS as u64;
+
+        info!(": Starting");
+        loop {
+            let start = Instant::now();
+            cortex_m::interrupt::free(|_| {
+                for step in 0..STEPS {
+                    // Linear in frequency, so works up or down.
+                    let hz = (START_HZ as i64
+                        + (END_HZ as i64 - START_HZ as i64) * step as i64 / (STEPS - 1) as i64)
+                        as u32;
+                    let half_cycles = cycles_for_period(500_000_000 / hz, speed);
+                    let delay = half_cycles.saturating_sub(LOOP_CYCLES);
+                    let periods = (hz as u64 * step_us / 1_000_000).max(1);
+                    for _ in 0..periods {
+                        sio::set_gpios(GPIO2_MASK);
+                        delay_loop(delay);
+                        sio::clear_gpios(GPIO2_MASK);
+                        delay_loop(delay);
+                    }
+                }
+            });
+            info!(": Ramp took {}ms", (Instant::now() - start).as_millis());
+            Timer::after_millis(GAP_MS).await;
+        }
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Pass/fail checking of a single GPIO test's measured period, enabled by the
+// ci-assert feature, which also enables measure, for automated hardware
+// regression tests without a scope.
+//
+// After SKIP_REPORTS of measure's reports, which include starting the test,
+// the next REPORTS are averaged and compared with the test's expected
+// period, from TestNum::info(), within its tolerance, from the same table.
+// A single line is then logged, and the test halts, with GPIO 2 low:
+//
+// ci: PASS test=2 period_ns=20187 expected_ns=20000 error_ppm=9350 tolerance_pct=10
+// ci: FAIL test=3 period_ns=2914 expected_ns=2000 error_ppm=457000 tolerance_pct=25
+//
+// A host script can key off "ci: PASS" and "ci: FAIL".  Tests without an
+// expected period, or which aren't measured, such as the asm tests, can't be
+// checked, so log a FAIL line with a reason at the start instead, so they
+// can't be mistaken for a pass:
+//
+// ci: FAIL test=14 reason=unchecked
+
+use core::sync::atomic::{AtomicU32, Ordering};
+use defmt::{error, info};
+
+use crate::{Test, TestInfo};
+
+#[cfg(feature = "test-sweep")]
+compile_error!("ci-assert checks a single test, so can't be used with test-sweep");
+
+// Reports to skip, and then to average.
+const SKIP_REPORTS: u32 = 1;
+const REPORTS: u32 = 5;
+
+// The test being checked, its expected period and its tolerance, from
+// start().
+static TEST: AtomicU32 = AtomicU32::new(0);
+static EXPECTED_NS: AtomicU32 = AtomicU32::new(0);
+static TOLERANCE_PCT: AtomicU32 = AtomicU32::new(0);
+
+// Records the test's expected period and tolerance, before it starts.
+// Halts with a FAIL if the test can't be checked.
+pub fn start(test: u8, info: TestInfo) {
+    let (Some(expected_ns), Some(tolerance_pct)) = (info.period_ns, info.tolerance_pct) else {
+        error!("ci: FAIL test={} reason=unchecked", test);
+        Test::idle()
+    };
+    TEST.store(test as u32, Ordering::Relaxed);
+    EXPECTED_NS.store(expected_ns, Ordering::Relaxed);
+    TOLERANCE_PCT.store(tolerance_pct, Ordering::Relaxed);
+    info!(
+        ": ci-assert: mean of {} reports, after {}, within {}%",
+        REPORTS, SKIP_REPORTS, tolerance_pct
+    );
+}
+
+pub struct CiAssert {
+    reports: u32,
+    total_ns: u64,
+}
+
+impl CiAssert {
+    pub fn new() -> Self {
+        Self {
+            reports: 0,
+            total_ns: 0,
+        }
+    }
+
+    // Counts one of measure's reports, and once REPORTS have been counted,
+    // logs the result and halts.
+    pub fn report(&mut self, period_ns: u64) {
+        self.reports += 1;
+        if self.reports <= SKIP_REPORTS {
+            return;
+        }
+        self.total_ns += period_ns;
+        if self.reports < SKIP_REPORTS + REPORTS {
+            return;
+        }
+
+        let test = TEST.load(Ordering::Relaxed);
+        let expected_ns = EXPECTED_NS.load(Ordering::Relaxed) as u64;
+        let tolerance_pct = TOLERANCE_PCT.load(Ordering::Relaxed);
+        let period_ns = self.total_ns / REPORTS as u64;
+        let error_ppm = period_ns.abs_diff(expected_ns) * 1_000_000 / expected_ns;
+        if error_ppm <= tolerance_pct as u64 * 10_000 {
+            info!(
+                "ci: PASS test={} period_ns={} expected_ns={} error_ppm={} tolerance_pct={}",
+                test, period_ns, expected_ns, error_ppm, tolerance_pct
+            );
+        } else {
+            error!(
+                "ci: FAIL test={} period_ns={} expected_ns={} error_ppm={} tolerance_pct={}",
+                test, period_ns, expected_ns, error_ppm, tolerance_pct
+            );
+        }
+        Test::idle()
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Clock source selection, and the clock source test.
+//
+// By default clk_sys is the system PLL's output, locked to the 12MHz
+// crystal.  The clk-xosc feature runs clk_sys directly from the crystal
+// oscillator (XOSC) instead, at 12MHz, and clk-rosc from the ring
+// oscillator (ROSC), which isn't locked to anything, so drifts with
+// voltage and temperature, and varies from chip to chip.  Either can be
+// added to any test, as with the sysclk features, and they are configured in
+// Test::init(), through embassy_rp::init()'s clock config.
+//
+// Only clk_sys moves.  clk_ref stays on the crystal, so embassy time, which
+// ticks from clk_ref, and so Timer, Delay and Instant, are still accurate
+// with clk-rosc.  That's what makes it safe, and what lets the clock source
+// test measure the ROSC's frequency.  With clk-rosc, clk_peri moves to the
+// crystal, as embassy computes UART baud rate divisors from the frequency
+// it's told clk_peri is.
+//
+// embassy can't measure the ROSC, so clk_sys_freq() reports ROSC_HZ, which
+// is only a nominal figure.  The asm tests' expected periods follow
+// clk_sys_freq(), so are only approximate with clk-rosc.
+//
+// The clock source test, the clock-source feature, toggles GPIO 2 with
+// GpioToggler::toggle_n_cycles() in bursts, each timed by Instant with
+// interrupts disabled.  After every SAMPLES bursts it logs the mean, minimum
+// and maximum periods, their spread in ppm, and the clk_sys frequency
+// they imply, next to the one clk_sys_freq() reports.  Build it with each of
+// no clock source feature, clk-xosc and clk-rosc in turn to compare them -
+// the PLL and XOSC should agree with clk_sys_freq() to within Instant's
+// resolution, and the ROSC shouldn't.
+
+use defmt::info;
+#[cfg(feature = "clock-source")]
+use embassy_pico_test::gpio_asm::GpioToggler;
+#[cfg(any(feature = "clk-xosc", feature = "clk-rosc"))]
+use embassy_rp::clocks::{ClockConfig, SysClkSrc};
+#[cfg(feature = "clk-rosc")]
+use embassy_rp::clocks::{PeriClkSrc, RoscConfig, RoscRange};
+#[cfg(any(feature = "clk-xosc", feature = "clk-rosc"))]
+use embassy_rp::config::Config;
+#[cfg(feature = "clock-source")]
+use embassy_rp::gpio::{Level, Output};
+#[cfg(feature = "clock-source")]
+use embassy_time::Instant;
+
+#[cfg(feature = "clock-source")]
+use crate::GPIO2_MASK;
+use crate::Test;
+
+#[cfg(all(feature = "clk-xosc", feature = "clk-rosc"))]
+compile_error!("Enable only one of the clk-xosc and clk-rosc features");
+#[cfg(all(
+    any(feature = "clk-xosc", feature = "clk-rosc"),
+    any(feature = "sysclk-200", feature = "sysclk-250")
+))]
+compile_error!("The clk-xosc and clk-rosc features can't be used with the sysclk features");
+
+#[cfg(any(feature = "clk-xosc", feature = "clk-rosc"))]
+const CRYSTAL_HZ: u32 = 12_000_000;
+
+// The ROSC's nominal frequency, in its high range, divided by 2.  Half the
+// 140MHz embassy's ClockConfig::rosc() assumes for it undivided, which
+// keeps it well inside both chips' rated clk_sys even if it runs fast.
+#[cfg(feature = "clk-rosc")]
+const ROSC_HZ: u32 = 70_000_000;
+#[cfg(feature = "clk-rosc")]
+const ROSC_DIV: u16 = 2;
+
+// Describes clk_sys's source, for logging.
+#[cfg(not(any(feature = "clk-xosc", feature = "clk-rosc")))]
+pub const SOURCE: &str = "PLL, from the 12MHz crystal";
+#[cfg(feature = "clk-xosc")]
+pub const SOURCE: &str = "XOSC, the 12MHz crystal";
+#[cfg(feature = "clk-rosc")]
+pub const SOURCE: &str = "ROSC";
+
+// Cycles GPIO 2 is high and then low for, with the clock source test.
+#[cfg(feature = "clock-source")]
+const HIGH_CYCLES: u32 = 10;
+#[cfg(feature = "clock-source")]
+const LOW_CYCLES: u32 = 10;
+#[cfg(feature = "clock-source")]
+const PERIOD_CYCLES: u32 = HIGH_CYCLES + LOW_CYCLES;
+
+// Nominal length of each burst, and the bursts per report.  Instant's 1us
+// resolution is 50ppm of a 20ms burst.
+#[cfg(feature = "clock-source")]
+const BURST_MS: u32 = 20;
+#[cfg(feature = "clock-source")]
+const SAMPLES: u32 = 50;
+
+#[cfg(any(feature = "clk-xosc", feature = "clk-rosc"))]
+impl Test {
+    // Initialises embassy with clk_sys from the selected clock source.
+    pub fn init_clock_source() -> embassy_rp::Peripherals {
+        let mut clocks = ClockConfig::crystal(CRYSTAL_HZ);
+        // The system PLL is unused.
+        if let Some(xosc) = clocks.xosc.as_mut() {
+            xosc.sys_pll = None;
+        }
+
+        #[cfg(feature = "clk-xosc")]
+        {
+            clocks.sys_clk.src = SysClkSrc::Xosc;
+        }
+        #[cfg(feature = "clk-rosc")]
+        {
+            clocks.rosc = Some(RoscConfig {
+                hz: ROSC_HZ,
+                range: RoscRange::High,
+                drive_strength: [0; 8],
+                div: ROSC_DIV,
+            });
+            clocks.sys_clk.src = SysClkSrc::Rosc;
+            clocks.peri_clk_src = Some(PeriClkSrc::Xosc);
+        }
+        let p = embassy_rp::init(Config::new(clocks));
+
+        info!(
+            "clk_sys from {}: {} Hz",
+            SOURCE,
+            embassy_rp::clocks::clk_sys_freq()
+        );
+        #[cfg(feature = "clk-rosc")]
+        defmt::warn!(": The ROSC's frequency is nominal - embassy can't measure it");
+        p
+    }
+}
+
+#[cfg(feature = "clock-source")]
+impl Test {
+    pub async fn clock_source() -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        info!("Clock source test");
+        info!(": Using GPIO 2");
+        info!(": clk_sys source: {}", SOURCE);
+        info!(": clk_sys_freq(): {} Hz", speed);
+        info!(
+            ": clk_ref_freq(): {} Hz, used by Instant",
+            embassy_rp::clocks::clk_ref_freq()
+        );
+
+        // Sized by the reported frequency, so only nominally BURST_MS.
+        let periods = speed / PERIOD_CYCLES / 1000 * BURST_MS;
+        info!(
+            ": Period: {} cycles ({}ns nominal), {} periods per burst, {} bursts per report",
+            PERIOD_CYCLES,
+            Self::cycles_to_ns(PERIOD_CYCLES),
+            periods,
+            SAMPLES
+        );
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+        let toggler = GpioToggler::new(GPIO2_MASK);
+
+        info!(": Starting");
+        loop {
+            // Periods in ps, for resolution at the PLL's speed.
+            let mut min_ps = u32::MAX;
+            let mut max_ps = 0;
+            let mut total_ps: u64 = 0;
+            for _ in 0..SAMPLES {
+                let elapsed = cortex_m::interrupt::free(|_| {
+                    let start = Instant::now();
+                    toggler.toggle_n_cycles::<HIGH_CYCLES, LOW_CYCLES>(periods);
+                    Instant::now() - start
+                });
+                let ps = (elapsed.as_micros() * 1_000_000 / periods as u64) as u32;
+                min_ps = min_ps.min(ps);
+                max_ps = max_ps.max(ps);
+                total_ps += ps as u64;
+            }
+
+            let mean_ps = (total_ps / SAMPLES as u64) as u32;
+            let spread_ppm = (max_ps - min_ps) as u64 * 1_000_000 / mean_ps as u64;
+            let measured_hz = PERIOD_CYCLES as u64 * 1_000_000_000_000 / mean_ps as u64;
+            info!(
+                ": Period: mean {}ps, min {}ps, max {}ps, spread {}ppm",
+                mean_ps, min_ps, max_ps, spread_ppm
+            );
+            info!(
+                ": clk_sys: measured {} Hz, reported {} Hz",
+                measured_hz, speed
+            );
+        }
+    }
+}
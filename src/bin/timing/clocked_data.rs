@@ -0,0 +1,155 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Clocked data test.  Outputs a continuous clock on GPIO 2, with data on
+// GPIO 3 changing on one clock edge (the setup edge), so it is stable when
+// sampled on the other.  A 32 bit pattern is sent MSB first, repeated
+// forever.
+//
+// Both are driven from one asm loop.  The clock is toggled with stores to
+// GPIO_OUT_XOR, and the setup edge is selected by the clock's starting
+// level.  Data is written with a store to GPIO_OUT_SET or GPIO_OUT_CLR
+// immediately after the setup edge's store, selected without branching, so
+// every bit has exactly the same instructions.  The data therefore always
+// changes a single store after the setup edge, and has a constant half
+// period of setup time before the sample edge.
+
+use core::arch::asm;
+use defmt::{error, info, warn};
+use embassy_rp::gpio::{Level, Output};
+
+use crate::{GPIO_OUT_CLR, GPIO_OUT_SET, GPIO_OUT_XOR, IS_PICO2, SIO_BASE, Test};
+
+// Bit rate, data pattern and setup edge.
+pub const BIT_RATE: u32 = 1_000_000;
+pub const DATA: u32 = 0xa5f0_0ff5;
+pub const SETUP_EDGE: ClockEdge = ClockEdge::Falling;
+
+// Clock edges
+#[derive(Clone, Copy, defmt::Format)]
+pub enum ClockEdge {
+    Rising,
+    Falling,
+}
+
+const CLOCK_MASK: u32 = 1 << 2;
+const DATA_MASK: u32 = 1 << 3;
+
+// Register offsets from SIO_BASE, for use as asm immediates.
+const SET_OFFSET: u32 = GPIO_OUT_SET - SIO_BASE;
+const XOR_OFFSET: u32 = GPIO_OUT_XOR - SIO_BASE;
+const CLR_SET_SHIFT: u32 = (GPIO_OUT_CLR - GPIO_OUT_SET).trailing_zeros();
+
+// Cycles per bit on the Pico are 6 per delay loop count, plus this fixed
+// overhead.  The Pico 2's instruction timings differ, so it is only an
+// estimate there.
+const BIT_OVERHEAD_CYCLES: u32 = 16;
+const BIT_CYCLES_PER_COUNT: u32 = 6;
+
+impl Test {
+    pub async fn clocked_data(bit_rate: u32, data: u32, setup_edge: ClockEdge) {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        info!("Clocked data test");
+        info!(": Clock on GPIO 2, data on GPIO 3");
+        info!(": Data: {:#010x}, MSB first, repeated", data);
+        info!(": Data set up on {} clock edge", setup_edge);
+
+        if bit_rate == 0 {
+            error!(": Bit rate must be non-zero");
+            return;
+        }
+
+        let count =
+            ((speed / bit_rate).saturating_sub(BIT_OVERHEAD_CYCLES) / BIT_CYCLES_PER_COUNT).max(1);
+        let achieved = speed / (count * BIT_CYCLES_PER_COUNT + BIT_OVERHEAD_CYCLES);
+        info!(
+            ": Requested bit rate: {} bps, achieved: {} bps",
+            bit_rate, achieved
+        );
+        if IS_PICO2 {
+            warn!(": Achieved bit rate is an estimate on the Pico 2 - measure it");
+        }
+        if achieved > bit_rate {
+            warn!(": Requested bit rate is above the maximum - using maximum");
+        }
+
+        // The first store in the loop toggles the clock for the setup edge,
+        // so start at the opposite level.
+        let clock_level = match setup_edge {
+            ClockEdge::Rising => Level::Low,
+            ClockEdge::Falling => Level::High,
+        };
+        let _clock = Output::new(p.PIN_2, clock_level);
+        let _data = Output::new(p.PIN_3, Level::Low);
+
+        info!(": Starting");
+
+        // Disable interrupts so nothing disturbs the timing.
+        cortex_m::interrupt::disable();
+        Self::asm_clocked_data(data, count);
+    }
+
+    // The loop is split into two half periods, each ending with a clock
+    // edge.  The first does the data store and computes the next bit's data
+    // register, and the second pads with nops and branches back, so the two
+    // halves take the same number of cycles on the Pico.
+    //
+    // The next bit's register is computed by rotating the pattern left
+    // through the carry (lsls then adcs), turning the carry into 0 or -1
+    // (sbcs), and then into SET_OFFSET or the CLR offset.
+    //
+    // r0: SIO_BASE, r1: data pattern, r2: next data register offset,
+    // r3: clock mask, r4: data mask, r5: delay loop counter, r8: count.
+    // Thumb-1 can't take inputs in high registers, so count is passed in r5
+    // and moved to r8.  Registers are free to clobber, as this never
+    // returns.
+    fn asm_clocked_data(data: u32, count: u32) -> ! {
+        unsafe {
+            asm!(
+                "mov r8, r5",
+                "lsls r2, r1, #1",
+                "adcs r1, r1",
+                "sbcs r2, r2",
+                "lsrs r2, r2, #31",
+                "lsls r2, r2, #{shift}",
+                "adds r2, #{set}",
+                "2:",
+                "str r3, [r0, #{xor}]",   // Setup edge
+                "str r4, [r0, r2]",       // Data
+                "lsls r2, r1, #1",
+                "adcs r1, r1",
+                "sbcs r2, r2",
+                "lsrs r2, r2, #31",
+                "lsls r2, r2, #{shift}",
+                "adds r2, #{set}",
+                "mov r5, r8",
+                "3:",
+                "subs r5, #1",
+                "bne 3b",
+                "str r3, [r0, #{xor}]",   // Sample edge
+                "mov r5, r8",
+                "4:",
+                "subs r5, #1",
+                "bne 4b",
+                "nop",
+                "nop",
+                "nop",
+                "nop",
+                "nop",
+                "b 2b",
+                in("r0") SIO_BASE,
+                in("r1") data,
+                in("r3") CLOCK_MASK,
+                in("r4") DATA_MASK,
+                in("r5") count,
+                shift = const CLR_SET_SHIFT,
+                set = const SET_OFFSET,
+                xor = const XOR_OFFSET,
+                options(noreturn, nostack),
+            );
+        }
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Serial console, enabled by the serial-console feature, so the single GPIO
+// tests can be selected and their results read over a plain USB-serial
+// adapter, without a debug probe.  UART0 RX (GPIO 1) receives commands, as
+// with runtime-select, which this enables, and UART0 TX (GPIO 0) sends
+// replies and results, both at CONSOLE_BAUD, 8N1.  The defmt output is
+// unchanged, for when a probe is attached.
+//
+// The protocol is lines of ASCII text.  Commands are ended by CR or LF, and
+// replies by CRLF.  After reset, the firmware sends:
+//
+// READY tests=1-28
+//
+// and waits for a command:
+//
+// RUN 14
+//
+// to which it replies with one of:
+//
+// OK RUN 14
+// ERR invalid test 99
+// ERR line too long
+//
+// After OK, the test runs until reset, and no more commands are read.  Its
+// number and expected period, and then its measured period from the measure
+// feature, which this also enables, about once a second, follow as the same
+// key=value pairs as measure's "timing: " lines:
+//
+// TEST test=14 expected_ns=20000
+// RESULT period_ns=20187 half_period_ns=10093 periods=49000 total=98000
+//
+// total is the periods since the test started.  expected_ns is omitted for
+// tests with no expected period, and only the tests using single_gpio! send
+// RESULT lines - see period_measure.rs.  With halt-after, a final line is
+// sent once the test is done:
+//
+// DONE periods=1000
+//
+// The selector's plain test numbers, such as "14" or "T14", are also
+// accepted in place of RUN, as is an empty line if a test number feature is
+// enabled.  To run another test, reset the board, for example by toggling
+// the RUN pin, and wait for READY.
+
+use core::fmt::{self, Write};
+use defmt::warn;
+
+use crate::report_kv::KvUart;
+use crate::selector::SELECT_BAUD;
+
+pub const CONSOLE_BAUD: u32 = SELECT_BAUD;
+
+// Sends a line to the host over UART0 TX.
+pub fn reply(args: fmt::Arguments) {
+    let mut uart = KvUart::new(CONSOLE_BAUD);
+    if uart
+        .write_fmt(args)
+        .and_then(|_| uart.write_str("\r\n"))
+        .is_err()
+    {
+        warn!(": Failed to write console line to UART");
+    }
+}
@@ -0,0 +1,230 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Dead-time test.  Drives GPIO 2 and GPIO 3 as complementary outputs with a
+// dead-time between them, where both are low, as a half-bridge's gate drive
+// needs, so one side turns off before the other turns on.  The antiphase
+// test's outputs change on the same cycle, so have no such interval.
+//
+// Each period is GPIO 2 high for ON_CYCLES, both low for DEAD_CYCLES, GPIO 3
+// high for ON_CYCLES, and both low for DEAD_CYCLES again.  Each of the four
+// intervals starts with a store to GPIO_OUT_SET or GPIO_OUT_CLR, so other
+// GPIOs are untouched, then the same counted subs/bne delay loop and adds
+// padding as delay_cycles().  The whole period is a single asm loop, so
+// nothing the compiler generates lands between the stores, and each dead
+// time is exact, including the second, which contains the branch back.
+// The Pico 2's counts are estimates, as for delay_cycles().
+//
+// Interrupts are disabled while toggling.
+
+use core::arch::asm;
+use defmt::{info, warn};
+use embassy_pico_test::gpio_asm::{GpioToggler, delay_loop_split};
+use embassy_rp::gpio::{Level, Output};
+
+use crate::{GPIO_OUT_CLR, GPIO_OUT_SET, GPIO2_MASK, IS_PICO2, Test};
+
+const GPIO3_MASK: u32 = 1 << 3;
+
+// Cycles each output is high for, and the dead-time after each turns off.
+const ON_CYCLES: u32 = 100;
+const DEAD_CYCLES: u32 = 10;
+
+const PERIOD_CYCLES: u32 = 2 * (ON_CYCLES + DEAD_CYCLES);
+
+// The delays after each store - the same as toggle_forever()'s, with the
+// branch back in the second dead-time - for use as asm const operands.
+const ON: (u32, u32) = {
+    assert!(
+        ON_CYCLES >= GpioToggler::FOREVER_MIN_HIGH_CYCLES,
+        "ON_CYCLES is too short"
+    );
+    delay_loop_split(ON_CYCLES - GpioToggler::FOREVER_MIN_HIGH_CYCLES)
+};
+const DEAD: (u32, u32) = {
+    assert!(
+        DEAD_CYCLES >= GpioToggler::FOREVER_MIN_HIGH_CYCLES,
+        "DEAD_CYCLES is too short"
+    );
+    delay_loop_split(DEAD_CYCLES - GpioToggler::FOREVER_MIN_HIGH_CYCLES)
+};
+const DEAD_BRANCH: (u32, u32) = {
+    assert!(
+        DEAD_CYCLES >= GpioToggler::FOREVER_MIN_LOW_CYCLES,
+        "DEAD_CYCLES is too short"
+    );
+    delay_loop_split(DEAD_CYCLES - GpioToggler::FOREVER_MIN_LOW_CYCLES)
+};
+
+impl Test {
+    pub async fn dead_time() -> ! {
+        let p = Self::init();
+
+        info!("Dead-time test");
+        info!(": Using GPIO 2 and GPIO 3");
+        info!(
+            ": Period: {} cycles ({}ns)",
+            PERIOD_CYCLES,
+            Self::cycles_to_ns(PERIOD_CYCLES)
+        );
+        info!(
+            ": Each output high for {} cycles ({}ns)",
+            ON_CYCLES,
+            Self::cycles_to_ns(ON_CYCLES)
+        );
+        info!(
+            ": Dead-time: {} cycles ({}ns), both low, after each turns off",
+            DEAD_CYCLES,
+            Self::cycles_to_ns(DEAD_CYCLES)
+        );
+        if IS_PICO2 {
+            warn!(": Cycle counts are estimates on the Pico 2 - measure the dead-time");
+        }
+
+        let _gpio2 = Output::new(p.PIN_2, Level::Low);
+        let _gpio3 = Output::new(p.PIN_3, Level::Low);
+
+        info!(": Starting");
+        cortex_m::interrupt::disable();
+        Self::dead_time_loop()
+    }
+
+    // Drives the outputs forever.  r0: GPIO_OUT_SET, r1: GPIO 2's mask, r3:
+    // GPIO 3's mask, r2: delay count and padding.
+    #[cfg(feature = "pico")]
+    fn dead_time_loop() -> ! {
+        // Safety: Only stores GPIO 2's and GPIO 3's masks to GPIO_OUT_SET
+        // and GPIO_OUT_CLR, using the registers declared.
+        unsafe {
+            asm!(
+                "2:",
+                "str r1, [r0]",          // GPIO 2 high
+                ".if {on_count}",
+                "movs r2, #({on_count} >> 8)",
+                "lsls r2, r2, #8",
+                "adds r2, #({on_count} & 0xff)",
+                "3:",
+                "subs r2, r2, #1",
+                "bne 3b",
+                ".endif",
+                ".rept {on_pad}",
+                "adds r2, r2, #1",
+                ".endr",
+                "str r1, [r0, #{clr}]",  // GPIO 2 low, dead-time
+                ".if {dead_count}",
+                "movs r2, #({dead_count} >> 8)",
+                "lsls r2, r2, #8",
+                "adds r2, #({dead_count} & 0xff)",
+                "4:",
+                "subs r2, r2, #1",
+                "bne 4b",
+                ".endif",
+                ".rept {dead_pad}",
+                "adds r2, r2, #1",
+                ".endr",
+                "str r3, [r0]",          // GPIO 3 high
+                ".if {on_count}",
+                "movs r2, #({on_count} >> 8)",
+                "lsls r2, r2, #8",
+                "adds r2, #({on_count} & 0xff)",
+                "5:",
+                "subs r2, r2, #1",
+                "bne 5b",
+                ".endif",
+                ".rept {on_pad}",
+                "adds r2, r2, #1",
+                ".endr",
+                "str r3, [r0, #{clr}]",  // GPIO 3 low, dead-time
+                ".if {branch_count}",
+                "movs r2, #({branch_count} >> 8)",
+                "lsls r2, r2, #8",
+                "adds r2, #({branch_count} & 0xff)",
+                "6:",
+                "subs r2, r2, #1",
+                "bne 6b",
+                ".endif",
+                ".rept {branch_pad}",
+                "adds r2, r2, #1",
+                ".endr",
+                "b 2b",
+                in("r0") GPIO_OUT_SET,
+                in("r1") GPIO2_MASK,
+                in("r3") GPIO3_MASK,
+                clr = const GPIO_OUT_CLR - GPIO_OUT_SET,
+                on_count = const ON.0,
+                on_pad = const ON.1,
+                dead_count = const DEAD.0,
+                dead_pad = const DEAD.1,
+                branch_count = const DEAD_BRANCH.0,
+                branch_pad = const DEAD_BRANCH.1,
+                options(noreturn, nostack),
+            );
+        }
+    }
+
+    // Drives the outputs forever.  r0: GPIO_OUT_SET, r1: GPIO 2's mask, r3:
+    // GPIO 3's mask, r2: delay count and padding.
+    #[cfg(feature = "pico2")]
+    fn dead_time_loop() -> ! {
+        // Safety: Only stores GPIO 2's and GPIO 3's masks to GPIO_OUT_SET
+        // and GPIO_OUT_CLR, using the registers declared.
+        unsafe {
+            asm!(
+                "2:",
+                "str r1, [r0]",          // GPIO 2 high
+                ".if {on_count}",
+                "movw r2, #{on_count}",
+                "3:",
+                "subs r2, r2, #1",
+                "bne 3b",
+                ".endif",
+                ".rept {on_pad}",
+                "adds r2, r2, #1",
+                ".endr",
+                "str r1, [r0, #{clr}]",  // GPIO 2 low, dead-time
+                ".if {dead_count}",
+                "movw r2, #{dead_count}",
+                "4:",
+                "subs r2, r2, #1",
+                "bne 4b",
+                ".endif",
+                ".rept {dead_pad}",
+                "adds r2, r2, #1",
+                ".endr",
+                "str r3, [r0]",          // GPIO 3 high
+                ".if {on_count}",
+                "movw r2, #{on_count}",
+                "5:",
+                "subs r2, r2, #1",
+                "bne 5b",
+                ".endif",
+                ".rept {on_pad}",
+                "adds r2, r2, #1",
+                ".endr",
+                "str r3, [r0, #{clr}]",  // GPIO 3 low, dead-time
+                ".if {branch_count}",
+                "movw r2, #{branch_count}",
+                "6:",
+                "subs r2, r2, #1",
+                "bne 6b",
+                ".endif",
+                ".rept {branch_pad}",
+                "adds r2, r2, #1",
+                ".endr",
+                "b 2b",
+                in("r0") GPIO_OUT_SET,
+                in("r1") GPIO2_MASK,
+                in("r3") GPIO3_MASK,
+                clr = const GPIO_OUT_CLR - GPIO_OUT_SET,
+                on_count = const ON.0,
+                on_pad = const ON.1,
+                dead_count = const DEAD.0,
+                dead_pad = const DEAD.1,
+                branch_count = const DEAD_BRANCH.0,
+                branch_pad = const DEAD_BRANCH.1,
+                options(noreturn, nostack),
+            );
+        }
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// DMA contention test.  Toggles GPIO 2 with GpioToggler::toggle_n_cycles()
+// in bursts, alternately with a DMA transfer running and without, to show
+// whether DMA traffic on the bus perturbs the CPU's GPIO timing.  On a
+// scope, the bursts with DMA are the first of each pair after a gap.
+//
+// The DMA is embassy's write_repeated(), unpaced, so as fast as it can go,
+// reading a static word and writing it to SINK, a word in SRAM, neither
+// address incrementing, TRANSFERS times.  That keeps the DMA's read and
+// write masters busy with SRAM for longer than a burst, and the transfer is
+// dropped, so aborted, after each.  The asm loop runs from flash, through
+// the XIP cache, and its stores to the SIO don't use the bus fabric at all,
+// so any effect is from contention on instruction fetches, or the SRAM the
+// stack is in.
+//
+// Each burst is timed with SysTick, with interrupts disabled, and the mean
+// period of each kind logged after every pair.
+
+use defmt::info;
+use embassy_pico_test::gpio_asm::GpioToggler;
+use embassy_rp::dma;
+use embassy_rp::gpio::{Level, Output};
+use embassy_rp::pac::dma::vals::TreqSel;
+use embassy_time::Timer;
+
+use crate::{GPIO2_MASK, Test};
+
+// Cycles GPIO 2 is high and then low for.
+const HIGH_CYCLES: u32 = 10;
+const LOW_CYCLES: u32 = 10;
+
+// Periods per burst.  A burst must be shorter than SysTick's 2^24 cycles,
+// and than the DMA transfer.
+const BURST_PERIODS: u32 = 100_000;
+
+// Words the DMA transfers per burst, at up to one per cycle.  Under the
+// RP2350's 2^28 transfer limit.
+const TRANSFERS: usize = 1 << 24;
+const _: () = assert!(
+    BURST_PERIODS * (HIGH_CYCLES + LOW_CYCLES) < TRANSFERS as u32,
+    "A burst must be shorter than the DMA transfer"
+);
+
+// Gap between bursts.
+const GAP_MS: u64 = 1;
+
+// Where the DMA writes.
+static mut SINK: u32 = 0;
+
+impl Test {
+    pub async fn dma_contention() -> ! {
+        let p = Self::init();
+
+        let period = HIGH_CYCLES + LOW_CYCLES;
+        info!("DMA contention test");
+        info!(": Using GPIO 2");
+        info!(
+            ": Period: {} cycles ({}ns), {} periods per burst",
+            period,
+            Self::cycles_to_ns(period),
+            BURST_PERIODS
+        );
+        info!(
+            ": DMA: channel 0, unpaced, 32-bit SRAM to SRAM, no increment, {} transfers",
+            TRANSFERS
+        );
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+        let toggler = GpioToggler::new(GPIO2_MASK);
+        let mut channel = p.DMA_CH0;
+
+        Self::systick_start();
+
+        info!(": Starting");
+        loop {
+            // Safety: SINK is only written by the DMA, and is never read.
+            let transfer = unsafe {
+                dma::write_repeated(&mut channel, &raw mut SINK, TRANSFERS, TreqSel::PERMANENT)
+            };
+            let with_dma = Self::time_burst(&toggler);
+            drop(transfer);
+            Timer::after_millis(GAP_MS).await;
+
+            let without_dma = Self::time_burst(&toggler);
+            Timer::after_millis(GAP_MS).await;
+
+            // Thousandths of a cycle per period.
+            for (name, cycles) in [("With DMA", with_dma), ("Without DMA", without_dma)] {
+                let mc = (cycles as u64 * 1000 / BURST_PERIODS as u64) as u32;
+                info!(
+                    ": {}: {}.{:03} cycles per period ({}ns)",
+                    name,
+                    mc / 1000,
+                    mc % 1000,
+                    Self::cycles_to_ns(mc) / 1000
+                );
+            }
+        }
+    }
+
+    // Returns the cycles taken by a burst of BURST_PERIODS periods.
+    fn time_burst(toggler: &GpioToggler) -> u32 {
+        cortex_m::interrupt::free(|_| {
+            let start = Self::systick_now();
+            toggler.toggle_n_cycles::<HIGH_CYCLES, LOW_CYCLES>(BURST_PERIODS);
+            Self::systick_elapsed(start, Self::systick_now())
+        })
+    }
+}
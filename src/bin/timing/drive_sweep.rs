@@ -0,0 +1,71 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Drive strength sweep test.  Toggles GPIO 2 with the same short period at
+// each of the four drive strengths, first with the slow slew rate and then
+// the fast, pausing low between each with a logged banner, so a single
+// capture shows how the edges change across them all.  Single-gpio tests 16
+// to 18 each fix a drive strength at build time instead.
+//
+// The toggling is GpioToggler::toggle_n_cycles(), for STEP_PERIODS periods
+// at each setting, with interrupts disabled.  The period is short enough
+// that the edges are a good part of each half period, so their rates are
+// easy to compare.
+
+use defmt::info;
+use embassy_pico_test::gpio_asm::GpioToggler;
+use embassy_rp::gpio::{Drive, Level, Output, SlewRate};
+use embassy_time::Timer;
+
+use crate::{GPIO2_MASK, Test};
+
+// Cycles GPIO 2 is high and then low for.
+const HIGH_CYCLES: u32 = 10;
+const LOW_CYCLES: u32 = 10;
+
+// Periods at each setting, and the pause low after each.
+const STEP_PERIODS: u32 = 1_000_000;
+const PAUSE_MS: u64 = 20;
+
+const DRIVES: [(Drive, &str); 4] = [
+    (Drive::_2mA, "2mA"),
+    (Drive::_4mA, "4mA"),
+    (Drive::_8mA, "8mA"),
+    (Drive::_12mA, "12mA"),
+];
+const SLEW_RATES: [(SlewRate, &str); 2] = [(SlewRate::Slow, "slow"), (SlewRate::Fast, "fast")];
+
+impl Test {
+    pub async fn drive_sweep() -> ! {
+        let p = Self::init();
+
+        info!("Drive strength sweep test");
+        info!(": Using GPIO 2");
+        let period = HIGH_CYCLES + LOW_CYCLES;
+        info!(
+            ": Period: {} cycles ({}ns), for {} periods at each setting",
+            period,
+            Self::cycles_to_ns(period),
+            STEP_PERIODS
+        );
+
+        let mut output = Output::new(p.PIN_2, Level::Low);
+        let toggler = GpioToggler::new(GPIO2_MASK);
+
+        info!(": Starting");
+        loop {
+            for (slew_rate, slew_name) in SLEW_RATES {
+                output.set_slew_rate(slew_rate);
+                for (drive, drive_name) in DRIVES {
+                    output.set_drive_strength(drive);
+                    info!(": {} drive, {} slew", drive_name, slew_name);
+                    cortex_m::interrupt::free(|_| {
+                        toggler.toggle_n_cycles::<HIGH_CYCLES, LOW_CYCLES>(STEP_PERIODS)
+                    });
+                    Timer::after_millis(PAUSE_MS).await;
+                }
+            }
+        }
+    }
+}
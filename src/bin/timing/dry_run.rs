@@ -0,0 +1,37 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Dry run.  Logs every single-gpio test's description and expected period,
+// from TestNum::info(), and then idles, without running any of them or
+// configuring any GPIOs.  This checks the image boots and the test table is
+// complete, for example on hardware in CI.
+
+use defmt::info;
+
+use crate::{BOARD, Test, TestNum};
+
+impl Test {
+    pub fn dry_run() -> ! {
+        let _p = Self::init();
+
+        info!("Dry run");
+        info!(": Board: {}", BOARD);
+
+        for test_num in TestNum::ALL {
+            let num = test_num as i32;
+            let info = test_num.info();
+            match info.period_ns {
+                Some(ns) => info!(
+                    ": Test #{}: {} (expected period {}ns)",
+                    num, info.description, ns
+                ),
+                None => info!(": Test #{}: {} (no fixed period)", num, info.description),
+            }
+        }
+        info!(": {} tests", TestNum::ALL.len());
+        info!(": Done");
+
+        Self::idle()
+    }
+}
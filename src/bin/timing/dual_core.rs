@@ -0,0 +1,117 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Dual core contention test.  Core 0 toggles GPIO 2 and core 1 toggles
+// GPIO 3, both as fast as possible with SET/CLR stores, and each times every
+// iteration of its toggle loop with its own SysTick.  Core 0's loop is first
+// timed alone, before core 1 is started, as the baseline, so the worst case
+// delay caused by the other core can be reported.
+//
+// Each core reaches the SIO through its own single-cycle IOPORT, and if both
+// write to the GPIO registers in the same cycle, the result is as if core 0
+// wrote first and core 1 immediately afterwards - neither is stalled.  Any
+// interference is therefore from contention elsewhere, such as both cores
+// fetching instructions through the XIP cache, or accessing the same SRAM
+// bank.
+
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use defmt::info;
+use embassy_rp::gpio::{Level, Output};
+use embassy_rp::multicore::{Stack, spawn_core1};
+
+use crate::measurement::{Measurement, MeasurementResult};
+use crate::{GPIO_OUT_CLR, GPIO_OUT_SET, Test};
+
+// Toggle loop iterations per measurement.
+const ITERATIONS: u32 = 1 << 20;
+
+const CORE0_MASK: u32 = 1 << 2;
+const CORE1_MASK: u32 = 1 << 3;
+
+static mut CORE1_STACK: Stack<4096> = Stack::new();
+
+// Set by core 0 to start core 1's toggle loop.
+static CORE1_GO: AtomicBool = AtomicBool::new(false);
+
+// Core 1's latest measurement, and a count of measurements taken.
+static CORE1_MIN_NS: AtomicU32 = AtomicU32::new(0);
+static CORE1_MAX_NS: AtomicU32 = AtomicU32::new(0);
+static CORE1_MEAN_NS: AtomicU32 = AtomicU32::new(0);
+static CORE1_RESULTS: AtomicU32 = AtomicU32::new(0);
+
+impl Test {
+    pub async fn dual_core_contention() -> ! {
+        let p = Self::init();
+
+        info!("Dual core contention test");
+        info!(": Core 0 toggling GPIO 2, core 1 toggling GPIO 3");
+
+        let _core0_output = Output::new(p.PIN_2, Level::Low);
+        let _core1_output = Output::new(p.PIN_3, Level::Low);
+
+        Self::systick_start();
+
+        let alone = Self::measure_toggle_loop(CORE0_MASK);
+        info!(": Core 0 alone: {}", alone);
+
+        // Safety: The stack is only used by core 1.
+        let stack = unsafe { &mut *core::ptr::addr_of_mut!(CORE1_STACK) };
+        spawn_core1(p.CORE1, stack, || {
+            // Each core has its own SysTick.
+            Self::systick_start();
+            while !CORE1_GO.load(Ordering::Acquire) {
+                core::hint::spin_loop();
+            }
+            loop {
+                let result = Self::measure_toggle_loop(CORE1_MASK);
+                CORE1_MIN_NS.store(result.min_ns, Ordering::Relaxed);
+                CORE1_MAX_NS.store(result.max_ns, Ordering::Relaxed);
+                CORE1_MEAN_NS.store(result.mean_ns, Ordering::Relaxed);
+                CORE1_RESULTS.store(CORE1_RESULTS.load(Ordering::Relaxed) + 1, Ordering::Release);
+            }
+        });
+
+        info!(": Starting");
+        CORE1_GO.store(true, Ordering::Release);
+        loop {
+            let contended = Self::measure_toggle_loop(CORE0_MASK);
+            info!(
+                ": Core 0 with core 1 toggling: {}, worst extra +{}ns",
+                contended,
+                contended.max_ns.saturating_sub(alone.min_ns)
+            );
+
+            if CORE1_RESULTS.load(Ordering::Acquire) > 0 {
+                let core1 = MeasurementResult {
+                    min_ns: CORE1_MIN_NS.load(Ordering::Relaxed),
+                    max_ns: CORE1_MAX_NS.load(Ordering::Relaxed),
+                    mean_ns: CORE1_MEAN_NS.load(Ordering::Relaxed),
+                    samples: ITERATIONS,
+                };
+                // Both cores run the same loop, so core 0's baseline applies.
+                info!(
+                    ": Core 1 with core 0 toggling: {}, worst extra +{}ns",
+                    core1,
+                    core1.max_ns.saturating_sub(alone.min_ns)
+                );
+            }
+        }
+    }
+
+    // Toggles the pin(s) in mask ITERATIONS times, timing each iteration.
+    // Runs on both cores, so is not inlined, so both run the same code.
+    #[inline(never)]
+    fn measure_toggle_loop(mask: u32) -> MeasurementResult {
+        let mut measurement = Measurement::new();
+        let mut last = Self::systick_now();
+        for _ in 0..ITERATIONS {
+            Self::sio_write(GPIO_OUT_SET, mask);
+            Self::sio_write(GPIO_OUT_CLR, mask);
+            let now = Self::systick_now();
+            measurement.add(Self::systick_elapsed(last, now));
+            last = now;
+        }
+        measurement.result(Self::cycles_to_ns)
+    }
+}
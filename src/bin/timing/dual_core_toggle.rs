@@ -0,0 +1,129 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Dual core toggle test.  Core 0 toggles GPIO 2 and core 1 toggles GPIO 3,
+// each from the same asm loop, GpioToggler::toggle_forever(), with the same
+// period, started together from a shared flag.  Probe both pins to
+// see whether the two cores stay phase-aligned or drift.
+//
+// Both cores are clocked by clk_sys, so the two waveforms should keep a
+// fixed offset - the few cycles between the cores seeing the flag - unless
+// one core is stalled, such as by both fetching through the XIP cache.
+// Before starting, each core reads its CPUID and CLK_SYS_CTRL, to report
+// the clock source it is running from.  Core 1 passes its readings back
+// through atomics, as in the dual core contention test, so only core 0 logs.
+//
+// Interrupts are disabled on both cores while toggling.
+
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use defmt::info;
+use embassy_pico_test::gpio_asm::GpioToggler;
+use embassy_rp::gpio::{Level, Output};
+use embassy_rp::multicore::{Stack, spawn_core1};
+use embassy_rp::pac;
+use embassy_rp::pac::clocks::regs::ClkSysCtrl;
+use embassy_rp::pac::clocks::vals::{ClkSysCtrlAuxsrc, ClkSysCtrlSrc};
+
+use crate::Test;
+
+// Cycles each pin is high and then low for, on both cores.
+pub const HIGH_CYCLES: u32 = 50;
+pub const LOW_CYCLES: u32 = 50;
+
+const CORE0_MASK: u32 = 1 << 2;
+const CORE1_MASK: u32 = 1 << 3;
+
+static mut CORE1_STACK: Stack<4096> = Stack::new();
+
+// Set by core 1 once its readings are stored, and by core 0 to start both
+// toggle loops.
+static CORE1_READY: AtomicBool = AtomicBool::new(false);
+static GO: AtomicBool = AtomicBool::new(false);
+
+// Core 1's CPUID and CLK_SYS_CTRL readings.
+static CORE1_CPUID: AtomicU32 = AtomicU32::new(0);
+static CORE1_CLK_SYS_CTRL: AtomicU32 = AtomicU32::new(0);
+
+impl Test {
+    pub async fn dual_core_toggle() -> ! {
+        let p = Self::init();
+
+        info!("Dual core toggle test");
+        info!(": Core 0 toggling GPIO 2, core 1 toggling GPIO 3");
+        let period = HIGH_CYCLES + LOW_CYCLES;
+        info!(
+            ": Period: {} cycles ({}ns) on both cores",
+            period,
+            Self::cycles_to_ns(period)
+        );
+
+        let _core0_output = Output::new(p.PIN_2, Level::Low);
+        let _core1_output = Output::new(p.PIN_3, Level::Low);
+
+        // Safety: The stack is only used by core 1.
+        let stack = unsafe { &mut *core::ptr::addr_of_mut!(CORE1_STACK) };
+        spawn_core1(p.CORE1, stack, || {
+            CORE1_CPUID.store(Self::cpuid(), Ordering::Relaxed);
+            CORE1_CLK_SYS_CTRL.store(Self::clk_sys_ctrl(), Ordering::Relaxed);
+            CORE1_READY.store(true, Ordering::Release);
+
+            cortex_m::interrupt::disable();
+            while !GO.load(Ordering::Acquire) {
+                core::hint::spin_loop();
+            }
+            GpioToggler::new(CORE1_MASK).toggle_forever::<HIGH_CYCLES, LOW_CYCLES>()
+        });
+
+        while !CORE1_READY.load(Ordering::Acquire) {
+            core::hint::spin_loop();
+        }
+        Self::log_core_clock(Self::cpuid(), Self::clk_sys_ctrl());
+        Self::log_core_clock(
+            CORE1_CPUID.load(Ordering::Relaxed),
+            CORE1_CLK_SYS_CTRL.load(Ordering::Relaxed),
+        );
+
+        info!(": Starting");
+        cortex_m::interrupt::disable();
+        GO.store(true, Ordering::Release);
+        GpioToggler::new(CORE0_MASK).toggle_forever::<HIGH_CYCLES, LOW_CYCLES>()
+    }
+
+    // Logs a core's clock source, from its CPUID and CLK_SYS_CTRL readings.
+    fn log_core_clock(cpuid: u32, clk_sys_ctrl: u32) {
+        info!(
+            ": Core {}: clk_sys from {}, at {} Hz",
+            cpuid,
+            Self::clk_sys_source(clk_sys_ctrl),
+            embassy_rp::clocks::clk_sys_freq()
+        );
+    }
+
+    // Returns the name of clk_sys's source, from CLK_SYS_CTRL.
+    fn clk_sys_source(clk_sys_ctrl: u32) -> &'static str {
+        let ctrl = ClkSysCtrl(clk_sys_ctrl);
+        match ctrl.src() {
+            ClkSysCtrlSrc::CLK_REF => "clk_ref",
+            ClkSysCtrlSrc::CLKSRC_CLK_SYS_AUX => match ctrl.auxsrc() {
+                ClkSysCtrlAuxsrc::CLKSRC_PLL_SYS => "pll_sys",
+                ClkSysCtrlAuxsrc::CLKSRC_PLL_USB => "pll_usb",
+                ClkSysCtrlAuxsrc::ROSC_CLKSRC => "rosc",
+                ClkSysCtrlAuxsrc::XOSC_CLKSRC => "xosc",
+                ClkSysCtrlAuxsrc::CLKSRC_GPIN0 => "gpin0",
+                ClkSysCtrlAuxsrc::CLKSRC_GPIN1 => "gpin1",
+                _ => "reserved",
+            },
+        }
+    }
+
+    // Returns the number of the core this is running on.
+    fn cpuid() -> u32 {
+        pac::SIO.cpuid().read()
+    }
+
+    // Returns the raw CLK_SYS_CTRL register, as read by this core.
+    fn clk_sys_ctrl() -> u32 {
+        pac::CLOCKS.clk_sys_ctrl().read().0
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// DWT cycle counter delays, used by single-gpio tests 26 and 27.  The
+// Pico 2's Cortex-M33 has a DWT with a 32 bit CYCCNT, counting clk_sys
+// cycles, so a delay can spin until it has advanced by an exact number of
+// cycles.  Unlike the counted asm delay loops, this doesn't depend on the
+// instructions' timings, which vary on the Pico 2.
+//
+// The Pico's Cortex-M0+ has no cycle counter, so these are Pico 2 only.
+// SysTick is the closest equivalent there - see systick_now().
+//
+// Each delay returns within a few cycles of the deadline, after the loop
+// notices it, plus the call overhead.  dwt_delay_ns() also converts to
+// cycles on every call, so for short delays convert once with
+// dwt_ns_to_cycles() and call dwt_delay_cycles().
+
+use cortex_m::peripheral::DWT;
+
+use crate::Test;
+
+impl Test {
+    // Enables the DWT cycle counter.  Must be called before the delays.
+    pub fn dwt_enable() {
+        // Safety: Nothing else (including embassy) uses the DCB or DWT.
+        let mut cp = unsafe { cortex_m::Peripherals::steal() };
+        cp.DCB.enable_trace();
+        cp.DWT.enable_cycle_counter();
+    }
+
+    // Spins until CYCCNT has advanced by cycles.  Handles CYCCNT wrapping,
+    // so any delay up to 2^32 - 1 cycles is valid.
+    #[inline(always)]
+    pub fn dwt_delay_cycles(cycles: u32) {
+        let start = DWT::cycle_count();
+        while DWT::cycle_count().wrapping_sub(start) < cycles {}
+    }
+
+    // Converts ns to clk_sys cycles, rounding to the nearest.
+    pub fn dwt_ns_to_cycles(ns: u32) -> u32 {
+        let speed = embassy_rp::clocks::clk_sys_freq() as u64;
+        ((ns as u64 * speed + 500_000_000) / 1_000_000_000) as u32
+    }
+
+    // Spins for ns, converted to cycles at the current clk_sys speed.
+    #[inline(always)]
+    pub fn dwt_delay_ns(ns: u32) {
+        Self::dwt_delay_cycles(Self::dwt_ns_to_cycles(ns));
+    }
+}
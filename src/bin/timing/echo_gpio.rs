@@ -0,0 +1,166 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Echo GPIO test.  Mirrors the level of an input, GPIO 3, to an output,
+// GPIO 2, in a tight loop, so the delay from an external edge to the
+// Pico's response can be measured on a scope with both pins probed.
+//
+// There are two versions of the loop, for comparison:
+// - Hal, which copies Input::get_level() to Output::set_level().
+// - Asm, which reads GPIO_IN directly, shifts the input's bit to the
+//   output's, and stores it to GPIO_OUT_SET, then its inverse within the
+//   output's mask to GPIO_OUT_CLR, so there's no branch on the level.
+//
+// Either way the response to an edge is the input's 2 cycle synchroniser,
+// then anywhere up to a full loop, depending on where in the loop the edge
+// arrives, so the delay seen jitters by a loop's length.  The Asm loop is
+// counted, exactly on the Pico.  With MEASURE, the Hal loop is first run
+// for MEASURE_LOOPS iterations, timed with SysTick, to log its length.
+//
+// Interrupts are disabled while mirroring, so nothing delays the response.
+
+use core::arch::asm;
+use defmt::{error, info, warn};
+use embassy_rp::gpio::{AnyPin, Input, Level, Output, Pull};
+
+use crate::{
+    GPIO_IN, GPIO_OUT_CLR, GPIO_OUT_SET, IS_PICO2, NUM_GPIOS, SIO_BASE, SYSTICK_MAX, Test,
+};
+
+// Loop to mirror the input with, and whether to measure the Hal loop.
+pub const MODE: EchoMode = EchoMode::Asm;
+pub const MEASURE: bool = true;
+
+// Pins to mirror from and to, and the input's pull.  Named for this test,
+// so as not to shadow the single GPIO tests' OUTPUT_PIN.
+const ECHO_INPUT_PIN: u8 = 3;
+const ECHO_OUTPUT_PIN: u8 = 2;
+const PULL: Pull = Pull::Down;
+const _: () = assert!(
+    ECHO_INPUT_PIN < NUM_GPIOS && ECHO_OUTPUT_PIN < NUM_GPIOS && ECHO_INPUT_PIN != ECHO_OUTPUT_PIN
+);
+
+// Echo loops
+#[derive(Clone, Copy, defmt::Format)]
+pub enum EchoMode {
+    Hal,
+    Asm,
+}
+
+// Hal loop iterations to time with MEASURE.
+const MEASURE_LOOPS: u32 = 1_000;
+
+// Cycles per Asm loop on the Pico - the load, shift, mask, two stores and
+// eors, and the branch back.
+const ASM_LOOP_CYCLES: u32 = 8;
+
+// Input synchroniser cycles
+const SYNC_CYCLES: u32 = 2;
+
+// Register offsets from SIO_BASE, for use as asm immediates.
+const IN_OFFSET: u32 = GPIO_IN - SIO_BASE;
+const SET_OFFSET: u32 = GPIO_OUT_SET - SIO_BASE;
+const CLR_OFFSET: u32 = GPIO_OUT_CLR - SIO_BASE;
+
+impl Test {
+    pub async fn echo_gpio(mode: EchoMode) -> ! {
+        let _p = Self::init();
+
+        info!("Echo GPIO test");
+        info!(
+            ": Input GPIO {}, output GPIO {}",
+            ECHO_INPUT_PIN, ECHO_OUTPUT_PIN
+        );
+        info!(": Mode: {}", mode);
+
+        // Safety: The typed pin peripherals aren't used by this test, and
+        // the pins differ, so each is only instantiated once.
+        let input = Input::new(unsafe { AnyPin::steal(ECHO_INPUT_PIN) }, PULL);
+        let mut output = Output::new(unsafe { AnyPin::steal(ECHO_OUTPUT_PIN) }, Level::Low);
+
+        let loop_cycles = match mode {
+            EchoMode::Hal if MEASURE => Self::measure_echo_hal(&input, &mut output),
+            EchoMode::Hal => None,
+            EchoMode::Asm => {
+                if IS_PICO2 {
+                    warn!(": Loop length is an estimate on the Pico 2 - measure it");
+                }
+                Some(ASM_LOOP_CYCLES)
+            }
+        };
+        if let Some(cycles) = loop_cycles {
+            info!(
+                ": Loop: {} cycles ({}ns), so response is {}-{}ns",
+                cycles,
+                Self::cycles_to_ns(cycles),
+                Self::cycles_to_ns(SYNC_CYCLES),
+                Self::cycles_to_ns(SYNC_CYCLES + cycles)
+            );
+        }
+
+        info!(": Starting");
+        cortex_m::interrupt::disable();
+        match mode {
+            EchoMode::Hal => loop {
+                output.set_level(input.get_level());
+            },
+            EchoMode::Asm => Self::asm_echo(),
+        }
+    }
+
+    // Times MEASURE_LOOPS iterations of the Hal loop, returning the cycles
+    // per iteration, or None if they took too long for SysTick.
+    fn measure_echo_hal(input: &Input<'_>, output: &mut Output<'_>) -> Option<u32> {
+        Self::systick_start();
+        let start = Self::systick_now();
+        for _ in 0..MEASURE_LOOPS {
+            output.set_level(input.get_level());
+        }
+        let end = Self::systick_now();
+
+        // Sanity check the loops took under half of SysTick's range, so it
+        // has definitely not wrapped more than once.
+        let cycles = Self::systick_elapsed(start, end);
+        if cycles > SYSTICK_MAX / 2 {
+            error!(": Hal loop took too long to measure");
+            return None;
+        }
+        Some(cycles.div_ceil(MEASURE_LOOPS))
+    }
+
+    // Mirrors ECHO_INPUT_PIN to ECHO_OUTPUT_PIN forever.  Only
+    // ECHO_OUTPUT_PIN is changed, as after shifting, the input's bit is
+    // masked to the output's.
+    //
+    // r0: SIO_BASE, r1: output mask, r2: scratch.
+    fn asm_echo() -> ! {
+        // Safety: The loop only reads GPIO_IN and stores to GPIO_OUT_SET and
+        // GPIO_OUT_CLR, and never returns, so may use r2 without declaring
+        // it.  The stores only change ECHO_OUTPUT_PIN, which this test owns.
+        unsafe {
+            asm!(
+                "2:",
+                "ldr r2, [r0, #{input}]",
+                ".if {in_pin} > {out_pin}",
+                "lsrs r2, r2, #{in_pin} - {out_pin}",
+                ".else",
+                "lsls r2, r2, #{out_pin} - {in_pin}",
+                ".endif",
+                "ands r2, r1",
+                "str r2, [r0, #{set}]",   // High if the input is
+                "eors r2, r1",
+                "str r2, [r0, #{clr}]",   // Low if the input is
+                "b 2b",
+                in("r0") SIO_BASE,
+                in("r1") 1u32 << ECHO_OUTPUT_PIN,
+                input = const IN_OFFSET,
+                set = const SET_OFFSET,
+                clr = const CLR_OFFSET,
+                in_pin = const ECHO_INPUT_PIN,
+                out_pin = const ECHO_OUTPUT_PIN,
+                options(noreturn, nostack),
+            );
+        }
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Glitch injection test.  Holds GPIO 2 low, but every EVERY_N periods emits
+// a glitch GLITCH_CYCLES clk_sys cycles wide in the middle of the period, to
+// probe the rejection threshold of a downstream input filter.
+//
+// The glitch is a SET store, GLITCH_CYCLES - 1 nops, and a CLR store, all in
+// one asm block with the addresses and mask already in registers, so nothing
+// can be scheduled between them.  SIO stores take a single cycle, so the
+// glitch is exactly GLITCH_CYCLES cycles wide at the pad, down to 1 cycle -
+// 8ns on the Pico, 6.67ns on the Pico 2.  At that width the pad's slew rate
+// and the trace's capacitance may mean the glitch never reaches a full
+// logic level, which is, after all, what a filter has to cope with.  For
+// that reason GPIO 2 is set to fast slew and 12mA drive.
+//
+// Periods are counted with SysTick, with interrupts disabled, as in the
+// ws2812 test.
+
+use defmt::{error, info};
+use embassy_rp::gpio::{Drive, Level, Output, SlewRate};
+use embassy_time::Duration;
+
+use crate::{GPIO_OUT_CLR, GPIO_OUT_SET, GPIO2_MASK, SYSTICK_MAX, Test};
+
+// Period, glitch width, and how often to glitch.
+pub const PERIOD: Duration = Duration::from_micros(10);
+pub const GLITCH_CYCLES: u32 = 1;
+pub const EVERY_N: u32 = 10;
+
+// Widest glitch supported.
+const MAX_GLITCH_CYCLES: u32 = 8;
+
+impl Test {
+    pub async fn glitch_inject(period: Duration, glitch_cycles: u32, every_n: u32) -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        info!("Glitch injection test");
+        info!(": Using GPIO 2");
+
+        let period_cycles = (period.as_micros() * speed as u64 / 1_000_000) as u32;
+        if !(1..=MAX_GLITCH_CYCLES).contains(&glitch_cycles) {
+            error!(": Glitch must be 1 to {} cycles", MAX_GLITCH_CYCLES);
+            Self::idle();
+        }
+        if every_n == 0 || period_cycles <= 2 * MAX_GLITCH_CYCLES || period_cycles > SYSTICK_MAX {
+            error!(": Period or glitch rate out of range");
+            Self::idle();
+        }
+        info!(
+            ": Glitch width: {} cycles ({}ns)",
+            glitch_cycles,
+            Self::cycles_to_ns(glitch_cycles)
+        );
+        info!(
+            ": Glitch every {} periods of {}us, so {} Hz",
+            every_n,
+            period.as_micros(),
+            1_000_000 / (period.as_micros() as u32 * every_n)
+        );
+
+        let mut output = Output::new(p.PIN_2, Level::Low);
+        output.set_slew_rate(SlewRate::Fast);
+        output.set_drive_strength(Drive::_12mA);
+
+        Self::systick_start();
+
+        info!(": Starting");
+
+        // Disable interrupts so nothing disturbs the timing.
+        cortex_m::interrupt::disable();
+        match glitch_cycles {
+            1 => Self::glitch_loop::<1>(period_cycles, every_n),
+            2 => Self::glitch_loop::<2>(period_cycles, every_n),
+            3 => Self::glitch_loop::<3>(period_cycles, every_n),
+            4 => Self::glitch_loop::<4>(period_cycles, every_n),
+            5 => Self::glitch_loop::<5>(period_cycles, every_n),
+            6 => Self::glitch_loop::<6>(period_cycles, every_n),
+            7 => Self::glitch_loop::<7>(period_cycles, every_n),
+            _ => Self::glitch_loop::<8>(period_cycles, every_n),
+        }
+    }
+
+    // Emits a glitch CYCLES wide in the middle of every every_n'th period.
+    fn glitch_loop<const CYCLES: u32>(period_cycles: u32, every_n: u32) -> ! {
+        let mut period_start = Self::systick_now();
+        let mut n = 0;
+        loop {
+            n += 1;
+            if n == every_n {
+                n = 0;
+                Self::wait_until_cycles(period_start, period_cycles / 2);
+                Self::asm_glitch::<CYCLES>();
+            }
+            Self::wait_until_cycles(period_start, period_cycles);
+            period_start = period_start.wrapping_sub(period_cycles) & SYSTICK_MAX;
+        }
+    }
+
+    // Drives GPIO 2 high for exactly CYCLES cycles.
+    #[inline(always)]
+    fn asm_glitch<const CYCLES: u32>() {
+        unsafe {
+            core::arch::asm!(
+                "str {mask}, [{set}]",
+                ".rept {nops}",
+                "nop",
+                ".endr",
+                "str {mask}, [{clr}]",
+                mask = in(reg) GPIO2_MASK,
+                set = in(reg) GPIO_OUT_SET,
+                clr = in(reg) GPIO_OUT_CLR,
+                nops = const CYCLES - 1,
+            );
+        }
+    }
+}
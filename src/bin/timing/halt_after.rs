@@ -0,0 +1,42 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Halt after a number of periods, enabled by the halt-after feature, for
+// single-shot scope captures.  The single_gpio! loop, and the tests with
+// their own loops, call HaltAfter::period() once per period, and end after
+// PERIODS full periods, just after the final falling edge.  The test then
+// holds GPIO 2 low, logs that it is done, and waits for interrupt forever.
+//
+// Test 28's bursts are counted whole, so it halts after the first burst
+// which reaches PERIODS.  The asm tests never return, so are replaced by
+// bounded versions, from asm_toggle_bounded(), as with test-sweep - their
+// duty cycles differ slightly.
+
+// Full periods to toggle before halting.
+pub const PERIODS: u32 = 1_000;
+const _: () = assert!(PERIODS > 0, "PERIODS must be at least 1");
+
+pub struct HaltAfter {
+    remaining: u32,
+}
+
+impl HaltAfter {
+    pub fn new() -> Self {
+        Self { remaining: PERIODS }
+    }
+
+    // Counts a period, returning true once PERIODS have completed.
+    #[inline(always)]
+    pub fn period(&mut self) -> bool {
+        self.remaining -= 1;
+        self.remaining == 0
+    }
+
+    // Counts periods periods at once, returning true once at least PERIODS
+    // have completed.
+    pub fn periods(&mut self, periods: u32) -> bool {
+        self.remaining = self.remaining.saturating_sub(periods);
+        self.remaining == 0
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Period histogram, enabled by the histogram feature.  The single_gpio!
+// loop calls Histogram::period() once per period, which reads Instant and
+// counts the time since the last call into 1us buckets.  After SAMPLES
+// periods the histogram is logged, and collection starts again.  This
+// shows the spread of periods, so, for example, the yielding Timer tests'
+// executor latency can be compared with the blocking Delay tests.
+//
+// The buckets start just below the shortest of the first CALIBRATE_SAMPLES
+// periods, so the histogram follows the test's period, with periods beyond
+// the buckets counted as overflows.  Instant has 1us resolution, so each
+// period is only resolved to 1us, and reading it every period lengthens the
+// shorter periods slightly.  The period in which the histogram is logged
+// isn't counted.
+
+use defmt::info;
+use embassy_time::Instant;
+
+// Periods per histogram, and the number of 1us buckets.
+pub const SAMPLES: u32 = 10_000;
+const BUCKETS: usize = 32;
+
+// Periods used to find the base of the buckets, and how far below the
+// shortest of them the buckets start.
+const CALIBRATE_SAMPLES: u32 = 100;
+const BASE_MARGIN_US: u64 = 2;
+
+pub struct Histogram {
+    last: Instant,
+    base_us: u64,
+    buckets: [u32; BUCKETS],
+    under: u32,
+    over: u32,
+    min_us: u64,
+    max_us: u64,
+    samples: u32,
+    calibrating: bool,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self {
+            last: Instant::now(),
+            base_us: 0,
+            buckets: [0; BUCKETS],
+            under: 0,
+            over: 0,
+            min_us: u64::MAX,
+            max_us: 0,
+            samples: 0,
+            calibrating: true,
+        }
+    }
+
+    // Counts the period since the last call, logging the histogram when
+    // SAMPLES have been counted.
+    #[inline(always)]
+    pub fn period(&mut self) {
+        let now = Instant::now();
+        let period_us = (now - self.last).as_micros();
+        self.last = now;
+        self.record(period_us);
+    }
+
+    #[inline(never)]
+    fn record(&mut self, period_us: u64) {
+        self.min_us = self.min_us.min(period_us);
+        self.max_us = self.max_us.max(period_us);
+        self.samples += 1;
+
+        if self.calibrating {
+            if self.samples == CALIBRATE_SAMPLES {
+                self.base_us = self.min_us.saturating_sub(BASE_MARGIN_US);
+                self.calibrating = false;
+                self.reset();
+            }
+            return;
+        }
+
+        match period_us.checked_sub(self.base_us) {
+            None => self.under += 1,
+            Some(offset) if offset >= BUCKETS as u64 => self.over += 1,
+            Some(offset) => self.buckets[offset as usize] += 1,
+        }
+
+        if self.samples == SAMPLES {
+            self.log();
+            self.reset();
+            self.last = Instant::now();
+        }
+    }
+
+    fn log(&self) {
+        info!(
+            ": Period histogram, {} periods, min {}us, max {}us",
+            self.samples, self.min_us, self.max_us
+        );
+        if self.under > 0 {
+            info!(":   <{}us: {}", self.base_us, self.under);
+        }
+        for (ii, &count) in self.buckets.iter().enumerate() {
+            if count > 0 {
+                info!(":   {}us: {}", self.base_us + ii as u64, count);
+            }
+        }
+        if self.over > 0 {
+            info!(":   >={}us: {}", self.base_us + BUCKETS as u64, self.over);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.buckets = [0; BUCKETS];
+        self.under = 0;
+        self.over = 0;
+        self.min_us = u64::MAX;
+        self.max_us = 0;
+        self.samples = 0;
+    }
+}
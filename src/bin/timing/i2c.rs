@@ -0,0 +1,126 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// I2C test.  Repeatedly writes DATA to ADDRESS from I2C1 in blocking mode,
+// with SDA on GPIO 2 and SCL on GPIO 3, so SCL's frequency and duty, and
+// SDA's hold time after SCL falls, can be scoped at each I2C speed.  Set
+// FREQUENCY to 100_000, 400_000 or 1_000_000 for standard mode, fast mode or
+// fast mode plus.
+//
+// ADDRESS needn't be present.  If nothing acknowledges it, each write ends
+// after the address byte, which still shows SCL's timing, and the test
+// counts the no-ACKs and carries on.  Other errors are also counted.  The
+// counts are logged every REPORT_INTERVAL.
+//
+// SCL's high and low counts are clk_peri cycles, so only some frequencies
+// are possible.  embassy picks them for a 40/60 high/low split, rounding to
+// the nearest, and the frequency they give is logged from the counts read
+// back.  The controller adds SPKLEN + 7 cycles to each high period and 1 to
+// each low, which is also shown, and SCL's rise time, set by the pull-ups,
+// adds more, as the controller only counts the high period once it sees SCL
+// high.  embassy enables the pads' pull-ups, but they are weak, so add
+// external pull-ups, of 4.7k for 100kHz down to 1k for 1MHz, for realistic
+// edges.
+//
+// A gap of GAP_US follows each write, so the transactions are distinct on a
+// scope.
+
+use defmt::{error, info, warn};
+use embassy_embedded_hal::SetConfig;
+use embassy_rp::i2c::{AbortReason, Config, Error, I2c};
+use embassy_rp::pac;
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::Test;
+
+// SCL frequency to request, in Hz.
+pub const FREQUENCY: u32 = 400_000;
+
+// 7-bit address to write to, and the bytes to write.
+const ADDRESS: u8 = 0x50;
+const DATA: [u8; 2] = [0xa5, 0x0f];
+
+// Gap between writes, and the time between reports.
+const GAP_US: u64 = 100;
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+impl Test {
+    pub async fn i2c(frequency: u32) -> ! {
+        let p = Self::init();
+
+        let clk_peri = embassy_rp::clocks::clk_peri_freq();
+        info!("I2C test");
+        info!(": I2C1, SDA on GPIO 2, SCL on GPIO 3");
+        info!(": Address: {:#04x}, data: {=[u8]:#04x}", ADDRESS, DATA);
+        info!(": Requested frequency: {} Hz", frequency);
+
+        // embassy panics if new_blocking() is given a frequency it can't
+        // configure, but set_config() returns an error, so start with the
+        // default 100kHz, which any clk_peri over 2MHz supports.
+        let mut i2c = I2c::new_blocking(p.I2C1, p.PIN_3, p.PIN_2, Config::default());
+        let mut config = Config::default();
+        config.frequency = frequency;
+        if let Err(e) = i2c.set_config(&config) {
+            error!(
+                ": Can't configure {} Hz from clk_peri {} Hz: {}",
+                frequency, clk_peri, e
+            );
+            Self::idle();
+        }
+
+        let hcnt = pac::I2C1.ic_fs_scl_hcnt().read().ic_fs_scl_hcnt() as u32;
+        let lcnt = pac::I2C1.ic_fs_scl_lcnt().read().ic_fs_scl_lcnt() as u32;
+        let spklen = pac::I2C1.ic_fs_spklen().read().ic_fs_spklen() as u32;
+        let hold = pac::I2C1.ic_sda_hold().read().ic_sda_tx_hold() as u32;
+        info!(
+            ": High count: {}, low count: {}, spike length: {}, from clk_peri {} Hz",
+            hcnt, lcnt, spklen, clk_peri
+        );
+        info!(
+            ": Frequency from the counts: {} Hz, with the controller's overheads: {} Hz",
+            clk_peri / (hcnt + lcnt),
+            clk_peri / (hcnt + spklen + 7 + lcnt + 1)
+        );
+        info!(
+            ": SDA hold: {} cycles ({}ns)",
+            hold,
+            (hold as u64 * 1_000_000_000 / clk_peri as u64) as u32
+        );
+
+        info!(": Starting");
+        let mut acks = 0u32;
+        let mut no_acks = 0u32;
+        let mut errors = 0u32;
+        let mut next_report = Instant::now() + REPORT_INTERVAL;
+        loop {
+            match i2c.blocking_write(ADDRESS, &DATA) {
+                Ok(()) => acks += 1,
+                Err(Error::Abort(AbortReason::NoAcknowledge)) => {
+                    if no_acks == 0 {
+                        warn!(
+                            ": No ACK from {:#04x} - continuing, with address-only writes",
+                            ADDRESS
+                        );
+                    }
+                    no_acks += 1;
+                }
+                Err(e) => {
+                    if errors == 0 {
+                        warn!(": Write failed: {} - continuing", e);
+                    }
+                    errors += 1;
+                }
+            }
+            Timer::after_micros(GAP_US).await;
+
+            if Instant::now() >= next_report {
+                info!(
+                    ": Writes: {} acknowledged, {} not acknowledged, {} other errors",
+                    acks, no_acks, errors
+                );
+                next_report += REPORT_INTERVAL;
+            }
+        }
+    }
+}
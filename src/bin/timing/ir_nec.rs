@@ -0,0 +1,134 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// NEC IR remote test.  Emits NEC frames on GPIO 2 as a 38kHz carrier gated
+// by the NEC envelope, for driving an IR LED (via a transistor) to test an
+// IR receiver.
+//
+// An NEC frame is a 9ms mark and a 4.5ms space, then 32 bits (address,
+// inverted address, command, inverted command, each LSB first), and finally
+// a stop mark.  Every bit is a 562.5us mark, followed by a 562.5us space for
+// a 0, or a 1687.5us space for a 1.  Frames are repeated every 108ms.  The
+// frame and its envelope are built by the library's nec module.
+//
+// Each frame's edges - carrier and envelope - are placed at their exact
+// clk_sys cycle offsets from the start of the frame, counted with SysTick
+// with interrupts disabled.  This avoids relying on the delay loop's cycle
+// cost, which varies on the Pico 2, and stops rounding errors accumulating
+// over the frame, so both the carrier frequency and mark/space durations are
+// accurate to a few cycles.  Each pulse is waited on from the previous
+// pulse's deadline, as in the ws2812 test, as a whole frame can be longer
+// than SysTick's range.
+
+use defmt::info;
+use embassy_pico_test::nec::{Pulse, nec_envelope, nec_frame};
+use embassy_rp::gpio::{Level, Output};
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::{GPIO_OUT_CLR, GPIO_OUT_SET, GPIO2_MASK, SYSTICK_MAX, Test};
+
+// Address and command to send.
+pub const ADDRESS: u8 = 0x00;
+pub const COMMAND: u8 = 0x45;
+
+// Carrier frequency.
+const CARRIER_HZ: u32 = 38_000;
+
+// Time from the start of one frame to the start of the next.
+const FRAME_PERIOD: Duration = Duration::from_millis(108);
+
+fn ns_to_cycles(ns: u64, speed: u32) -> u32 {
+    (ns * speed as u64 / 1_000_000_000) as u32
+}
+
+impl Test {
+    pub async fn ir_nec(address: u8, command: u8) -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        let frame = nec_frame(address, command);
+        info!("NEC IR remote test");
+        info!(": Using GPIO 2");
+        info!(": Address: {:#x}, command: {:#x}", address, command);
+        info!(": Frame: {:#x}", frame);
+        info!(
+            ": Carrier: {} Hz, {} cycles per period",
+            CARRIER_HZ,
+            speed / CARRIER_HZ
+        );
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+        #[cfg(feature = "sync-pin")]
+        let _sync = Self::sync_output(p.PIN_3, "start of each frame");
+        let envelope = nec_envelope(frame);
+
+        Self::systick_start();
+
+        info!(": Starting");
+        let mut next_frame = Instant::now();
+        loop {
+            cortex_m::interrupt::free(|_| Self::send_envelope(&envelope, speed));
+            next_frame += FRAME_PERIOD;
+            Timer::at(next_frame).await;
+        }
+    }
+
+    // Sends the envelope.  The carrier restarts, going high, at the start of
+    // each mark, and the final half period is cut short when the mark ends.
+    //
+    // Must be called with interrupts disabled, and with SysTick started.
+    // Every NEC frame has 16 ones and 16 zeros, so is 67.5ms plus the stop
+    // mark - 17M cycles at 250MHz, over SysTick's 2^24 cycle range - so
+    // each pulse's edges are counted from its own start, which is the
+    // previous pulse's deadline.  A pulse is at most the 13.5ms leader, or
+    // 3.4M cycles at 250MHz.
+    fn send_envelope(envelope: &[Pulse], speed: u32) {
+        // Half periods are generally a non-integer number of cycles, so
+        // spread the remainder across them.
+        let half_divisor = 2 * CARRIER_HZ;
+        let half_cycles = speed / half_divisor;
+        let half_rem = speed % half_divisor;
+
+        #[cfg(not(feature = "sync-pin"))]
+        let mut pulse_start = Self::systick_now();
+        #[cfg(feature = "sync-pin")]
+        let mut pulse_start = Self::sync_pulse();
+        // Each pulse's offset, in cycles, is rounded from the start of the
+        // frame, so rounding doesn't accumulate, but its edges are waited on
+        // from pulse_start, its SysTick value.
+        let mut elapsed_ns = 0u64;
+        let mut pulse_offset = 0;
+        for pulse in envelope {
+            elapsed_ns += pulse.mark_ns as u64;
+            let mark_end = ns_to_cycles(elapsed_ns, speed) - pulse_offset;
+
+            let mut edge = 0;
+            let mut frac = 0;
+            let mut high = false;
+            while edge < mark_end {
+                Self::wait_until_cycles(pulse_start, edge);
+                if high {
+                    Self::sio_write(GPIO_OUT_CLR, GPIO2_MASK);
+                } else {
+                    Self::sio_write(GPIO_OUT_SET, GPIO2_MASK);
+                }
+                high = !high;
+
+                edge += half_cycles;
+                frac += half_rem;
+                if frac >= half_divisor {
+                    edge += 1;
+                    frac -= half_divisor;
+                }
+            }
+            Self::wait_until_cycles(pulse_start, mark_end);
+            Self::sio_write(GPIO_OUT_CLR, GPIO2_MASK);
+
+            elapsed_ns += pulse.space_ns as u64;
+            let next_offset = ns_to_cycles(elapsed_ns, speed);
+            pulse_start = pulse_start.wrapping_sub(next_offset - pulse_offset) & SYSTICK_MAX;
+            pulse_offset = next_offset;
+        }
+    }
+}
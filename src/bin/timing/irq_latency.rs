@@ -0,0 +1,221 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Interrupt latency test.  Arms a rising edge interrupt on GPIO 3, and
+// raises GPIO 2 as soon as it is handled, so the delay from the input edge
+// to the output edge can be measured on a scope with both pins probed.
+//
+// There are two versions, run in turn, for comparison:
+// - Async, which awaits Input::wait_for_rising_edge(), so the edge goes
+//   through embassy's IO_IRQ_BANK0 handler and a wake of the test's task,
+//   before GPIO 2 is set.
+// - Raw, which replaces IO_IRQ_BANK0's vector with raw_irq_handler(), whose
+//   first store sets GPIO 2, before acknowledging the edge.
+//
+// GPIO 3 is driven by the test itself, as well as being read back through
+// its pad as the interrupt's input, so no external signal is needed.  That
+// gives a SysTick reading from just before each edge is driven, and
+// another just after GPIO 2 is set, so each edge is timed as well - within
+// a few cycles, as that includes a SysTick read and store.  Both include
+// the input's 2 cycle synchroniser.
+//
+// embassy's handler must stay linked, so the raw version is installed by
+// copying the vector table to RAM, changing the entry there and pointing
+// VTOR at the copy, which is undone after each run.  Both handlers run from
+// flash, so the first edges may also include XIP cache misses - the minimum
+// is the figure to compare between the Pico and Pico 2.
+
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use defmt::info;
+use embassy_futures::join::join;
+use embassy_rp::gpio::{AnyPin, Input, Level, Output, Pull};
+use embassy_rp::interrupt::Interrupt;
+use embassy_rp::pac;
+use embassy_time::Timer;
+
+use crate::measurement::{Measurement, MeasurementResult};
+use crate::{GPIO_OUT_CLR, GPIO_OUT_SET, GPIO2_MASK, NUM_GPIOS, Test};
+
+// Pin whose edges are interrupted on, and driven.  GPIO 2 is the output.
+const INPUT_PIN: u8 = 3;
+const INPUT_MASK: u32 = 1 << INPUT_PIN;
+const _: () = assert!(INPUT_PIN < NUM_GPIOS && INPUT_PIN != 2);
+
+// Edges to time with each version, per run.
+const SAMPLES: u32 = 1_000;
+
+// Time to hold GPIO 2 high after each edge, and between runs.
+const HOLD_US: u64 = 10;
+const RUN_GAP_MS: u64 = 1_000;
+
+// Vector table entries - the 16 system exceptions, then the IRQs.
+#[cfg(feature = "pico")]
+const VECTORS: usize = 16 + 32;
+#[cfg(feature = "pico2")]
+const VECTORS: usize = 16 + 52;
+
+// A RAM vector table.  VTOR needs it aligned to its size, rounded up to a
+// power of 2.
+#[repr(C, align(512))]
+struct VectorTable([usize; VECTORS]);
+const _: () = assert!(core::mem::size_of::<VectorTable>() <= 512);
+
+static mut RAM_VECTORS: VectorTable = VectorTable([0; VECTORS]);
+
+// Set by raw_irq_handler() for each edge, with its SysTick reading.
+static RAW_EDGE: AtomicBool = AtomicBool::new(false);
+static RAW_SYSTICK: AtomicU32 = AtomicU32::new(0);
+
+impl Test {
+    pub async fn irq_latency() -> ! {
+        let _p = Self::init();
+
+        info!("Interrupt latency test");
+        info!(": Input (and driven) GPIO {}, output GPIO 2", INPUT_PIN);
+        info!(": {} edges per version, per run", SAMPLES);
+
+        // Safety: The typed pin peripherals aren't used by this test, and
+        // the pins differ, so each is only instantiated once.
+        let mut input = Input::new(unsafe { AnyPin::steal(INPUT_PIN) }, Pull::Down);
+        let _output = Output::new(unsafe { AnyPin::steal(2) }, Level::Low);
+
+        // Drive the input pin from SIO as well.  Its pad's input stays
+        // enabled, so it still sees its own edges.
+        pac::SIO.gpio_oe(0).value_set().write_value(INPUT_MASK);
+
+        Self::systick_start();
+
+        info!(": Starting");
+        loop {
+            let async_result = Self::irq_latency_async(&mut input).await;
+            let raw_result = Self::irq_latency_raw().await;
+            info!(": Async: {}", async_result);
+            info!(": Raw: {}", raw_result);
+            Timer::after_millis(RUN_GAP_MS).await;
+        }
+    }
+
+    // Times SAMPLES edges through wait_for_rising_edge().
+    async fn irq_latency_async(input: &mut Input<'_>) -> MeasurementResult {
+        let mut measurement = Measurement::new();
+        for _ in 0..SAMPLES {
+            // The wait is polled first, so the interrupt is armed before
+            // the edge is driven.
+            let wait = async {
+                input.wait_for_rising_edge().await;
+                Self::sio_write(GPIO_OUT_SET, GPIO2_MASK);
+                Self::systick_now()
+            };
+            let drive = async {
+                let start = Self::systick_now();
+                Self::sio_write(GPIO_OUT_SET, INPUT_MASK);
+                start
+            };
+            let (end, start) = join(wait, drive).await;
+            measurement.add(Self::systick_elapsed(start, end));
+
+            Self::irq_latency_hold().await;
+        }
+        measurement.result(Self::cycles_to_ns)
+    }
+
+    // Times SAMPLES edges through raw_irq_handler().
+    async fn irq_latency_raw() -> MeasurementResult {
+        let vtor = Self::install_raw_irq_handler();
+
+        // Clear any stale edge, then enable the interrupt for this core.
+        let input = INPUT_PIN as usize;
+        pac::IO_BANK0
+            .intr(input / 8)
+            .write(|w| w.set_edge_high(input % 8, true));
+        pac::IO_BANK0
+            .int_proc(0)
+            .inte(input / 8)
+            .modify(|w| w.set_edge_high(input % 8, true));
+
+        let mut measurement = Measurement::new();
+        for _ in 0..SAMPLES {
+            RAW_EDGE.store(false, Ordering::Relaxed);
+            let start = Self::systick_now();
+            Self::sio_write(GPIO_OUT_SET, INPUT_MASK);
+            while !RAW_EDGE.load(Ordering::Acquire) {
+                core::hint::spin_loop();
+            }
+            let end = RAW_SYSTICK.load(Ordering::Relaxed);
+            measurement.add(Self::systick_elapsed(start, end));
+
+            Self::irq_latency_hold().await;
+        }
+
+        pac::IO_BANK0
+            .int_proc(0)
+            .inte(input / 8)
+            .modify(|w| w.set_edge_high(input % 8, false));
+        Self::restore_vtor(vtor);
+
+        measurement.result(Self::cycles_to_ns)
+    }
+
+    // Holds GPIO 2 high for a while after an edge, then lowers it and the
+    // input, ready for the next.
+    async fn irq_latency_hold() {
+        Timer::after_micros(HOLD_US).await;
+        Self::sio_write(GPIO_OUT_CLR, GPIO2_MASK | INPUT_MASK);
+        Timer::after_micros(HOLD_US).await;
+    }
+
+    // Copies the vector table to RAM_VECTORS, with IO_IRQ_BANK0's entry
+    // replaced by raw_irq_handler(), and switches VTOR to it.  Returns the
+    // previous VTOR, for restore_vtor().
+    fn install_raw_irq_handler() -> u32 {
+        let entry = 16 + Interrupt::IO_IRQ_BANK0 as usize;
+
+        cortex_m::interrupt::free(|_| {
+            // Safety: Only this core's SCB is used, with interrupts
+            // disabled, and RAM_VECTORS is only referenced here.  If the
+            // current table is shorter than VECTORS, the extra entries are
+            // read from flash, and are for IRQs that never fire.
+            unsafe {
+                let scb = &cortex_m::Peripherals::steal().SCB;
+                let vtor = scb.vtor.read();
+                let vectors = &mut *core::ptr::addr_of_mut!(RAM_VECTORS);
+                let current = vtor as *const usize;
+                for (ii, vector) in vectors.0.iter_mut().enumerate() {
+                    *vector = core::ptr::read_volatile(current.add(ii));
+                }
+                vectors.0[entry] = Self::raw_irq_handler as extern "C" fn() as usize;
+
+                cortex_m::asm::dsb();
+                scb.vtor.write(vectors as *const VectorTable as u32);
+                cortex_m::asm::dsb();
+                cortex_m::asm::isb();
+                vtor
+            }
+        })
+    }
+
+    // Switches VTOR back to the table install_raw_irq_handler() replaced.
+    fn restore_vtor(vtor: u32) {
+        // Safety: vtor was this core's table, before
+        // install_raw_irq_handler().
+        unsafe {
+            cortex_m::Peripherals::steal().SCB.vtor.write(vtor);
+        }
+        cortex_m::asm::dsb();
+        cortex_m::asm::isb();
+    }
+
+    // IO_IRQ_BANK0's handler while the raw version runs.  Sets GPIO 2 first,
+    // then takes a SysTick reading and acknowledges the edge.
+    extern "C" fn raw_irq_handler() {
+        Self::sio_write(GPIO_OUT_SET, GPIO2_MASK);
+        RAW_SYSTICK.store(Self::systick_now(), Ordering::Relaxed);
+
+        let input = INPUT_PIN as usize;
+        pac::IO_BANK0
+            .intr(input / 8)
+            .write(|w| w.set_edge_high(input % 8, true));
+        RAW_EDGE.store(true, Ordering::Release);
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// LED heartbeat, enabled by the led-heartbeat feature, which blinks the
+// onboard LED at about 1Hz while any test runs, to show at a glance that a
+// build is alive, without a scope or probe.
+//
+// Most tests never yield, so a task on the main executor would never run.
+// Instead the blink task runs on an interrupt executor, on SWI_IRQ_1 at the
+// lowest interrupt priority, so it preempts the test, for a few
+// microseconds per toggle, every HALF_PERIOD.  That disturbs any period it
+// lands in, so don't use it when measuring jitter.  Tests which disable
+// interrupts, such as the soak test's bursts, delay the toggles until they
+// re-enable them, and a test which disables interrupts for good stops the
+// blinking, which is itself a useful sign.
+//
+// The onboard LED is on GPIO 25 on the Pico and Pico 2.  On the Pico W and
+// Pico 2 W it's on the CYW43 wireless chip, so this does nothing but log
+// that.
+
+use defmt::{info, warn};
+use embassy_executor::InterruptExecutor;
+use embassy_rp::gpio::{AnyPin, Level, Output};
+use embassy_rp::interrupt::{InterruptExt, Priority, SWI_IRQ_1};
+use embassy_rp::pac::interrupt;
+use embassy_time::{Duration, Timer};
+
+use crate::BOARD;
+
+const LED_PIN: u8 = 25;
+const LED_ON_CYW43: bool = cfg!(any(feature = "pico-w", feature = "pico2-w"));
+
+// Half the blink period - on for this long, then off for this long.
+const HALF_PERIOD: Duration = Duration::from_millis(500);
+
+// The lowest interrupt priority.  embassy-rp uses 2 priority bits on both
+// the RP2040 and RP2350, although the RP2350 implements 4.
+const PRIORITY: Priority = Priority::P3;
+
+#[cfg(feature = "blink-result")]
+compile_error!("led-heartbeat and blink-result both use the onboard LED");
+
+static EXECUTOR: InterruptExecutor = InterruptExecutor::new();
+
+#[interrupt]
+unsafe fn SWI_IRQ_1() {
+    // Safety: Only called from SWI_IRQ_1, which EXECUTOR is started on.
+    unsafe { EXECUTOR.on_interrupt() }
+}
+
+// Starts blinking the LED.  Must be called after embassy_rp::init().
+pub fn start() {
+    if LED_ON_CYW43 {
+        warn!(
+            ": led-heartbeat: the {} LED is on the CYW43, so isn't blinked",
+            BOARD
+        );
+        return;
+    }
+    info!(": led-heartbeat: blinking GPIO {}", LED_PIN);
+
+    // Safety: GPIO 25 isn't used by any test, and blink-result, which does
+    // use it, can't be enabled with this.
+    let led = Output::new(unsafe { AnyPin::steal(LED_PIN) }, Level::Low);
+    SWI_IRQ_1.set_priority(PRIORITY);
+    let spawner = EXECUTOR.start(SWI_IRQ_1);
+    spawner.must_spawn(heartbeat(led));
+}
+
+#[embassy_executor::task]
+async fn heartbeat(mut led: Output<'static>) -> ! {
+    loop {
+        led.toggle();
+        Timer::after(HALF_PERIOD).await;
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Locked period test - a software PLL.  Toggles GPIO 2 in windows of many
+// periods, measures each window's average period against embassy-time's
+// Instant, and adjusts the delay loop count with a proportional controller
+// to drive the error towards zero.
+//
+// The count is held as fixed point, with the fractional part dithered by
+// running that fraction of each window's periods at count + 1.  This allows
+// the average period to lock to the target between whole count steps.
+//
+// There is a slightly longer period between windows, while the measurement
+// and adjustment are done.
+
+use defmt::{info, warn};
+use embassy_pico_test::gpio_asm::DELAY_LOOP_CYCLES_PER_COUNT;
+use embassy_rp::gpio::{Level, Output};
+use embassy_time::{Duration, Instant};
+
+use crate::Test;
+use crate::measurement::Measurement;
+
+// Target period.  Note embassy-time Durations have 1us resolution.
+pub const TARGET: Duration = Duration::from_micros(20);
+
+// Approximate length of each measurement window.
+const WINDOW: Duration = Duration::from_millis(50);
+
+// Log the locked period every this many windows.
+const LOG_WINDOWS: u32 = 10;
+
+// Fractional bits in the fixed point count.
+const FRAC_BITS: u32 = 8;
+const FRAC_MASK: i64 = (1 << FRAC_BITS) - 1;
+
+// Minimum count the delay loop supports.
+const MIN_COUNT_FP: i64 = 1 << FRAC_BITS;
+
+// Largest adjustment made per window, to keep the loop stable even if it
+// starts far from the target.
+const MAX_STEP_FP: i64 = 16 << FRAC_BITS;
+
+// Estimated cycles per delay loop count (exact on the Pico).  This is only
+// used to set the controller gain, so needn't be exact on the Pico 2.
+const DELAY_LOOP_CYCLES: i64 = DELAY_LOOP_CYCLES_PER_COUNT as i64;
+
+impl Test {
+    // The controller corrects half the measured error each window, based on
+    // the estimated cost of a count.  If that estimate is out by a factor of
+    // g, the loop gain is g/2, so it converges without oscillating for any
+    // g < 2, and remains stable (if ringing) up to g < 4.
+    pub async fn locked_period(target: Duration) -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq() as i64;
+        info!("Locked period test");
+        info!(": Using GPIO 2");
+        info!(": Target period: {}us", target.as_micros());
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+
+        let target_ps = target.as_micros() as i64 * 1_000_000;
+        let periods = (WINDOW.as_micros() / target.as_micros().max(1)).max(1) as u32;
+
+        // Each count is used twice per period (once per half period).
+        let per_count_ps = 2 * DELAY_LOOP_CYCLES * 1_000_000_000_000 / speed;
+
+        // Start from the nominal count - the controller corrects from here.
+        let mut count_fp = ((target_ps << FRAC_BITS) / per_count_ps).max(MIN_COUNT_FP);
+        let mut clamped = false;
+        let mut windows = Measurement::new();
+
+        info!(": {} periods per window", periods);
+        info!(": Starting");
+        loop {
+            let count = (count_fp >> FRAC_BITS) as u32;
+            let dithered = ((periods as i64 * (count_fp & FRAC_MASK)) >> FRAC_BITS) as u32;
+
+            let start = Instant::now();
+            Self::toggle_gpio2_periods(count + 1, dithered);
+            Self::toggle_gpio2_periods(count, periods - dithered);
+            let elapsed_ps = start.elapsed().as_micros() as i64 * 1_000_000;
+            let measured_ps = elapsed_ps / periods as i64;
+
+            let error_ps = target_ps - measured_ps;
+            let step =
+                ((error_ps << FRAC_BITS) / (2 * per_count_ps)).clamp(-MAX_STEP_FP, MAX_STEP_FP);
+            count_fp += step;
+
+            if count_fp < MIN_COUNT_FP {
+                count_fp = MIN_COUNT_FP;
+                if !clamped {
+                    warn!(
+                        ": Target unachievable - clamped at minimum period ~{}ns",
+                        measured_ps / 1000
+                    );
+                    clamped = true;
+                }
+            }
+
+            windows.add((measured_ps / 1000) as u32);
+            if windows.samples() == LOG_WINDOWS {
+                info!(
+                    ": Period: {}ns, error: {}ns, count: {}.{:03}",
+                    measured_ps / 1000,
+                    error_ps / 1000,
+                    count_fp >> FRAC_BITS,
+                    ((count_fp & FRAC_MASK) * 1000) >> FRAC_BITS
+                );
+                info!(": Window periods: {}", windows.result(|ns| ns));
+                windows = Measurement::new();
+            }
+        }
+    }
+}
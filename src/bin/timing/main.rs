@@ -0,0 +1,1979 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// memory.x handling drived from embassy-rs examples.
+
+#![allow(dead_code)]
+#![allow(unused_imports)]
+#![no_std]
+#![no_main]
+
+#[cfg(feature = "rtt")]
+use {defmt_rtt as _, panic_probe as _};
+
+use core::arch::asm;
+use cortex_m::peripheral::SYST;
+use cortex_m::peripheral::syst::SystClkSource;
+use defmt::{error, info, warn};
+use embassy_executor::Spawner;
+use embassy_futures::yield_now;
+use embassy_pico_test::gpio_asm::{
+    ATOMIC_SET_CLR, DELAY_LOOP_CYCLES_PER_COUNT, GpioToggler, asm_1_cycle_r2, asm_2_cycles_add_r2,
+    asm_3_cycles_add_r2, asm_5_cycles_r2, asm_9_cycles_add_r2, asm_9_cycles_nop,
+    asm_10_cycles_add_r2, asm_10_cycles_nop, asm_load_gpio_out_addr, delay_loop, set_gpio_high,
+    set_gpio_low,
+};
+use embassy_pico_test::sio::{
+    GPIO_IN, GPIO_OUT, GPIO_OUT_CLR, GPIO_OUT_SET, GPIO_OUT_XOR, SIO_BASE,
+};
+use embassy_rp::gpio::{self, AnyPin, Drive, Input, Level, Output, Pin, Pull};
+use embassy_rp::peripherals;
+use embassy_time::{Delay, Duration, Instant, Ticker, Timer};
+use embedded_hal::delay::DelayNs;
+
+// GPIO 2's bit in the SIO GPIO registers
+const GPIO2_MASK: u32 = 1 << 2;
+
+// Output pin for the single GPIO tests, and the asm toggle loops they share
+// with other tests.  Change this if GPIO 2 is already in use.  The asm loops'
+// cycle counts are for GPIOs 0-7 - see set_gpio_high().
+const OUTPUT_PIN: u8 = 2;
+const OUTPUT_MASK: u32 = 1 << OUTPUT_PIN;
+const _: () = assert!(OUTPUT_PIN < NUM_GPIOS, "OUTPUT_PIN must be a valid GPIO");
+#[cfg(feature = "runtime-select")]
+const _: () = assert!(OUTPUT_PIN != 1, "runtime-select uses GPIO 1 for UART0 RX");
+#[cfg(feature = "serial-console")]
+const _: () = assert!(OUTPUT_PIN != 0, "serial-console uses GPIO 0 for UART0 TX");
+
+// SysTick is a 24-bit counter
+const SYSTICK_MAX: u32 = 0x00ff_ffff;
+
+// Number of user GPIOs in bank 0 (the same on the RP2040 and RP2350A)
+const NUM_GPIOS: u8 = 30;
+
+// The toggled pin's initial level, and so the direction of its first edge.
+#[cfg(not(feature = "start-high"))]
+const INITIAL_LEVEL: Level = Level::Low;
+#[cfg(feature = "start-high")]
+const INITIAL_LEVEL: Level = Level::High;
+
+// The clk_sys frequencies the asm loops' cycle counts, and the expected
+// periods, are for - the boards' defaults.
+const PICO_SPEED: u32 = 125_000_000;
+const PICO2_SPEED: u32 = 150_000_000;
+#[cfg(feature = "pico")]
+const NOMINAL_SPEED: u32 = PICO_SPEED;
+#[cfg(feature = "pico2")]
+const NOMINAL_SPEED: u32 = PICO2_SPEED;
+
+// Converts cycles to whole ns at speed, for checking the asm loops'
+// documented timings at compile time.
+const fn cycles_to_ns_at(cycles: u32, speed: u32) -> u32 {
+    (cycles as u64 * 1_000_000_000 / speed as u64) as u32
+}
+
+// The asm helpers' and loops' documented timings.  The Pico 2 loops are
+// asm_m33_toggle()'s padding plus its 3 cycles of stores and branch.
+const _: () = assert!(cycles_to_ns_at(9, PICO_SPEED) == 72);
+const _: () = assert!(cycles_to_ns_at(10, PICO_SPEED) == 80);
+const _: () = assert!(cycles_to_ns_at(25, PICO_SPEED) == 200);
+const _: () = assert!(cycles_to_ns_at(6, PICO_SPEED) == 48);
+const _: () = assert!(cycles_to_ns_at(15, PICO2_SPEED) == 100);
+const _: () = assert!(cycles_to_ns_at(14 + 13 + 3, PICO2_SPEED) == 200);
+const _: () = assert!(cycles_to_ns_at(5 + 4 + 3, PICO2_SPEED) == 80);
+const _: () = assert!(cycles_to_ns_at(3, PICO2_SPEED) == 20);
+
+// The W boards have the same chips as the Pico and Pico 2, so their
+// features enable those, and only the board's name differs here.
+#[cfg(all(feature = "pico", not(feature = "pico-w")))]
+const BOARD: &str = "Pico";
+#[cfg(feature = "pico-w")]
+const BOARD: &str = "Pico W";
+#[cfg(feature = "pico")]
+const IS_PICO2: bool = false;
+#[cfg(all(feature = "pico2", not(feature = "pico2-w")))]
+const BOARD: &str = "Pico 2";
+#[cfg(feature = "pico2-w")]
+const BOARD: &str = "Pico 2 W";
+#[cfg(feature = "pico2")]
+const IS_PICO2: bool = true;
+
+// SYSINFO CHIP_ID part numbers, for checking the build matches the chip.
+const RP2040_PART: u16 = 0x0002;
+const RP2350_PART: u16 = 0x0004;
+
+// The single GPIO tests the board can run, for messages.  Tests 26 and 27
+// need the Pico 2's DWT cycle counter.
+#[cfg(feature = "pico")]
+const AVAILABLE_TESTS: &str = "1-25 and 28";
+#[cfg(all(feature = "single-gpio", feature = "pico2"))]
+const AVAILABLE_TESTS: &str = "1-28";
+
+// Logs at info level, unless the quiet feature is enabled, which leaves only
+// the test's banner.  When quiet, the arguments are still referenced, so
+// they aren't unused.  Defined before the modules, so they can use it too.
+macro_rules! detail {
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {{
+        #[cfg(not(feature = "quiet"))]
+        defmt::info!($fmt $(, $arg)*);
+        #[cfg(feature = "quiet")]
+        {
+            $(let _ = &$arg;)*
+        }
+    }};
+}
+
+#[cfg(feature = "adc")]
+mod adc;
+#[cfg(feature = "antiphase")]
+mod antiphase;
+#[cfg(feature = "asm-readback")]
+mod asm_readback;
+#[cfg(feature = "bench-output-new")]
+mod bench_output;
+#[cfg(feature = "blink-result")]
+mod blink_result;
+#[cfg(feature = "busy-task")]
+mod busy_task;
+#[cfg(feature = "calibrate")]
+mod calibrate;
+#[cfg(feature = "calibrate-pad")]
+mod calibrate_pad;
+#[cfg(feature = "chirp")]
+mod chirp;
+#[cfg(feature = "ci-assert")]
+mod ci_assert;
+#[cfg(any(feature = "clk-xosc", feature = "clk-rosc", feature = "clock-source"))]
+mod clock_source;
+#[cfg(feature = "clocked-data")]
+mod clocked_data;
+#[cfg(feature = "serial-console")]
+mod console;
+#[cfg(feature = "dead-time")]
+mod dead_time;
+#[cfg(feature = "dma-contention")]
+mod dma_contention;
+#[cfg(feature = "drive-sweep")]
+mod drive_sweep;
+#[cfg(feature = "dry-run")]
+mod dry_run;
+#[cfg(feature = "pico2")]
+mod dwt;
+#[cfg(all(not(feature = "pico2"), any(feature = "26", feature = "27")))]
+compile_error!("Tests 26 and 27 use the DWT cycle counter, which only the Pico 2 has");
+#[cfg(feature = "dual-core-contention")]
+mod dual_core;
+#[cfg(feature = "dual-core-toggle")]
+mod dual_core_toggle;
+#[cfg(feature = "echo-gpio")]
+mod echo_gpio;
+#[cfg(feature = "glitch-inject")]
+mod glitch;
+#[cfg(feature = "halt-after")]
+mod halt_after;
+#[cfg(feature = "histogram")]
+mod histogram;
+#[cfg(feature = "i2c")]
+mod i2c;
+#[cfg(feature = "ir-nec")]
+mod ir_nec;
+#[cfg(feature = "irq-latency")]
+mod irq_latency;
+#[cfg(feature = "led-heartbeat")]
+mod led_heartbeat;
+#[cfg(feature = "locked-period")]
+mod locked_period;
+mod measurement;
+#[cfg(feature = "mem-report")]
+mod mem_report;
+#[cfg(feature = "min-period")]
+mod min_period;
+#[cfg(feature = "multi-gpio")]
+mod multi_gpio;
+#[cfg(feature = "no-clobber")]
+mod no_clobber;
+#[cfg(feature = "no-defmt")]
+mod no_defmt;
+#[cfg(feature = "output-overhead")]
+mod output_overhead;
+#[cfg(feature = "pad-latency")]
+mod pad_latency;
+#[cfg(feature = "pad-sweep")]
+mod pad_sweep;
+#[cfg(feature = "pattern")]
+mod pattern;
+#[cfg(feature = "measure")]
+mod period_measure;
+#[cfg(feature = "pio")]
+mod pio;
+#[cfg(any(feature = "toggle-in-flash", feature = "toggle-in-ram"))]
+mod placement;
+#[cfg(feature = "pre-test-idle")]
+mod pre_test_idle;
+#[cfg(feature = "prop-delay")]
+mod prop_delay;
+#[cfg(feature = "pulse-train")]
+mod pulse_train;
+#[cfg(feature = "pwm")]
+mod pwm;
+#[cfg(feature = "pwm-pair")]
+mod pwm_pair;
+#[cfg(feature = "remapped")]
+mod remapped;
+#[cfg(any(feature = "serial-report", feature = "serial-console"))]
+mod report_kv;
+#[cfg(feature = "reset-state")]
+mod reset_state;
+#[cfg(feature = "result-log")]
+mod result_log;
+#[cfg(feature = "rise-time")]
+mod rise_time;
+#[cfg(feature = "runtime-select")]
+mod selector;
+#[cfg(feature = "self-test")]
+mod self_test;
+#[cfg(feature = "soak")]
+mod soak;
+#[cfg(feature = "soft-pwm")]
+mod soft_pwm;
+#[cfg(feature = "spi")]
+mod spi;
+#[cfg(feature = "sync-pin")]
+mod sync;
+#[cfg(any(feature = "sysclk-200", feature = "sysclk-250"))]
+mod sysclk;
+#[cfg(feature = "target-period")]
+mod target_period;
+#[cfg(feature = "temperature")]
+mod temperature;
+#[cfg(feature = "test-sweep")]
+mod test_sweep;
+#[cfg(feature = "timer-drift")]
+mod timer_drift;
+#[cfg(feature = "timer-sweep")]
+mod timer_sweep;
+#[cfg(feature = "uart-frame")]
+mod uart_frame;
+#[cfg(feature = "uart-rx-jitter")]
+mod uart_rx_jitter;
+#[cfg(feature = "toggle-variable")]
+mod variable;
+#[cfg(feature = "toggle-verify")]
+mod verify;
+#[cfg(feature = "walking-ones")]
+mod walking_ones;
+#[cfg(feature = "ws2812")]
+mod ws2812;
+#[cfg(feature = "xip-thrash")]
+mod xip_thrash;
+#[cfg(feature = "xor-toggle")]
+mod xor_toggle;
+#[cfg(feature = "yield-tasks")]
+mod yield_tasks;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    // This must come before anything else touches the GPIOs.
+    #[cfg(feature = "reset-state")]
+    let reset_state = Test::capture_reset_state();
+
+    // Paint the stack before anything else uses it, for the memory report.
+    #[cfg(feature = "mem-report")]
+    Test::paint_stack();
+
+    // Get test type
+    let test_type = TestType::get();
+
+    detail!("embassy-pico-test");
+    detail!(
+        ": Git hash: {}, built: {}",
+        env!("GIT_HASH"),
+        env!("BUILD_TIME")
+    );
+    detail!(": Features: {}", env!("BUILD_FEATURES"));
+
+    // Before anything whose timing depends on the chip.
+    Test::check_chip();
+
+    // Before the test, so it's run whichever is selected.
+    #[cfg(feature = "self-test")]
+    Test::self_test();
+
+    match test_type {
+        #[cfg(feature = "single-gpio")]
+        TestType::SingleGpio => {
+            #[allow(unused_mut)]
+            let mut p = Test::init();
+            #[cfg(not(feature = "runtime-select"))]
+            let test_num = TestNum::get();
+            #[cfg(feature = "runtime-select")]
+            let test_num = Some(
+                selector::TestSelector::new(&mut p.UART0, &mut p.PIN_1).select(TestNum::get()),
+            );
+            match test_num {
+                Some(test_num) => Test::single_gpio(p, test_num).await,
+                // build.rs requires a test number without runtime-select,
+                // and the selector always returns one.
+                None => {
+                    error!(
+                        "No test number selected - enable one of features {}",
+                        AVAILABLE_TESTS
+                    );
+                    Test::idle()
+                }
+            }
+        }
+        #[cfg(feature = "walking-ones")]
+        TestType::WalkingOnes => {
+            Test::walking_ones(
+                walking_ones::BASE,
+                walking_ones::WIDTH,
+                walking_ones::SCAN_PERIOD,
+            )
+            .await
+        }
+        #[cfg(feature = "blink-result")]
+        TestType::BlinkResult => Test::blink_result_test(blink_result::VALUE).await,
+        #[cfg(feature = "uart-rx-jitter")]
+        TestType::UartRxJitter => Test::toggle_with_uart_rx(uart_rx_jitter::BAUD).await,
+        #[cfg(feature = "calibrate")]
+        TestType::Calibrate => Test::calibrate_and_toggle(calibrate::TARGET).await,
+        #[cfg(feature = "reset-state")]
+        TestType::ResetState => Test::reset_state(reset_state).await,
+        #[cfg(feature = "locked-period")]
+        TestType::LockedPeriod => Test::locked_period(locked_period::TARGET).await,
+        #[cfg(feature = "remapped")]
+        TestType::Remapped => Test::toggle_remapped(remapped::PIN).await,
+        #[cfg(feature = "ir-nec")]
+        TestType::IrNec => Test::ir_nec(ir_nec::ADDRESS, ir_nec::COMMAND).await,
+        #[cfg(feature = "clocked-data")]
+        TestType::ClockedData => {
+            Test::clocked_data(
+                clocked_data::BIT_RATE,
+                clocked_data::DATA,
+                clocked_data::SETUP_EDGE,
+            )
+            .await
+        }
+        #[cfg(feature = "min-period")]
+        TestType::MinPeriod => {
+            Test::report_min_period().await;
+        }
+        #[cfg(feature = "ws2812")]
+        TestType::Ws2812 => Test::ws2812(ws2812::COLORS).await,
+        #[cfg(feature = "prop-delay")]
+        TestType::PropDelay => {
+            Test::prop_delay(prop_delay::DRIVE_PIN, prop_delay::SENSE_PIN).await;
+        }
+        #[cfg(any(feature = "toggle-in-flash", feature = "toggle-in-ram"))]
+        TestType::TogglePlacement => Test::toggle_placement().await,
+        #[cfg(feature = "dual-core-contention")]
+        TestType::DualCoreContention => Test::dual_core_contention().await,
+        #[cfg(feature = "toggle-verify")]
+        TestType::ToggleVerify => Test::toggle_with_verify(verify::PERIOD).await,
+        #[cfg(feature = "pulse-train")]
+        TestType::PulseTrain => Test::pulse_train(pulse_train::COUNT, pulse_train::FREQ).await,
+        #[cfg(feature = "rise-time")]
+        TestType::RiseTime => Test::rise_time().await,
+        #[cfg(feature = "xip-thrash")]
+        TestType::XipThrash => Test::irq_jitter_under_xip_thrash().await,
+        #[cfg(feature = "pattern")]
+        TestType::Pattern => Test::play_pattern().await,
+        #[cfg(feature = "no-clobber")]
+        TestType::NoClobber => Test::verify_no_clobber().await,
+        #[cfg(feature = "bench-output-new")]
+        TestType::BenchOutputNew => {
+            Test::bench_output_new(bench_output::ITERATIONS).await;
+        }
+        #[cfg(feature = "glitch-inject")]
+        TestType::GlitchInject => {
+            Test::glitch_inject(glitch::PERIOD, glitch::GLITCH_CYCLES, glitch::EVERY_N).await
+        }
+        #[cfg(feature = "dry-run")]
+        TestType::DryRun => Test::dry_run(),
+        #[cfg(feature = "timer-sweep")]
+        TestType::TimerSweep => {
+            Test::timer_accuracy_sweep().await;
+        }
+        #[cfg(feature = "pwm-pair")]
+        TestType::PwmPair => Test::pwm_pair(pwm_pair::PERIOD).await,
+        #[cfg(feature = "toggle-variable")]
+        TestType::ToggleVariable => Test::toggle_variable(variable::PERIOD).await,
+        #[cfg(feature = "yield-tasks")]
+        TestType::YieldTasks => Test::toggle_yield_with_tasks(yield_tasks::TASKS).await,
+        #[cfg(feature = "multi-gpio")]
+        TestType::MultiGpio => Test::multi_gpio(multi_gpio::PINS, multi_gpio::COUNT).await,
+        #[cfg(feature = "pwm")]
+        TestType::Pwm => Test::pwm(pwm::FREQUENCY).await,
+        #[cfg(feature = "pio")]
+        TestType::Pio => Test::pio(pio::DELAY, pio::DIVIDER).await,
+        #[cfg(feature = "test-sweep")]
+        TestType::TestSweep => Test::test_sweep().await,
+        #[cfg(feature = "echo-gpio")]
+        TestType::EchoGpio => Test::echo_gpio(echo_gpio::MODE).await,
+        #[cfg(feature = "dual-core-toggle")]
+        TestType::DualCoreToggle => Test::dual_core_toggle().await,
+        #[cfg(feature = "target-period")]
+        TestType::TargetPeriod => Test::target_period().await,
+        #[cfg(feature = "irq-latency")]
+        TestType::IrqLatency => Test::irq_latency().await,
+        #[cfg(feature = "soft-pwm")]
+        TestType::SoftPwm => Test::soft_pwm().await,
+        #[cfg(feature = "spi")]
+        TestType::Spi => Test::spi(spi::FREQUENCY).await,
+        #[cfg(feature = "calibrate-pad")]
+        TestType::CalibratePad => Test::calibrate_pad(calibrate_pad::TARGET_NS).await,
+        #[cfg(feature = "output-overhead")]
+        TestType::OutputOverhead => Test::output_overhead().await,
+        #[cfg(feature = "drive-sweep")]
+        TestType::DriveSweep => Test::drive_sweep().await,
+        #[cfg(feature = "asm-readback")]
+        TestType::AsmReadback => Test::asm_readback().await,
+        #[cfg(feature = "adc")]
+        TestType::Adc => Test::adc().await,
+        #[cfg(feature = "timer-drift")]
+        TestType::TimerDrift => Test::timer_drift().await,
+        #[cfg(feature = "xor-toggle")]
+        TestType::XorToggle => Test::xor_toggle().await,
+        #[cfg(feature = "antiphase")]
+        TestType::Antiphase => Test::antiphase().await,
+        #[cfg(feature = "dma-contention")]
+        TestType::DmaContention => Test::dma_contention().await,
+        #[cfg(feature = "pad-sweep")]
+        TestType::PadSweep => Test::pad_sweep().await,
+        #[cfg(feature = "soak")]
+        TestType::Soak => Test::soak().await,
+        #[cfg(feature = "clock-source")]
+        TestType::ClockSource => Test::clock_source().await,
+        #[cfg(feature = "i2c")]
+        TestType::I2c => Test::i2c(i2c::FREQUENCY).await,
+        #[cfg(feature = "chirp")]
+        TestType::Chirp => Test::chirp().await,
+        #[cfg(feature = "uart-frame")]
+        TestType::UartFrame => Test::uart_frame().await,
+        #[cfg(feature = "pad-latency")]
+        TestType::PadLatency => Test::pad_latency().await,
+        #[cfg(feature = "dead-time")]
+        TestType::DeadTime => Test::dead_time().await,
+        #[cfg(feature = "busy-task")]
+        TestType::BusyTask => Test::busy_task().await,
+    }
+
+    // Only reached by tests which return.
+    #[cfg(feature = "mem-report")]
+    #[allow(unreachable_code)]
+    Test::log_memory_usage();
+}
+
+// With start-high, the pin is driven low for one pause before the loop, so
+// the first edge is high to low, and the loop, so the period and duty, is
+// unchanged.
+//
+// Takes a single pause for both halves of the period, or separate high and
+// low pauses.
+//
+// With test-sweep, the loop instead ends once the sweep's per-test duration
+// has passed, and with halt-after, once its number of periods is done.
+macro_rules! single_gpio {
+    ($pause:block, $pin:expr) => {
+        single_gpio!($pause, $pause, $pin)
+    };
+    ($high_pause:block, $low_pause:block, $pin:expr) => {
+        {
+            detail!(": Starting");
+            #[cfg(feature = "start-high")]
+            {
+                $pin.set_low();
+                $low_pause
+            }
+            #[cfg(feature = "measure")]
+            let mut measure = period_measure::PeriodMeasure::new();
+            #[cfg(feature = "histogram")]
+            let mut histogram = histogram::Histogram::new();
+            #[cfg(feature = "test-sweep")]
+            let mut sweep = test_sweep::SweepTimer::new(test_sweep::DURATION);
+            #[cfg(feature = "halt-after")]
+            let mut halt = halt_after::HaltAfter::new();
+            loop {
+                $pin.set_high();
+                $high_pause
+                $pin.set_low();
+                $low_pause
+                #[cfg(feature = "measure")]
+                measure.period();
+                #[cfg(feature = "histogram")]
+                histogram.period();
+                #[cfg(feature = "test-sweep")]
+                if sweep.expired() {
+                    break;
+                }
+                #[cfg(feature = "halt-after")]
+                if halt.period() {
+                    break;
+                }
+            }
+        }
+    };
+}
+
+struct Test {}
+
+impl Test {
+    async fn single_gpio(_p: embassy_rp::Peripherals, test_num: TestNum) -> ! {
+        let mut output = Self::single_gpio_output();
+        let info = test_num.info();
+        info!("Single GPIO Timing test #{}", test_num as i32);
+        detail!(": {}", info.description);
+        if let Some(period_ns) = info.period_ns {
+            detail!(": Expected period: {}ns", period_ns);
+        }
+        #[cfg(feature = "measure")]
+        period_measure::log_test(test_num as u8, info.period_ns);
+        #[cfg(feature = "ci-assert")]
+        ci_assert::start(test_num as u8, info);
+        Self::run_single_gpio(&mut output, test_num).await;
+
+        // Only reached with halt-after, as test-sweep doesn't call this.
+        #[cfg(feature = "halt-after")]
+        {
+            output.set_low();
+            info!(": Done - {} periods", halt_after::PERIODS);
+            #[cfg(feature = "serial-console")]
+            console::reply(format_args!("DONE periods={}", halt_after::PERIODS));
+        }
+        Self::idle()
+    }
+
+    // Logs the clock speed and output pin, and creates the output for the
+    // single GPIO tests.
+    fn single_gpio_output() -> Output<'static> {
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        detail!("{} clock speed: {} Hz", BOARD, speed);
+        if speed != NOMINAL_SPEED {
+            warn!(
+                ": Not the default {} Hz - asm tests' periods scale with clk_sys",
+                NOMINAL_SPEED
+            );
+        }
+        detail!(": Using GPIO {}", OUTPUT_PIN);
+        if OUTPUT_PIN > 7 {
+            warn!(": asm tests are a cycle longer per edge on GPIOs above 7");
+        }
+        if !ATOMIC_SET_CLR {
+            warn!(": raw-gpio-out: asm tests store to GPIO_OUT, driving other outputs low");
+        }
+
+        // Safety: The typed pin peripherals aren't used by these tests, so
+        // the pin is only instantiated once.
+        let pin = unsafe { AnyPin::steal(OUTPUT_PIN) };
+        let output = Output::new(pin, INITIAL_LEVEL);
+        match INITIAL_LEVEL {
+            Level::Low => detail!(": Starts low, first edge rising"),
+            Level::High => detail!(": Starts high, first edge falling"),
+        }
+        output
+    }
+
+    // Runs single GPIO test test_num on output.  Never returns, except with
+    // test-sweep, where the loops end after the sweep's per-test duration,
+    // and halt-after, where they end after its number of periods.  The asm
+    // tests can't end, so test-sweep runs bounded versions of them instead,
+    // as does this with halt-after.
+    async fn run_single_gpio(output: &mut Output<'_>, test_num: TestNum) {
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        #[cfg(feature = "halt-after")]
+        if test_num.is_asm() {
+            Self::asm_toggle_bounded(output, test_num, |_| halt_after::PERIODS);
+            return;
+        }
+        match test_num {
+            TestNum::T1 => single_gpio!({ Timer::after_micros(100).await }, output),
+            TestNum::T2 => single_gpio!({ Timer::after_micros(10).await }, output),
+            TestNum::T3 => single_gpio!({ Timer::after_micros(1).await }, output),
+            TestNum::T4 => single_gpio!({ Delay.delay_us(100) }, output),
+            TestNum::T5 => single_gpio!({ Delay.delay_us(10) }, output),
+            TestNum::T6 => single_gpio!({ Delay.delay_us(2) }, output),
+            TestNum::T7 => single_gpio!({ Delay.delay_us(1) }, output),
+            TestNum::T8 => single_gpio!({ Delay.delay_ns(100) }, output),
+            TestNum::T9 => single_gpio!(
+                {
+                    Delay.delay_us(100);
+                    yield_now().await
+                },
+                output
+            ),
+            TestNum::T10 => single_gpio!(
+                {
+                    Delay.delay_us(10);
+                    yield_now().await
+                },
+                output
+            ),
+            TestNum::T11 => single_gpio!(
+                {
+                    Delay.delay_us(1);
+                    yield_now().await
+                },
+                output
+            ),
+            TestNum::T12 => single_gpio!({ cortex_m::asm::delay(2) }, output),
+            TestNum::T13 => {
+                single_gpio!({}, output);
+            }
+            TestNum::T14 => {
+                detail!(": Using same assembly for both Pico and Pico 2");
+                Self::log_asm_periods(25, 15);
+                detail!(": Starting");
+                Self::asm_toggle_period_200ns_pico();
+            }
+            TestNum::T15 => {
+                detail!(": Using Pico and Pico 2 specific assembly");
+                detail!(": Starting");
+                Self::asm_toggle_period_200ns();
+            }
+            TestNum::T16 => {
+                detail!(": Using Pico and Pico 2 specific assembly");
+                detail!(": Low drive strength (2mA)");
+                detail!(": Starting");
+                output.set_drive_strength(Drive::_2mA);
+                Self::asm_toggle_period_80ns();
+            }
+            TestNum::T17 => {
+                detail!(": Using Pico and Pico 2 specific assembly");
+                Self::log_asm_periods(6, 3);
+                detail!(": Low drive strength (2mA)");
+                detail!(": Starting");
+                output.set_drive_strength(Drive::_2mA);
+                Self::asm_toggle_period_min();
+            }
+            TestNum::T18 => {
+                detail!(": Using Pico and Pico 2 specific assembly");
+                Self::log_asm_periods(6, 3);
+                detail!(": High drive strength (12mA)");
+                detail!(": Starting");
+                output.set_drive_strength(Drive::_12mA);
+                Self::asm_toggle_period_min();
+            }
+            TestNum::T19 => {
+                detail!(": Using Pico and Pico 2 specific assembly");
+                detail!(": Starting");
+                let mut expires = Instant::now();
+                let _10us = Duration::from_micros(10);
+                #[cfg(feature = "start-high")]
+                {
+                    output.set_low();
+                    expires += _10us;
+                    Timer::at(expires).await;
+                }
+                #[cfg(feature = "test-sweep")]
+                let mut sweep = test_sweep::SweepTimer::new(test_sweep::DURATION);
+                #[cfg(feature = "halt-after")]
+                let mut halt = halt_after::HaltAfter::new();
+                loop {
+                    output.set_high();
+                    expires += _10us;
+                    Timer::at(expires).await;
+                    output.set_low();
+                    expires += _10us;
+                    Timer::at(expires).await;
+                    #[cfg(feature = "test-sweep")]
+                    if sweep.expired() {
+                        break;
+                    }
+                    #[cfg(feature = "halt-after")]
+                    if halt.period() {
+                        break;
+                    }
+                }
+            }
+            TestNum::T20 => {
+                let half_cycles = speed / 2_000_000;
+                single_gpio!({ cortex_m::asm::delay(half_cycles) }, output)
+            }
+            TestNum::T21 => {
+                // Each step is held for ~100ms.
+                const STEPS_US: [u32; 7] = [2, 5, 10, 20, 50, 100, 200];
+                detail!(": Starting");
+                #[cfg(feature = "start-high")]
+                {
+                    output.set_low();
+                    Delay.delay_us(STEPS_US[0] / 2);
+                }
+                // With test-sweep, only whole sweeps are run, but halt-after
+                // counts every period, so can end one part way through.
+                #[cfg(feature = "test-sweep")]
+                let mut sweep = test_sweep::SweepTimer::new(test_sweep::DURATION);
+                #[cfg(feature = "halt-after")]
+                let mut halt = halt_after::HaltAfter::new();
+                // The label is only used with halt-after.
+                #[cfg_attr(not(feature = "halt-after"), allow(unused_labels))]
+                'test: loop {
+                    for period_us in STEPS_US {
+                        for _ in 0..100_000 / period_us {
+                            output.set_high();
+                            Delay.delay_us(period_us / 2);
+                            output.set_low();
+                            Delay.delay_us(period_us / 2);
+                            #[cfg(feature = "halt-after")]
+                            if halt.period() {
+                                break 'test;
+                            }
+                        }
+                    }
+                    #[cfg(feature = "test-sweep")]
+                    if sweep.expired() {
+                        break;
+                    }
+                }
+            }
+            TestNum::T22 => {
+                detail!(": Starting");
+                #[cfg(feature = "test-sweep")]
+                let mut sweep = test_sweep::SweepTimer::new(test_sweep::DURATION);
+                // halt-after counts the bursts' periods.
+                #[cfg(feature = "halt-after")]
+                let mut halt = halt_after::HaltAfter::new();
+                // The label is only used with halt-after.
+                #[cfg_attr(not(feature = "halt-after"), allow(unused_labels))]
+                'test: loop {
+                    // The idle comes first, so with start-high the burst's
+                    // first edge is still rising.
+                    output.set_low();
+                    Delay.delay_us(100);
+                    for _ in 0..10 {
+                        output.set_high();
+                        Delay.delay_us(1);
+                        output.set_low();
+                        Delay.delay_us(1);
+                        #[cfg(feature = "halt-after")]
+                        if halt.period() {
+                            break 'test;
+                        }
+                    }
+                    #[cfg(feature = "test-sweep")]
+                    if sweep.expired() {
+                        break;
+                    }
+                }
+            }
+            TestNum::T23 => single_gpio!({ Delay.delay_us(5) }, { Delay.delay_us(15) }, output),
+            TestNum::T24 => {
+                let mut ticker = Ticker::every(Duration::from_micros(10));
+                single_gpio!({ ticker.next().await }, output)
+            }
+            TestNum::T25 => {
+                // Each toggle flips from the initial level, so start-high
+                // needs no special handling.
+                detail!(": Starting");
+                #[cfg(feature = "test-sweep")]
+                let mut sweep = test_sweep::SweepTimer::new(test_sweep::DURATION);
+                // halt-after counts each falling edge as the end of a period.
+                #[cfg(feature = "halt-after")]
+                let mut halt = halt_after::HaltAfter::new();
+                loop {
+                    output.toggle();
+                    #[cfg(feature = "test-sweep")]
+                    if sweep.expired() {
+                        break;
+                    }
+                    #[cfg(feature = "halt-after")]
+                    if output.is_set_low() && halt.period() {
+                        break;
+                    }
+                }
+            }
+            #[cfg(feature = "pico2")]
+            TestNum::T26 => {
+                // Converted once, as the conversion would take most of the
+                // 100ns.
+                let half_cycles = Self::dwt_ns_to_cycles(100);
+                detail!(": {} cycles per half period", half_cycles);
+                Self::dwt_enable();
+                single_gpio!({ Self::dwt_delay_cycles(half_cycles) }, output)
+            }
+            #[cfg(feature = "pico2")]
+            TestNum::T27 => {
+                Self::dwt_enable();
+                single_gpio!({ Self::dwt_delay_ns(10_000) }, output)
+            }
+            TestNum::T28 => {
+                // Periods per burst, each half period's cycles, and the low
+                // gap between bursts.
+                const BURST_PERIODS: u32 = 8;
+                const BURST_HIGH_CYCLES: u32 = 10;
+                const BURST_LOW_CYCLES: u32 = 10;
+                const BURST_GAP_US: u64 = 100;
+                detail!(
+                    ": {} periods of {}ns ({} cycles high, {} low), then {}us low",
+                    BURST_PERIODS,
+                    Self::cycles_to_ns(BURST_HIGH_CYCLES + BURST_LOW_CYCLES),
+                    BURST_HIGH_CYCLES,
+                    BURST_LOW_CYCLES,
+                    BURST_GAP_US
+                );
+                detail!(": Starting");
+                let toggler = GpioToggler::new(OUTPUT_MASK);
+                #[cfg(feature = "test-sweep")]
+                let mut sweep = test_sweep::SweepTimer::new(test_sweep::DURATION);
+                // halt-after counts whole bursts' periods, so rounds up to a
+                // whole burst.
+                #[cfg(feature = "halt-after")]
+                let mut halt = halt_after::HaltAfter::new();
+                loop {
+                    // The gap comes first, so with start-high the burst's
+                    // first edge is still rising.
+                    output.set_low();
+                    Timer::after_micros(BURST_GAP_US).await;
+                    // Interrupts are disabled, so nothing stretches the burst.
+                    cortex_m::interrupt::free(|_| {
+                        toggler
+                            .toggle_n_cycles::<BURST_HIGH_CYCLES, BURST_LOW_CYCLES>(BURST_PERIODS)
+                    });
+                    #[cfg(feature = "test-sweep")]
+                    if sweep.expired() {
+                        break;
+                    }
+                    #[cfg(feature = "halt-after")]
+                    if halt.periods(BURST_PERIODS) {
+                        break;
+                    }
+                }
+            }
+            // Only reachable with runtime-select, as otherwise the features
+            // are a compile error on the Pico.
+            #[cfg(not(feature = "pico2"))]
+            TestNum::T26 | TestNum::T27 => {
+                error!(
+                    ": Test {} needs the Pico 2's DWT cycle counter - the {} can run tests {}",
+                    test_num as i32, BOARD, AVAILABLE_TESTS
+                );
+                Self::idle()
+            }
+        }
+    }
+
+    // Runs a bounded version of asm test test_num, one of tests 14-18, which
+    // never return, for test-sweep and halt-after.  It sets the test's drive
+    // strength, then runs the library's GpioToggler::toggle_n_cycles() for
+    // the number of periods returned by periods, from the period in cycles.
+    // Its half periods are chosen to give each test's period, but as it
+    // toggles using GPIO_OUT_SET and GPIO_OUT_CLR with a loop counter in the
+    // low half, the duty cycle differs slightly.  On the Pico 2, the counter
+    // makes the minimum period 4 cycles, rather than 3.
+    #[cfg(any(feature = "test-sweep", feature = "halt-after"))]
+    fn asm_toggle_bounded(
+        output: &mut Output<'_>,
+        test_num: TestNum,
+        periods: impl FnOnce(u32) -> u32,
+    ) {
+        match test_num {
+            TestNum::T14 => {
+                if IS_PICO2 {
+                    Self::asm_toggle_n::<8, 7>(periods);
+                } else {
+                    Self::asm_toggle_n::<12, 13>(periods);
+                }
+            }
+            TestNum::T15 => {
+                if IS_PICO2 {
+                    Self::asm_toggle_n::<15, 15>(periods);
+                } else {
+                    Self::asm_toggle_n::<12, 13>(periods);
+                }
+            }
+            TestNum::T16 => {
+                output.set_drive_strength(Drive::_2mA);
+                if IS_PICO2 {
+                    Self::asm_toggle_n::<6, 6>(periods);
+                } else {
+                    Self::asm_toggle_n::<5, 5>(periods);
+                }
+            }
+            TestNum::T17 | TestNum::T18 => {
+                match test_num {
+                    TestNum::T17 => output.set_drive_strength(Drive::_2mA),
+                    _ => output.set_drive_strength(Drive::_12mA),
+                }
+                // cfg'd rather than IS_PICO2, as the Pico 2's low half is
+                // shorter than the Pico's minimum, so won't compile for it.
+                #[cfg(feature = "pico")]
+                Self::asm_toggle_n::<2, 4>(periods);
+                #[cfg(feature = "pico2")]
+                Self::asm_toggle_n::<1, 3>(periods);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    // Runs GpioToggler::toggle_n_cycles() for the number of periods returned
+    // by periods, from the period in cycles.
+    #[cfg(any(feature = "test-sweep", feature = "halt-after"))]
+    fn asm_toggle_n<const HIGH_CYCLES: u32, const LOW_CYCLES: u32>(
+        periods: impl FnOnce(u32) -> u32,
+    ) {
+        let period_cycles = HIGH_CYCLES + LOW_CYCLES;
+        let periods = periods(period_cycles);
+        detail!(
+            ": Bounded asm loop, {} cycles ({}ns) for {} periods",
+            period_cycles,
+            Self::cycles_to_ns(period_cycles),
+            periods
+        );
+        detail!(": Starting");
+        GpioToggler::new(OUTPUT_MASK).toggle_n_cycles::<HIGH_CYCLES, LOW_CYCLES>(periods);
+    }
+
+    // This function takes exactly 200ns to toggle OUTPUT_PIN twice.  It does 6
+    // cycles of work - the two edges and the branch - and 19 cycles of
+    // no-ops, for a total of 25 clock cycles.  The Pico does 125MHz, so 25
+    // cycles is 200ns.
+    //
+    // However, this takes 100ns on the Pico 2, as it used only 15 cycles, at
+    // 150Mhz.  This is because the branch takes 1 fewer cycle, and some of
+    // the other instructions (likely nops) are being executed in parallel.
+    fn asm_toggle_period_200ns_pico() -> ! {
+        // Safety: r0 holds GPIO_OUT throughout, as the loop is only the asm
+        // helpers, none of which change it.
+        unsafe {
+            // Load register r0 with the GPIO_OUT register address
+            asm_load_gpio_out_addr();
+
+            // As in single_gpio!, with start-high, drive one low half period
+            // before the loop, so the first edge is falling.
+            #[cfg(feature = "start-high")]
+            {
+                set_gpio_low::<OUTPUT_PIN>();
+                asm_9_cycles_nop();
+            }
+
+            // Loop around, setting the pin high, pausing 10 clock cycles, then
+            // setting it low, pausing 9 clock cycles.
+            loop {
+                set_gpio_high::<OUTPUT_PIN>();
+                asm_10_cycles_nop();
+                set_gpio_low::<OUTPUT_PIN>();
+                asm_9_cycles_nop();
+            }
+        }
+    }
+
+    // This function takes exactly 200ns, 25 cycles, to toggle OUTPUT_PIN
+    // twice on the Pico, padding with adds to a register rather than nops.
+    // The Pico 2 has its own version, below.
+    #[cfg(feature = "pico")]
+    fn asm_toggle_period_200ns() -> ! {
+        // Safety: r0 holds GPIO_OUT throughout, as the loop is only the asm
+        // helpers, none of which change it.
+        unsafe {
+            // Load register r0 with the GPIO_OUT register address
+            asm_load_gpio_out_addr();
+
+            #[cfg(feature = "start-high")]
+            {
+                set_gpio_low::<OUTPUT_PIN>();
+                asm_9_cycles_add_r2();
+            }
+
+            // Loop around, setting the pin high, pausing 10 clock cycles, then
+            // setting it low, pausing 9 clock cycles.
+            loop {
+                set_gpio_high::<OUTPUT_PIN>();
+                asm_10_cycles_add_r2();
+                set_gpio_low::<OUTPUT_PIN>();
+                asm_9_cycles_add_r2();
+            }
+        }
+    }
+
+    // 200ns is 30 cycles on the Pico 2, so each half is 15.
+    #[cfg(feature = "pico2")]
+    fn asm_toggle_period_200ns() -> ! {
+        Self::asm_m33_toggle::<14, 13>()
+    }
+
+    // Toggles OUTPUT_PIN using a period of 80ns on the Pico, which is 10
+    // cycles.
+    #[cfg(feature = "pico")]
+    fn asm_toggle_period_80ns() -> ! {
+        // Safety: r0 holds GPIO_OUT throughout, as the loop is only the asm
+        // helpers, none of which change it.
+        unsafe {
+            // Load register r0 with the GPIO_OUT register address
+            asm_load_gpio_out_addr();
+
+            #[cfg(feature = "start-high")]
+            {
+                set_gpio_low::<OUTPUT_PIN>();
+                asm_2_cycles_add_r2();
+            }
+
+            loop {
+                set_gpio_high::<OUTPUT_PIN>(); // 2 cycles
+                asm_2_cycles_add_r2();
+                set_gpio_low::<OUTPUT_PIN>(); // 2 cycles
+                asm_2_cycles_add_r2();
+            }
+        }
+    }
+
+    // 80ns is 12 cycles on the Pico 2, so each half is 6.
+    #[cfg(feature = "pico2")]
+    fn asm_toggle_period_80ns() -> ! {
+        Self::asm_m33_toggle::<5, 4>()
+    }
+
+    // Toggles OUTPUT_PIN using minimum period possible on the Pico.
+    #[cfg(feature = "pico")]
+    fn asm_toggle_period_min() -> ! {
+        // Safety: r0 holds GPIO_OUT throughout, as the loop is only the asm
+        // helpers, none of which change it.
+        unsafe {
+            // Load register r0 with the GPIO_OUT register address
+            asm_load_gpio_out_addr();
+
+            #[cfg(feature = "start-high")]
+            set_gpio_low::<OUTPUT_PIN>();
+
+            loop {
+                set_gpio_high::<OUTPUT_PIN>(); // 2 cycles
+                set_gpio_low::<OUTPUT_PIN>(); // 2 cycles
+            }
+        }
+    }
+
+    // On the Pico 2, the minimum is 3 cycles - the two stores and the
+    // branch - so the pin is only high for 1 of them.
+    #[cfg(feature = "pico2")]
+    fn asm_toggle_period_min() -> ! {
+        Self::asm_m33_toggle::<0, 0>()
+    }
+
+    // Toggles OUTPUT_PIN on the Pico 2, as a single asm block using the
+    // M33's Thumb-2 instructions, so the compiler can't add anything to the
+    // loop.  The GPIO_OUT address is loaded with movw/movt, and the high and
+    // low values with a mov each, as Thumb-2's modified immediates can encode
+    // any single bit, so there is nothing to reload in the loop.  Edges are
+    // stores of the mask to GPIO_OUT_SET and GPIO_OUT_CLR, so other GPIOs are
+    // untouched - or, with raw-gpio-out, of the mask and 0 to GPIO_OUT, as
+    // set_gpio_high() and set_gpio_low() do, taking the same cycles.
+    //
+    // Each half period is its store, then HIGH_PAD or LOW_PAD padding
+    // cycles, with the branch back taking the last cycle of the low half.
+    // The padding is a chain of dependent adds, as the M33 can dual-issue
+    // nops, so they don't reliably take a cycle each.  Each of the store,
+    // add and taken branch take a cycle, as assumed by the Pico 2 padding
+    // in the other asm tests - so a period of 200ns is 14 and 13.
+    #[cfg(feature = "pico2")]
+    fn asm_m33_toggle<const HIGH_PAD: u32, const LOW_PAD: u32>() -> ! {
+        unsafe {
+            asm!(
+                "movw r0, #{out_lo}",
+                "movt r0, #{out_hi}",
+                "mov r1, #{mask}",
+                "mov r2, #{low_value}",
+                // With start-high, drive one low half period first.
+                ".if {start_high}",
+                "str r2, [r0, #{clr}]",
+                ".rept {low} + 1",
+                "adds r3, r3, #1",
+                ".endr",
+                ".endif",
+                "2:",
+                "str r1, [r0, #{set}]",  // High
+                ".rept {high}",
+                "adds r3, r3, #1",
+                ".endr",
+                "str r2, [r0, #{clr}]",  // Low
+                ".rept {low}",
+                "adds r3, r3, #1",
+                ".endr",
+                "b 2b",
+                out_lo = const GPIO_OUT & 0xffff,
+                out_hi = const GPIO_OUT >> 16,
+                mask = const OUTPUT_MASK,
+                low_value = const if ATOMIC_SET_CLR { OUTPUT_MASK } else { 0 },
+                set = const if ATOMIC_SET_CLR { GPIO_OUT_SET - GPIO_OUT } else { 0 },
+                clr = const if ATOMIC_SET_CLR { GPIO_OUT_CLR - GPIO_OUT } else { 0 },
+                start_high = const cfg!(feature = "start-high") as u32,
+                high = const HIGH_PAD,
+                low = const LOW_PAD,
+                options(noreturn, nostack),
+            );
+        }
+    }
+
+    // Writes value to the SIO register at address reg.
+    #[inline(always)]
+    fn sio_write(reg: u32, value: u32) {
+        unsafe { core::ptr::write_volatile(reg as *mut u32, value) }
+    }
+
+    // Reads the SIO register at address reg.
+    #[inline(always)]
+    fn sio_read(reg: u32) -> u32 {
+        unsafe { core::ptr::read_volatile(reg as *const u32) }
+    }
+
+    // Reads SYSINFO's CHIP_ID register, which holds the chip's part number
+    // and revision.  SYSINFO is at the same address, with the same CHIP_ID
+    // layout, on both chips, so this reads correctly whichever board the
+    // build is for.
+    fn read_chip_id() -> embassy_rp::pac::sysinfo::regs::ChipId {
+        embassy_rp::pac::SYSINFO.chip_id().read()
+    }
+
+    // Warns if the chip isn't the one the build is for, as every cycle count
+    // and clock speed would then be wrong.
+    fn check_chip() {
+        let chip = |is_rp2350| if is_rp2350 { "RP2350" } else { "RP2040" };
+        let chip_id = Self::read_chip_id();
+        let is_rp2350 = match chip_id.part() {
+            RP2040_PART => false,
+            RP2350_PART => true,
+            _ => {
+                warn!(
+                    "Unknown chip: SYSINFO CHIP_ID {:#010x} - built for the {}, an {}",
+                    chip_id.0,
+                    BOARD,
+                    chip(IS_PICO2)
+                );
+                return;
+            }
+        };
+        if is_rp2350 == IS_PICO2 {
+            detail!(
+                ": Chip: {} revision {}",
+                chip(is_rp2350),
+                chip_id.revision()
+            );
+        } else {
+            warn!(
+                "Chip mismatch: built for the {}, an {}, but running on an {} - timings are wrong, rebuild for this board",
+                BOARD,
+                chip(IS_PICO2),
+                chip(is_rp2350)
+            );
+        }
+    }
+
+    // Toggles GPIO 2 for the given number of periods, with count iterations
+    // of delay_loop() for each half period - at least 1, even for a count of
+    // 0.  Not inlined, so that every caller gets identical timing.
+    #[inline(never)]
+    fn toggle_gpio2_periods(count: u32, periods: u32) {
+        let cycles = count * DELAY_LOOP_CYCLES_PER_COUNT;
+        for _ in 0..periods {
+            Self::sio_write(GPIO_OUT_SET, GPIO2_MASK);
+            delay_loop(cycles);
+            Self::sio_write(GPIO_OUT_CLR, GPIO2_MASK);
+            delay_loop(cycles);
+        }
+    }
+
+    // Initialises embassy, and with led-heartbeat, starts the LED blinking.
+    fn init() -> embassy_rp::Peripherals {
+        let p = Self::init_clocks();
+        #[cfg(feature = "led-heartbeat")]
+        led_heartbeat::start();
+        #[cfg(feature = "pre-test-idle")]
+        pre_test_idle::idle();
+        p
+    }
+
+    // Initialises embassy, with clk_sys overclocked by the sysclk features,
+    // or from another clock source with clk-xosc or clk-rosc.
+    fn init_clocks() -> embassy_rp::Peripherals {
+        #[cfg(any(feature = "sysclk-200", feature = "sysclk-250"))]
+        return Self::init_overclocked();
+        #[cfg(any(feature = "clk-xosc", feature = "clk-rosc"))]
+        return Self::init_clock_source();
+        #[cfg(not(any(
+            feature = "sysclk-200",
+            feature = "sysclk-250",
+            feature = "clk-xosc",
+            feature = "clk-rosc"
+        )))]
+        embassy_rp::init(Default::default())
+    }
+
+    // Does nothing, forever.  Used once a test has finished, or if it can't
+    // be run.
+    fn idle() -> ! {
+        loop {
+            cortex_m::asm::wfi();
+        }
+    }
+
+    // Starts SysTick free running from the core clock, so it can be used as
+    // a cycle counter on both the Pico and Pico 2.  It is a 24-bit down
+    // counter, so use systick_elapsed() to compute deltas.
+    fn systick_start() {
+        // Safety: Nothing else (including embassy) uses SysTick.
+        let mut syst = unsafe { cortex_m::Peripherals::steal() }.SYST;
+        syst.set_clock_source(SystClkSource::Core);
+        syst.set_reload(SYSTICK_MAX);
+        syst.clear_current();
+        syst.enable_counter();
+    }
+
+    // Reads the current SysTick value.
+    #[inline(always)]
+    fn systick_now() -> u32 {
+        SYST::get_current()
+    }
+
+    // Returns the number of cycles between two SysTick readings, handling
+    // the counter wrapping.  Only valid for intervals under 2^24 cycles -
+    // ~134ms at 125MHz, ~111ms at 150MHz, ~84ms at sysclk-200's 200MHz and
+    // ~67ms at sysclk-250's 250MHz.
+    #[inline(always)]
+    fn systick_elapsed(start: u32, end: u32) -> u32 {
+        start.wrapping_sub(end) & SYSTICK_MAX
+    }
+
+    // Returns the cycles f takes, timed with SysTick, which is (re)started
+    // from the core clock first, so needn't already be running.  This is the
+    // Pico's cycle counter, as the M0+ has no DWT, and is used on the Pico 2
+    // too, so both are measured the same way.  The wrap is handled as by
+    // systick_elapsed(), so f must take under 2^24 cycles.  The two SysTick
+    // reads are included - compare runs of different lengths to cancel them.
+    #[inline(always)]
+    fn systick_measure(f: impl FnOnce()) -> u32 {
+        Self::systick_start();
+        let start = Self::systick_now();
+        f();
+        Self::systick_elapsed(start, Self::systick_now())
+    }
+
+    // Spins until target cycles have elapsed since the start SysTick value.
+    // target must be under 2^24.
+    #[inline(always)]
+    fn wait_until_cycles(start: u32, target: u32) {
+        while Self::systick_elapsed(start, Self::systick_now()) < target {}
+    }
+
+    // Converts a number of clk_sys cycles to nanoseconds.
+    fn cycles_to_ns(cycles: u32) -> u32 {
+        let speed = embassy_rp::clocks::clk_sys_freq() as u64;
+        (cycles as u64 * 1_000_000_000 / speed) as u32
+    }
+
+    // Logs the periods of an asm loop which takes pico_cycles on the Pico
+    // and pico2_cycles on the Pico 2, marking this board's as selected.
+    // This board's is at the running clk_sys, and the other's at the clk_sys
+    // it would run at with the same features - its default, or the sysclk
+    // feature's overclock.
+    fn log_asm_periods(pico_cycles: u32, pico2_cycles: u32) {
+        #[cfg(any(feature = "sysclk-200", feature = "sysclk-250"))]
+        let (pico_speed, pico2_speed) = (sysclk::SYSCLK_HZ, sysclk::SYSCLK_HZ);
+        #[cfg(not(any(feature = "sysclk-200", feature = "sysclk-250")))]
+        let (pico_speed, pico2_speed) = (PICO_SPEED, PICO2_SPEED);
+        let (pico_ns, pico2_ns) = if IS_PICO2 {
+            (
+                cycles_to_ns_at(pico_cycles, pico_speed),
+                Self::cycles_to_ns(pico2_cycles),
+            )
+        } else {
+            (
+                Self::cycles_to_ns(pico_cycles),
+                cycles_to_ns_at(pico2_cycles, pico2_speed),
+            )
+        };
+        let selected = |is_pico2| {
+            if is_pico2 == IS_PICO2 {
+                "<== selected"
+            } else {
+                ""
+            }
+        };
+        detail!(
+            ": {}ns period using asm (Pico)    {}",
+            pico_ns,
+            selected(false)
+        );
+        detail!(
+            ": {}ns period using asm (Pico 2)  {}",
+            pico2_ns,
+            selected(true)
+        );
+    }
+}
+
+// Helper routines to get test type and number
+enum TestType {
+    #[cfg(feature = "single-gpio")]
+    SingleGpio,
+    #[cfg(feature = "walking-ones")]
+    WalkingOnes,
+    #[cfg(feature = "blink-result")]
+    BlinkResult,
+    #[cfg(feature = "uart-rx-jitter")]
+    UartRxJitter,
+    #[cfg(feature = "calibrate")]
+    Calibrate,
+    #[cfg(feature = "reset-state")]
+    ResetState,
+    #[cfg(feature = "locked-period")]
+    LockedPeriod,
+    #[cfg(feature = "remapped")]
+    Remapped,
+    #[cfg(feature = "ir-nec")]
+    IrNec,
+    #[cfg(feature = "clocked-data")]
+    ClockedData,
+    #[cfg(feature = "min-period")]
+    MinPeriod,
+    #[cfg(feature = "ws2812")]
+    Ws2812,
+    #[cfg(feature = "prop-delay")]
+    PropDelay,
+    #[cfg(any(feature = "toggle-in-flash", feature = "toggle-in-ram"))]
+    TogglePlacement,
+    #[cfg(feature = "dual-core-contention")]
+    DualCoreContention,
+    #[cfg(feature = "toggle-verify")]
+    ToggleVerify,
+    #[cfg(feature = "pulse-train")]
+    PulseTrain,
+    #[cfg(feature = "rise-time")]
+    RiseTime,
+    #[cfg(feature = "xip-thrash")]
+    XipThrash,
+    #[cfg(feature = "pattern")]
+    Pattern,
+    #[cfg(feature = "no-clobber")]
+    NoClobber,
+    #[cfg(feature = "bench-output-new")]
+    BenchOutputNew,
+    #[cfg(feature = "glitch-inject")]
+    GlitchInject,
+    #[cfg(feature = "dry-run")]
+    DryRun,
+    #[cfg(feature = "timer-sweep")]
+    TimerSweep,
+    #[cfg(feature = "pwm-pair")]
+    PwmPair,
+    #[cfg(feature = "toggle-variable")]
+    ToggleVariable,
+    #[cfg(feature = "yield-tasks")]
+    YieldTasks,
+    #[cfg(feature = "multi-gpio")]
+    MultiGpio,
+    #[cfg(feature = "pwm")]
+    Pwm,
+    #[cfg(feature = "pio")]
+    Pio,
+    #[cfg(feature = "test-sweep")]
+    TestSweep,
+    #[cfg(feature = "echo-gpio")]
+    EchoGpio,
+    #[cfg(feature = "dual-core-toggle")]
+    DualCoreToggle,
+    #[cfg(feature = "target-period")]
+    TargetPeriod,
+    #[cfg(feature = "irq-latency")]
+    IrqLatency,
+    #[cfg(feature = "soft-pwm")]
+    SoftPwm,
+    #[cfg(feature = "spi")]
+    Spi,
+    #[cfg(feature = "calibrate-pad")]
+    CalibratePad,
+    #[cfg(feature = "output-overhead")]
+    OutputOverhead,
+    #[cfg(feature = "drive-sweep")]
+    DriveSweep,
+    #[cfg(feature = "asm-readback")]
+    AsmReadback,
+    #[cfg(feature = "adc")]
+    Adc,
+    #[cfg(feature = "timer-drift")]
+    TimerDrift,
+    #[cfg(feature = "xor-toggle")]
+    XorToggle,
+    #[cfg(feature = "antiphase")]
+    Antiphase,
+    #[cfg(feature = "dma-contention")]
+    DmaContention,
+    #[cfg(feature = "pad-sweep")]
+    PadSweep,
+    #[cfg(feature = "soak")]
+    Soak,
+    #[cfg(feature = "clock-source")]
+    ClockSource,
+    #[cfg(feature = "i2c")]
+    I2c,
+    #[cfg(feature = "chirp")]
+    Chirp,
+    #[cfg(feature = "uart-frame")]
+    UartFrame,
+    #[cfg(feature = "pad-latency")]
+    PadLatency,
+    #[cfg(feature = "dead-time")]
+    DeadTime,
+    #[cfg(feature = "busy-task")]
+    BusyTask,
+}
+
+impl TestType {
+    // The number of test types enabled, which must be exactly one.  The
+    // toggle-in-flash and toggle-in-ram features are the same test type.
+    const ENABLED: usize = cfg!(feature = "single-gpio") as usize
+        + cfg!(feature = "walking-ones") as usize
+        + cfg!(feature = "blink-result") as usize
+        + cfg!(feature = "uart-rx-jitter") as usize
+        + cfg!(feature = "calibrate") as usize
+        + cfg!(feature = "reset-state") as usize
+        + cfg!(feature = "locked-period") as usize
+        + cfg!(feature = "remapped") as usize
+        + cfg!(feature = "ir-nec") as usize
+        + cfg!(feature = "clocked-data") as usize
+        + cfg!(feature = "min-period") as usize
+        + cfg!(feature = "ws2812") as usize
+        + cfg!(feature = "prop-delay") as usize
+        + cfg!(any(feature = "toggle-in-flash", feature = "toggle-in-ram")) as usize
+        + cfg!(feature = "dual-core-contention") as usize
+        + cfg!(feature = "toggle-verify") as usize
+        + cfg!(feature = "pulse-train") as usize
+        + cfg!(feature = "rise-time") as usize
+        + cfg!(feature = "xip-thrash") as usize
+        + cfg!(feature = "pattern") as usize
+        + cfg!(feature = "no-clobber") as usize
+        + cfg!(feature = "bench-output-new") as usize
+        + cfg!(feature = "glitch-inject") as usize
+        + cfg!(feature = "dry-run") as usize
+        + cfg!(feature = "timer-sweep") as usize
+        + cfg!(feature = "pwm-pair") as usize
+        + cfg!(feature = "toggle-variable") as usize
+        + cfg!(feature = "yield-tasks") as usize
+        + cfg!(feature = "multi-gpio") as usize
+        + cfg!(feature = "pwm") as usize
+        + cfg!(feature = "pio") as usize
+        + cfg!(feature = "test-sweep") as usize
+        + cfg!(feature = "echo-gpio") as usize
+        + cfg!(feature = "dual-core-toggle") as usize
+        + cfg!(feature = "target-period") as usize
+        + cfg!(feature = "irq-latency") as usize
+        + cfg!(feature = "soft-pwm") as usize
+        + cfg!(feature = "spi") as usize
+        + cfg!(feature = "calibrate-pad") as usize
+        + cfg!(feature = "output-overhead") as usize
+        + cfg!(feature = "drive-sweep") as usize
+        + cfg!(feature = "asm-readback") as usize
+        + cfg!(feature = "adc") as usize
+        + cfg!(feature = "timer-drift") as usize
+        + cfg!(feature = "xor-toggle") as usize
+        + cfg!(feature = "antiphase") as usize
+        + cfg!(feature = "dma-contention") as usize
+        + cfg!(feature = "pad-sweep") as usize
+        + cfg!(feature = "soak") as usize
+        + cfg!(feature = "clock-source") as usize
+        + cfg!(feature = "i2c") as usize
+        + cfg!(feature = "chirp") as usize
+        + cfg!(feature = "uart-frame") as usize
+        + cfg!(feature = "pad-latency") as usize
+        + cfg!(feature = "dead-time") as usize
+        + cfg!(feature = "busy-task") as usize;
+
+    fn get() -> Self {
+        #[cfg(feature = "single-gpio")]
+        return TestType::SingleGpio;
+        #[cfg(feature = "walking-ones")]
+        return TestType::WalkingOnes;
+        #[cfg(feature = "blink-result")]
+        return TestType::BlinkResult;
+        #[cfg(feature = "uart-rx-jitter")]
+        return TestType::UartRxJitter;
+        #[cfg(feature = "calibrate")]
+        return TestType::Calibrate;
+        #[cfg(feature = "reset-state")]
+        return TestType::ResetState;
+        #[cfg(feature = "locked-period")]
+        return TestType::LockedPeriod;
+        #[cfg(feature = "remapped")]
+        return TestType::Remapped;
+        #[cfg(feature = "ir-nec")]
+        return TestType::IrNec;
+        #[cfg(feature = "clocked-data")]
+        return TestType::ClockedData;
+        #[cfg(feature = "min-period")]
+        return TestType::MinPeriod;
+        #[cfg(feature = "ws2812")]
+        return TestType::Ws2812;
+        #[cfg(feature = "prop-delay")]
+        return TestType::PropDelay;
+        #[cfg(any(feature = "toggle-in-flash", feature = "toggle-in-ram"))]
+        return TestType::TogglePlacement;
+        #[cfg(feature = "dual-core-contention")]
+        return TestType::DualCoreContention;
+        #[cfg(feature = "toggle-verify")]
+        return TestType::ToggleVerify;
+        #[cfg(feature = "pulse-train")]
+        return TestType::PulseTrain;
+        #[cfg(feature = "rise-time")]
+        return TestType::RiseTime;
+        #[cfg(feature = "xip-thrash")]
+        return TestType::XipThrash;
+        #[cfg(feature = "pattern")]
+        return TestType::Pattern;
+        #[cfg(feature = "no-clobber")]
+        return TestType::NoClobber;
+        #[cfg(feature = "bench-output-new")]
+        return TestType::BenchOutputNew;
+        #[cfg(feature = "glitch-inject")]
+        return TestType::GlitchInject;
+        #[cfg(feature = "dry-run")]
+        return TestType::DryRun;
+        #[cfg(feature = "timer-sweep")]
+        return TestType::TimerSweep;
+        #[cfg(feature = "pwm-pair")]
+        return TestType::PwmPair;
+        #[cfg(feature = "toggle-variable")]
+        return TestType::ToggleVariable;
+        #[cfg(feature = "yield-tasks")]
+        return TestType::YieldTasks;
+        #[cfg(feature = "multi-gpio")]
+        return TestType::MultiGpio;
+        #[cfg(feature = "pwm")]
+        return TestType::Pwm;
+        #[cfg(feature = "pio")]
+        return TestType::Pio;
+        #[cfg(feature = "test-sweep")]
+        return TestType::TestSweep;
+        #[cfg(feature = "echo-gpio")]
+        return TestType::EchoGpio;
+        #[cfg(feature = "dual-core-toggle")]
+        return TestType::DualCoreToggle;
+        #[cfg(feature = "target-period")]
+        return TestType::TargetPeriod;
+        #[cfg(feature = "irq-latency")]
+        return TestType::IrqLatency;
+        #[cfg(feature = "soft-pwm")]
+        return TestType::SoftPwm;
+        #[cfg(feature = "spi")]
+        return TestType::Spi;
+        #[cfg(feature = "calibrate-pad")]
+        return TestType::CalibratePad;
+        #[cfg(feature = "output-overhead")]
+        return TestType::OutputOverhead;
+        #[cfg(feature = "drive-sweep")]
+        return TestType::DriveSweep;
+        #[cfg(feature = "asm-readback")]
+        return TestType::AsmReadback;
+        #[cfg(feature = "adc")]
+        return TestType::Adc;
+        #[cfg(feature = "timer-drift")]
+        return TestType::TimerDrift;
+        #[cfg(feature = "xor-toggle")]
+        return TestType::XorToggle;
+        #[cfg(feature = "antiphase")]
+        return TestType::Antiphase;
+        #[cfg(feature = "dma-contention")]
+        return TestType::DmaContention;
+        #[cfg(feature = "pad-sweep")]
+        return TestType::PadSweep;
+        #[cfg(feature = "soak")]
+        return TestType::Soak;
+        #[cfg(feature = "clock-source")]
+        return TestType::ClockSource;
+        #[cfg(feature = "i2c")]
+        return TestType::I2c;
+        #[cfg(feature = "chirp")]
+        return TestType::Chirp;
+        #[cfg(feature = "uart-frame")]
+        return TestType::UartFrame;
+        #[cfg(feature = "pad-latency")]
+        return TestType::PadLatency;
+        #[cfg(feature = "dead-time")]
+        return TestType::DeadTime;
+        #[cfg(feature = "busy-task")]
+        return TestType::BusyTask;
+        #[cfg(not(any(
+            feature = "single-gpio",
+            feature = "walking-ones",
+            feature = "blink-result",
+            feature = "uart-rx-jitter",
+            feature = "calibrate",
+            feature = "reset-state",
+            feature = "locked-period",
+            feature = "remapped",
+            feature = "ir-nec",
+            feature = "clocked-data",
+            feature = "min-period",
+            feature = "ws2812",
+            feature = "prop-delay",
+            feature = "toggle-in-flash",
+            feature = "toggle-in-ram",
+            feature = "dual-core-contention",
+            feature = "toggle-verify",
+            feature = "pulse-train",
+            feature = "rise-time",
+            feature = "xip-thrash",
+            feature = "pattern",
+            feature = "no-clobber",
+            feature = "bench-output-new",
+            feature = "glitch-inject",
+            feature = "dry-run",
+            feature = "timer-sweep",
+            feature = "pwm-pair",
+            feature = "toggle-variable",
+            feature = "yield-tasks",
+            feature = "multi-gpio",
+            feature = "pwm",
+            feature = "pio",
+            feature = "test-sweep",
+            feature = "echo-gpio",
+            feature = "dual-core-toggle",
+            feature = "target-period",
+            feature = "irq-latency",
+            feature = "soft-pwm",
+            feature = "spi",
+            feature = "calibrate-pad",
+            feature = "output-overhead",
+            feature = "drive-sweep",
+            feature = "asm-readback",
+            feature = "adc",
+            feature = "timer-drift",
+            feature = "xor-toggle",
+            feature = "antiphase",
+            feature = "dma-contention",
+            feature = "pad-sweep",
+            feature = "soak",
+            feature = "clock-source",
+            feature = "i2c",
+            feature = "chirp",
+            feature = "uart-frame",
+            feature = "pad-latency",
+            feature = "dead-time",
+            feature = "busy-task"
+        )))]
+        {
+            compile_error!(
+                "No test type selected - enable one of features single-gpio, walking-ones, blink-result, uart-rx-jitter, calibrate, reset-state, locked-period, remapped, ir-nec, clocked-data, min-period, ws2812, prop-delay, toggle-in-flash, toggle-in-ram, dual-core-contention, toggle-verify, pulse-train, rise-time, xip-thrash, pattern, no-clobber, bench-output-new, glitch-inject, dry-run, timer-sweep, pwm-pair, toggle-variable, yield-tasks, multi-gpio, pwm, pio, test-sweep, echo-gpio, dual-core-toggle, target-period, irq-latency, soft-pwm, spi, calibrate-pad, output-overhead, drive-sweep, asm-readback, adc, timer-drift, xor-toggle, antiphase, dma-contention, pad-sweep, soak, clock-source, i2c, chirp, uart-frame, pad-latency, dead-time, busy-task"
+            );
+            unreachable!()
+        }
+    }
+}
+
+const _: () = assert!(
+    TestType::ENABLED <= 1,
+    "Multiple test types are enabled - enable only one test type feature"
+);
+
+#[derive(Clone, Copy)]
+#[repr(i32)]
+enum TestNum {
+    T1 = 1,
+    T2,
+    T3,
+    T4,
+    T5,
+    T6,
+    T7,
+    T8,
+    T9,
+    T10,
+    T11,
+    T12,
+    T13,
+    T14,
+    T15,
+    T16,
+    T17,
+    T18,
+    T19,
+    T20,
+    T21,
+    T22,
+    T23,
+    T24,
+    T25,
+    T26,
+    T27,
+    T28,
+}
+
+// A single-gpio test's description, its expected period on this board, if
+// it has a predictable one, and the percentage its measured period may be
+// off by, for ci-assert, if it's measured - see ci_assert.rs.
+#[derive(Clone, Copy)]
+struct TestInfo {
+    description: &'static str,
+    period_ns: Option<u32>,
+    tolerance_pct: Option<u32>,
+}
+
+impl TestNum {
+    #[cfg(any(
+        feature = "dry-run",
+        feature = "runtime-select",
+        feature = "test-sweep"
+    ))]
+    const ALL: [TestNum; 28] = [
+        TestNum::T1,
+        TestNum::T2,
+        TestNum::T3,
+        TestNum::T4,
+        TestNum::T5,
+        TestNum::T6,
+        TestNum::T7,
+        TestNum::T8,
+        TestNum::T9,
+        TestNum::T10,
+        TestNum::T11,
+        TestNum::T12,
+        TestNum::T13,
+        TestNum::T14,
+        TestNum::T15,
+        TestNum::T16,
+        TestNum::T17,
+        TestNum::T18,
+        TestNum::T19,
+        TestNum::T20,
+        TestNum::T21,
+        TestNum::T22,
+        TestNum::T23,
+        TestNum::T24,
+        TestNum::T25,
+        TestNum::T26,
+        TestNum::T27,
+        TestNum::T28,
+    ];
+
+    // Whether the test is one of the asm tests, 14-18, which never return.
+    #[cfg(any(feature = "test-sweep", feature = "halt-after"))]
+    fn is_asm(self) -> bool {
+        matches!(
+            self,
+            TestNum::T14 | TestNum::T15 | TestNum::T16 | TestNum::T17 | TestNum::T18
+        )
+    }
+
+    // Returns the test with the given number, if there is one.
+    #[cfg(feature = "runtime-select")]
+    fn from_number(number: u8) -> Option<Self> {
+        Self::ALL.get((number as usize).checked_sub(1)?).copied()
+    }
+
+    // Returns the test's description, expected period and tolerance.  This
+    // is the one place tests are described, so there's deliberately no
+    // catch-all arm - a new test needs an entry.  It's also the one table of
+    // expected periods, which is why it isn't a const - the asm tests' follow
+    // clk_sys.  Code comparing against a test's period should use period_ns
+    // from here, as measure's timing lines do, rather than parsing the
+    // description.
+    //
+    // Only the tests using single_gpio! are measured, so only they have a
+    // tolerance.  The yielding tests' periods depend on the executor, so are
+    // looser than the blocking ones, and the shorter a period, the larger
+    // its loop overhead is as a proportion of it.
+    fn info(self) -> TestInfo {
+        let (description, period_ns, tolerance_pct) = match self {
+            TestNum::T1 => (
+                "~200us period using yielding Timer::after_micros",
+                Some(200_000),
+                Some(5),
+            ),
+            TestNum::T2 => (
+                "~20us period using yielding Timer::after_micros",
+                Some(20_000),
+                Some(10),
+            ),
+            TestNum::T3 => (
+                "~2us period using yielding Timer::after_micros",
+                Some(2_000),
+                Some(25),
+            ),
+            TestNum::T4 => (
+                "200us period using blocking Delay.delay_us",
+                Some(200_000),
+                Some(1),
+            ),
+            TestNum::T5 => (
+                "20us period using blocking Delay.delay_us",
+                Some(20_000),
+                Some(2),
+            ),
+            TestNum::T6 => (
+                "4us period using blocking Delay.delay_us",
+                Some(4_000),
+                Some(5),
+            ),
+            TestNum::T7 => (
+                "2us period using blocking Delay.delay_us",
+                Some(2_000),
+                Some(10),
+            ),
+            TestNum::T8 => (
+                "not near 200ns period using blocking Delay.delay_ns",
+                None,
+                None,
+            ),
+            TestNum::T9 => (
+                "~200us period using blocking Delay.delay_us then yield_now()",
+                Some(200_000),
+                Some(5),
+            ),
+            TestNum::T10 => (
+                "~20us period using blocking Delay.delay_us then yield_now()",
+                Some(20_000),
+                Some(10),
+            ),
+            TestNum::T11 => (
+                "~2us period using blocking Delay.delay_us then yield_now()",
+                Some(2_000),
+                Some(25),
+            ),
+            TestNum::T12 => (
+                "\"2 cycle\" delay using blocking cortex_m::asm::delay()",
+                None,
+                None,
+            ),
+            TestNum::T13 => (
+                "As fast as possible with no delay and embassy GPIO functions",
+                None,
+                None,
+            ),
+            // The asm tests' periods are fixed numbers of cycles, so are
+            // computed from clk_sys, to follow any overclock.  The
+            // descriptions are for the boards' default clk_sys.
+            TestNum::T14 => (
+                "200ns (Pico) or 100ns (Pico 2) period using asm",
+                Some(Test::cycles_to_ns(if IS_PICO2 { 15 } else { 25 })),
+                None,
+            ),
+            TestNum::T15 => (
+                "200ns period using asm on both Pico and Pico 2",
+                Some(Test::cycles_to_ns(if IS_PICO2 { 30 } else { 25 })),
+                None,
+            ),
+            TestNum::T16 => (
+                "80ns period using asm on both Pico and Pico 2",
+                Some(Test::cycles_to_ns(if IS_PICO2 { 12 } else { 10 })),
+                None,
+            ),
+            TestNum::T17 | TestNum::T18 => (
+                "48ns (Pico) or 20ns (Pico 2) minimum period using asm",
+                Some(Test::cycles_to_ns(if IS_PICO2 { 3 } else { 6 })),
+                None,
+            ),
+            TestNum::T19 => ("20us period using Timer::at()", Some(20_000), None),
+            TestNum::T20 => (
+                "~1us period using blocking cortex_m::asm::delay()",
+                Some(1_000),
+                Some(10),
+            ),
+            TestNum::T21 => (
+                "Period sweep from 2us to 200us, ~100ms per step, using blocking Delay.delay_us",
+                None,
+                None,
+            ),
+            TestNum::T22 => (
+                "Bursts of 10 2us periods then 100us low, using blocking Delay.delay_us",
+                None,
+                None,
+            ),
+            TestNum::T23 => (
+                "20us period at 25% duty using blocking Delay.delay_us",
+                Some(20_000),
+                Some(2),
+            ),
+            TestNum::T24 => (
+                "~20us period using embassy_time::Ticker",
+                Some(20_000),
+                Some(2),
+            ),
+            TestNum::T25 => (
+                "As fast as possible with no delay using Output::toggle()",
+                None,
+                None,
+            ),
+            TestNum::T26 => (
+                "200ns period using the DWT cycle counter (Pico 2 only)",
+                Some(200),
+                Some(5),
+            ),
+            TestNum::T27 => (
+                "20us period using the DWT cycle counter (Pico 2 only)",
+                Some(20_000),
+                Some(1),
+            ),
+            TestNum::T28 => (
+                "Bursts of 8 fast asm toggles then 100us low, using Timer::after_micros",
+                None,
+                None,
+            ),
+        };
+        TestInfo {
+            description,
+            period_ns,
+            tolerance_pct,
+        }
+    }
+
+    // Test numbers only apply to the single-gpio tests.  Returns None if no
+    // test number feature is enabled.
+    #[cfg(feature = "single-gpio")]
+    #[allow(unreachable_code)]
+    fn get() -> Option<Self> {
+        #[cfg(feature = "1")]
+        return Some(TestNum::T1);
+        #[cfg(feature = "2")]
+        return Some(TestNum::T2);
+        #[cfg(feature = "3")]
+        return Some(TestNum::T3);
+        #[cfg(feature = "4")]
+        return Some(TestNum::T4);
+        #[cfg(feature = "5")]
+        return Some(TestNum::T5);
+        #[cfg(feature = "6")]
+        return Some(TestNum::T6);
+        #[cfg(feature = "7")]
+        return Some(TestNum::T7);
+        #[cfg(feature = "8")]
+        return Some(TestNum::T8);
+        #[cfg(feature = "9")]
+        return Some(TestNum::T9);
+        #[cfg(feature = "10")]
+        return Some(TestNum::T10);
+        #[cfg(feature = "11")]
+        return Some(TestNum::T11);
+        #[cfg(feature = "12")]
+        return Some(TestNum::T12);
+        #[cfg(feature = "13")]
+        return Some(TestNum::T13);
+        #[cfg(feature = "14")]
+        return Some(TestNum::T14);
+        #[cfg(feature = "15")]
+        return Some(TestNum::T15);
+        #[cfg(feature = "16")]
+        return Some(TestNum::T16);
+        #[cfg(feature = "17")]
+        return Some(TestNum::T17);
+        #[cfg(feature = "18")]
+        return Some(TestNum::T18);
+        #[cfg(feature = "19")]
+        return Some(TestNum::T19);
+        #[cfg(feature = "20")]
+        return Some(TestNum::T20);
+        #[cfg(feature = "21")]
+        return Some(TestNum::T21);
+        #[cfg(feature = "22")]
+        return Some(TestNum::T22);
+        #[cfg(feature = "23")]
+        return Some(TestNum::T23);
+        #[cfg(feature = "24")]
+        return Some(TestNum::T24);
+        #[cfg(feature = "25")]
+        return Some(TestNum::T25);
+        #[cfg(feature = "26")]
+        return Some(TestNum::T26);
+        #[cfg(feature = "27")]
+        return Some(TestNum::T27);
+        #[cfg(feature = "28")]
+        return Some(TestNum::T28);
+        None
+    }
+}
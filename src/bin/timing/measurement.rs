@@ -0,0 +1,65 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Measurement results.  Tests which measure a period, jitter or latency
+// return their results as a MeasurementResult, as well as logging them, so
+// that a sequence of tests can collect and compare them.
+
+// The result of a timing measurement, all in nanoseconds.  If there were no
+// samples, everything is 0.
+#[derive(Clone, Copy, Default, defmt::Format)]
+pub struct MeasurementResult {
+    pub min_ns: u32,
+    pub max_ns: u32,
+    pub mean_ns: u32,
+    pub samples: u32,
+}
+
+// Accumulates samples into a MeasurementResult.  Samples are in whatever
+// units are cheapest to take (such as SysTick cycles), and only converted to
+// nanoseconds when the result is produced, so this can be used in tight
+// loops.
+#[derive(Clone, Copy)]
+pub struct Measurement {
+    min: u32,
+    max: u32,
+    total: u64,
+    samples: u32,
+}
+
+impl Measurement {
+    pub const fn new() -> Self {
+        Self {
+            min: u32::MAX,
+            max: 0,
+            total: 0,
+            samples: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn add(&mut self, sample: u32) {
+        self.min = self.min.min(sample);
+        self.max = self.max.max(sample);
+        self.total += sample as u64;
+        self.samples += 1;
+    }
+
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    // Produces the result, using to_ns to convert from the sample units.
+    pub fn result(&self, to_ns: impl Fn(u32) -> u32) -> MeasurementResult {
+        if self.samples == 0 {
+            return MeasurementResult::default();
+        }
+        MeasurementResult {
+            min_ns: to_ns(self.min),
+            max_ns: to_ns(self.max),
+            mean_ns: to_ns((self.total / self.samples as u64) as u32),
+            samples: self.samples,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Memory usage report.  Reports the static RAM used by .data and .bss, and
+// the stack's size and high-water mark, for checking the headroom left when
+// adding larger buffers.
+//
+// cortex-m-rt's linker script places the stack at the top of RAM, from
+// _stack_start growing down to _stack_end, which is the end of .bss (there
+// is no heap).  paint_stack() fills the unused part of the stack with
+// PAINT, at the top of main, and log_memory_usage() later finds the lowest
+// word which no longer holds PAINT, which is as deep as the stack has
+// reached.  A frame which was allocated but never written could hold PAINT
+// by chance, so the high-water mark is a lower bound, but a good one.
+//
+// Painting takes a few ms, before the test starts (but after reset-state's
+// capture), and scanning similar, so neither disturbs a test's timing.
+// As most tests run forever, main only reports the high-water mark for tests
+// which return.  Other tests can call log_memory_usage() themselves, at a
+// point where timing doesn't matter.
+
+use defmt::info;
+
+use crate::Test;
+
+// Pattern the unused stack is painted with.
+const PAINT: u32 = 0xdead_beef;
+
+// Bytes below the current stack pointer left unpainted, to stay clear of
+// paint_stack()'s own frame.
+const MARGIN: usize = 256;
+
+unsafe extern "C" {
+    // From cortex-m-rt's link.x
+    static __sdata: u8;
+    static __edata: u8;
+    static __sbss: u8;
+    static __ebss: u8;
+    static _stack_start: u8;
+    static _stack_end: u8;
+}
+
+impl Test {
+    // Fills the stack below the current stack pointer with PAINT.
+    #[inline(never)]
+    pub fn paint_stack() {
+        let bottom = (&raw const _stack_end) as usize;
+        let top = (cortex_m::register::msp::read() as usize - MARGIN) & !3;
+        for addr in (bottom..top).step_by(4) {
+            // Safety: The region is RAM reserved for the stack, and below
+            // anything currently on it.
+            unsafe { core::ptr::write_volatile(addr as *mut u32, PAINT) };
+        }
+    }
+
+    // Reports static RAM usage, and the stack's high-water mark since
+    // paint_stack() was called.
+    pub fn log_memory_usage() {
+        let data = (&raw const __edata) as usize - (&raw const __sdata) as usize;
+        let bss = (&raw const __ebss) as usize - (&raw const __sbss) as usize;
+        let top = (&raw const _stack_start) as usize;
+        let bottom = (&raw const _stack_end) as usize;
+
+        let lowest = (bottom..top)
+            .step_by(4)
+            // Safety: The region is RAM reserved for the stack.
+            .find(|&addr| unsafe { core::ptr::read_volatile(addr as *const u32) } != PAINT)
+            .unwrap_or(top);
+
+        info!("Memory usage");
+        info!(": Static RAM: .data {} bytes, .bss {} bytes", data, bss);
+        info!(
+            ": Stack: {:#010x} to {:#010x}, {} bytes",
+            bottom,
+            top,
+            top - bottom
+        );
+        info!(
+            ": Stack used: {} bytes (high-water), free: {} bytes",
+            top - lowest,
+            lowest - bottom
+        );
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Minimum period report.  Measures the effective period of the minimum
+// period asm toggle loop (asm_toggle_period_min(), used by
+// single-gpio test 18) over a bounded number of periods, and reports it
+// along with the profile and opt-level it was built with.
+//
+// Test 18 uses its own Thumb-2 loop on the Pico 2, so there this measures
+// the Pico's loop instead, which is still a useful comparison across
+// builds.
+//
+// The stores are inline asm, so aren't affected by the optimisation level,
+// but the loop around them is.  To compare, change opt-level and lto in
+// Cargo.toml's [profile.dev] and [profile.release] sections, and run this
+// with and without --release.
+//
+// Each sample times PERIODS and 2 * PERIODS periods with SysTick, with
+// interrupts disabled, and uses the difference, so SysTick's read overhead
+// and the loop's setup cancel out.
+//
+// With the result-log feature, the previous results are reported at boot,
+// and this result is added to the log, so results can be compared across
+// firmware versions.
+//
+// With the serial-report feature, the result is also written to UART0 as a
+// line of key=value pairs.
+
+use defmt::info;
+use embassy_pico_test::gpio_asm::{asm_load_gpio_out_addr, set_gpio_high, set_gpio_low};
+use embassy_rp::gpio::{Level, Output};
+
+use crate::Test;
+use crate::measurement::{Measurement, MeasurementResult};
+
+// Periods to time for each sample.  2 * PERIODS periods must take under
+// SysTick's 2^24 cycles.
+const PERIODS: u32 = 10_000;
+
+// Number of samples.
+const SAMPLES: u32 = 8;
+
+impl Test {
+    pub async fn report_min_period() -> MeasurementResult {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        info!("Minimum period report");
+        info!(": Using GPIO 2");
+        info!(
+            ": Profile: {}, opt-level: {}",
+            env!("BUILD_PROFILE"),
+            env!("BUILD_OPT_LEVEL")
+        );
+
+        #[cfg(feature = "result-log")]
+        Self::dump_result_history();
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+
+        // Samples are in thousandths of a cycle per period.
+        let mut measurement = Measurement::new();
+        for _ in 0..SAMPLES {
+            let (single, double) = cortex_m::interrupt::free(|_| {
+                (
+                    Self::time_periods_min(PERIODS),
+                    Self::time_periods_min(2 * PERIODS),
+                )
+            });
+            let millicycles = (double - single) as u64 * 1000 / PERIODS as u64;
+            measurement.add(millicycles as u32);
+        }
+
+        let millicycles = measurement.result(|mc| mc);
+        let result = measurement.result(|mc| (mc as u64 * 1_000_000 / speed as u64) as u32);
+        info!(
+            ": Cycles per period: {}.{:03} (min {}.{:03}, max {}.{:03})",
+            millicycles.mean_ns / 1000,
+            millicycles.mean_ns % 1000,
+            millicycles.min_ns / 1000,
+            millicycles.min_ns % 1000,
+            millicycles.max_ns / 1000,
+            millicycles.max_ns % 1000,
+        );
+        info!(": Period: {}", result);
+
+        #[cfg(feature = "result-log")]
+        Self::log_result_to_flash(result);
+
+        #[cfg(feature = "serial-report")]
+        Self::report_kv_uart("min-period", &result);
+
+        info!(": Done");
+
+        result
+    }
+
+    // Returns the number of cycles taken to toggle GPIO 2 for the given
+    // number of periods.
+    fn time_periods_min(periods: u32) -> u32 {
+        Self::systick_measure(|| {
+            Self::asm_toggle_gpio2_periods_min(periods);
+        })
+    }
+
+    // A bounded version of asm_toggle_period_min(), from the same
+    // set_gpio_high() and set_gpio_low() for GPIO 2, so it follows
+    // raw-gpio-out as they do.  GPIO_OUT is reloaded into r0 each period,
+    // rather than once beforehand, as the compiler is free to use r0 for the
+    // loop counter, so each period includes asm_load_gpio_out_addr().  Not
+    // inlined, so the loop is compiled the same way for both sample lengths.
+    #[inline(never)]
+    fn asm_toggle_gpio2_periods_min(periods: u32) {
+        for _ in 0..periods {
+            // Safety: The three helpers are consecutive asm blocks, so the
+            // compiler generates nothing between them to change r0.
+            unsafe {
+                asm_load_gpio_out_addr();
+                set_gpio_high::<2>();
+                set_gpio_low::<2>();
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Multi GPIO test.  Toggles a set of GPIOs together, with every edge a
+// single store of the combined bitmask, to GPIO_OUT_SET for rising edges
+// and GPIO_OUT_CLR for falling edges, so the skew between the pins can be
+// measured.  The SIO write is single cycle, so all of the pins should
+// change on the same clk_sys edge - any skew seen is from the pads and
+// board routing.
+//
+// SET and CLR are used rather than GPIO_OUT, so other GPIOs are left
+// alone.  Between edges is a counted delay loop, as in the variable period
+// toggle test, with the same cost - 6 cycles per count plus 6 per period
+// on the Pico - so the period can be slowed to suit the logic analyzer.
+
+use core::arch::asm;
+use defmt::{error, info, warn};
+use embassy_rp::gpio::{AnyPin, Level, Output};
+use heapless::Vec;
+
+use crate::{GPIO_OUT_CLR, GPIO_OUT_SET, IS_PICO2, NUM_GPIOS, SIO_BASE, Test};
+
+// GPIOs to toggle together, and the delay loop count each half period.
+pub const PINS: &[u8] = &[2, 3, 4, 5];
+pub const COUNT: u32 = 10;
+
+// Cycles per period on the Pico.
+const CYCLES_PER_COUNT: u32 = 6;
+const OVERHEAD_CYCLES: u32 = 6;
+
+// Register offsets from SIO_BASE, for use as asm immediates.
+const SET_OFFSET: u32 = GPIO_OUT_SET - SIO_BASE;
+const CLR_OFFSET: u32 = GPIO_OUT_CLR - SIO_BASE;
+
+impl Test {
+    pub async fn multi_gpio(pins: &[u8], count: u32) -> ! {
+        let _p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        info!("Multi GPIO test");
+
+        let mut mask = 0u32;
+        for &pin in pins {
+            if pin >= NUM_GPIOS || mask & (1 << pin) != 0 {
+                error!(": Invalid or duplicate GPIO {}", pin);
+                Self::idle();
+            }
+            mask |= 1 << pin;
+        }
+        if mask == 0 || count == 0 {
+            error!(": Need at least one GPIO, and a non-zero count");
+            Self::idle();
+        }
+
+        let cycles = count as u64 * CYCLES_PER_COUNT as u64 + OVERHEAD_CYCLES as u64;
+        info!(": Using GPIOs {}", pins);
+        info!(": Bitmask: {:#010x}", mask);
+        info!(
+            ": Count: {}, period: {} cycles ({}ns)",
+            count,
+            cycles,
+            cycles * 1_000_000_000 / speed as u64
+        );
+        if IS_PICO2 {
+            warn!(": Period is an estimate on the Pico 2 - measure it");
+        }
+
+        // The Outputs must be kept, as dropping them would deconfigure the
+        // pins.
+        let mut outputs: Vec<Output<'static>, { NUM_GPIOS as usize }> = Vec::new();
+        for &pin in pins {
+            // Safety: The typed pin peripherals aren't used by this test, and
+            // duplicates are rejected above, so each of these pins is only
+            // instantiated once.
+            let pin = unsafe { AnyPin::steal(pin) };
+            let _ = outputs.push(Output::new(pin, Level::Low));
+        }
+
+        info!(": Starting");
+
+        // Disable interrupts so nothing disturbs the timing.
+        cortex_m::interrupt::disable();
+        Self::asm_toggle_mask(mask, count);
+    }
+
+    // Toggles every GPIO in mask together, with a single store per edge,
+    // and count delay loops each half period.  The halves are balanced with
+    // nops, as in the variable period toggle test.
+    //
+    // r0: SIO_BASE, r3: mask, r4: count, r5: delay loop counter.
+    // Registers are free to clobber, as this never returns.
+    #[inline(never)]
+    #[unsafe(link_section = ".data.ram_func")]
+    fn asm_toggle_mask(mask: u32, count: u32) -> ! {
+        unsafe {
+            asm!(
+                "2:",
+                "str r3, [r0, #{set}]",   // All high
+                "mov r5, r4",
+                "3:",
+                "subs r5, #1",
+                "bne 3b",
+                "nop",
+                "nop",
+                "str r3, [r0, #{clr}]",   // All low
+                "mov r5, r4",
+                "4:",
+                "subs r5, #1",
+                "bne 4b",
+                "b 2b",
+                in("r0") SIO_BASE,
+                in("r3") mask,
+                in("r4") count,
+                set = const SET_OFFSET,
+                clr = const CLR_OFFSET,
+                options(noreturn, nostack),
+            );
+        }
+    }
+}
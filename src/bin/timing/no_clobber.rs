@@ -0,0 +1,100 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// No clobber self-test.  Holds GPIO 3 high, toggles GPIO 2, and checks via
+// GPIO_IN that GPIO 3 stays high.  GPIO 2 is toggled first with the shared
+// SET/CLR based toggle helper, toggle_gpio2_periods(), and then with the
+// raw asm helpers, set_gpio_high() and set_gpio_low(), which the asm
+// tests' loops are built from.  This guards against either going back to
+// writing the whole of GPIO_OUT, which would drive every other output low,
+// as a `str` of 0 to GPIO_OUT does.  The raw helpers fail with the
+// raw-gpio-out feature, which does just that.
+//
+// GPIO 3 is checked after every CHECK_PERIODS periods.  Once clobbered,
+// nothing sets it high again until the test does, so a clobber anywhere in
+// the toggle is caught.  Each round's result is logged as PASS or FAIL for
+// each, and a FAIL is logged as an error, so it can't be missed.
+
+use defmt::{error, info};
+use embassy_pico_test::gpio_asm::{asm_load_gpio_out_addr, set_gpio_high, set_gpio_low};
+use embassy_rp::gpio::{Level, Output};
+use embassy_time::Timer;
+
+use crate::{GPIO_IN, Test};
+
+// Delay loop count for each half period.
+const COUNT: u32 = 10;
+
+// Periods between each check of GPIO 3.
+const CHECK_PERIODS: u32 = 1_000;
+
+// Checks per round.
+const CHECKS: u32 = 1_000;
+
+const GPIO3_MASK: u32 = 1 << 3;
+
+impl Test {
+    pub async fn verify_no_clobber() -> ! {
+        let p = Self::init();
+
+        info!("No clobber self-test");
+        info!(": Toggling GPIO 2, holding GPIO 3 high");
+
+        let _toggle = Output::new(p.PIN_2, Level::Low);
+        let mut held = Output::new(p.PIN_3, Level::High);
+
+        // Let GPIO 3's pad settle before the first check.
+        Timer::after_millis(1).await;
+
+        info!(": Starting");
+        let mut round: u32 = 0;
+        loop {
+            for (name, toggle) in [
+                ("toggle_gpio2_periods()", Self::toggle_gpio2_checked as fn()),
+                ("set_gpio_high()/set_gpio_low()", Self::toggle_gpio2_raw),
+            ] {
+                let mut failures = 0;
+                for _ in 0..CHECKS {
+                    toggle();
+                    if Self::sio_read(GPIO_IN) & GPIO3_MASK == 0 {
+                        failures += 1;
+                        // Restore it, so later checks are independent.
+                        held.set_high();
+                    }
+                }
+
+                if failures == 0 {
+                    info!(": Round {}: {}: PASS", round, name);
+                } else {
+                    error!(
+                        ": Round {}: {}: FAIL - GPIO 3 found low at {}/{} checks",
+                        round, name, failures, CHECKS
+                    );
+                }
+            }
+            round = round.wrapping_add(1);
+
+            // Let the logs go out.
+            Timer::after_millis(100).await;
+        }
+    }
+    // Toggles GPIO 2 for CHECK_PERIODS periods with toggle_gpio2_periods().
+    fn toggle_gpio2_checked() {
+        Self::toggle_gpio2_periods(COUNT, CHECK_PERIODS);
+    }
+
+    // Toggles GPIO 2 for CHECK_PERIODS periods with the raw asm helpers.
+    #[inline(never)]
+    fn toggle_gpio2_raw() {
+        for _ in 0..CHECK_PERIODS {
+            // Safety: r0 is loaded immediately before the stores, with only
+            // asm between them.
+            unsafe {
+                asm_load_gpio_out_addr();
+                set_gpio_high::<2>();
+                set_gpio_low::<2>();
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Logging and panic backends for the no-defmt feature, which builds in place
+// of the default rtt feature, so without defmt-rtt and panic-probe, for the
+// smallest binary and no RTT machinery.
+//
+// build.rs sets DEFMT_LOG to off for this crate, so its info!, warn! and
+// error! calls, and detail!, compile to nothing.  embassy's crates are still
+// built with their defmt feature, and can log warnings and errors, so a
+// global logger is still needed to link - this one discards everything, and
+// never takes a critical section, so costs nothing but a few bytes of
+// interned strings.
+//
+// A panic halts the core in a loop, with interrupts disabled, like
+// panic-halt, rather than panic-probe's breakpoint and message.  Attach a
+// debugger to see where.
+//
+// With no logging, results can still be reported by serial-report,
+// serial-console or blink-result.
+
+use core::panic::PanicInfo;
+use core::sync::atomic::{Ordering, compiler_fence};
+
+#[defmt::global_logger]
+struct NoLogger;
+
+// Safety: Does nothing, so has no state to protect.
+unsafe impl defmt::Logger for NoLogger {
+    fn acquire() {}
+
+    unsafe fn flush() {}
+
+    unsafe fn release() {}
+
+    unsafe fn write(_bytes: &[u8]) {}
+}
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    cortex_m::interrupt::disable();
+    loop {
+        compiler_fence(Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Output overhead test.  Alternates bursts of GPIO 2 toggles through
+// embassy's Output::set_high() and set_low(), as test 13 does, with bursts
+// of raw stores of GPIO 2's mask to GPIO_OUT_SET and GPIO_OUT_CLR, as the
+// asm tests do, in the same run and from the same loop, so the difference
+// is only the embassy abstraction.
+//
+// Each burst is timed with SysTick, with interrupts disabled, and its mean
+// half period is logged every ROUNDS bursts of each, along with the
+// difference, which is embassy's overhead per call.  The overhead depends
+// heavily on the build's optimisation level, so that is logged too.  On a
+// scope, the Hal bursts are the first of each pair after a gap.
+
+use defmt::info;
+use embassy_rp::gpio::{Level, Output};
+use embassy_time::Timer;
+
+use crate::measurement::Measurement;
+use crate::{GPIO_OUT_CLR, GPIO_OUT_SET, GPIO2_MASK, Test};
+
+// Periods per burst, and bursts of each per log.
+const BURST_PERIODS: u32 = 100;
+const ROUNDS: u32 = 100;
+
+// Gap between bursts.
+const GAP_US: u64 = 50;
+
+impl Test {
+    pub async fn output_overhead() -> ! {
+        let p = Self::init();
+
+        info!("Output overhead test");
+        info!(": Using GPIO 2");
+        info!(
+            ": Profile: {}, opt-level: {}",
+            env!("BUILD_PROFILE"),
+            env!("BUILD_OPT_LEVEL")
+        );
+        info!(
+            ": Bursts of {} periods, Output::set_high()/set_low() then raw stores",
+            BURST_PERIODS
+        );
+
+        let mut output = Output::new(p.PIN_2, Level::Low);
+
+        info!(": Starting");
+        loop {
+            // Half periods, in thousandths of a cycle.
+            let mut hal = Measurement::new();
+            let mut raw = Measurement::new();
+            for _ in 0..ROUNDS {
+                let cycles = cortex_m::interrupt::free(|_| Self::time_toggles_hal(&mut output));
+                hal.add(cycles * 1000 / (2 * BURST_PERIODS));
+                Timer::after_micros(GAP_US).await;
+
+                let cycles = cortex_m::interrupt::free(|_| Self::time_toggles_raw());
+                raw.add(cycles * 1000 / (2 * BURST_PERIODS));
+                Timer::after_micros(GAP_US).await;
+            }
+
+            let hal = hal.result(|mc| mc).mean_ns;
+            let raw = raw.result(|mc| mc).mean_ns;
+            let overhead = hal.saturating_sub(raw);
+            for (name, mc) in [
+                ("Hal half period", hal),
+                ("Raw half period", raw),
+                ("Embassy overhead per call", overhead),
+            ] {
+                info!(
+                    ": {}: {}.{:03} cycles ({}ns)",
+                    name,
+                    mc / 1000,
+                    mc % 1000,
+                    Self::cycles_to_ns(mc) / 1000
+                );
+            }
+        }
+    }
+
+    // Returns the cycles taken by BURST_PERIODS periods of Output toggles.
+    // Not inlined, as with time_toggles_raw(), so both loops are compiled
+    // the same way.
+    #[inline(never)]
+    fn time_toggles_hal(output: &mut Output<'_>) -> u32 {
+        Self::systick_measure(|| {
+            for _ in 0..BURST_PERIODS {
+                output.set_high();
+                output.set_low();
+            }
+        })
+    }
+
+    // Returns the cycles taken by BURST_PERIODS periods of raw stores.
+    #[inline(never)]
+    fn time_toggles_raw() -> u32 {
+        Self::systick_measure(|| {
+            for _ in 0..BURST_PERIODS {
+                Self::sio_write(GPIO_OUT_SET, GPIO2_MASK);
+                Self::sio_write(GPIO_OUT_CLR, GPIO2_MASK);
+            }
+        })
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Pad latency test.  Measures how many cycles after a store to GPIO_OUT_SET
+// or GPIO_OUT_CLR the new level of GPIO 2 can be read back from GPIO_IN -
+// the time through the output path, the pad, and the input's synchroniser.
+// This is why the toggle-verify and asm readback tests wait before reading
+// back, and part of the echo and propagation delay tests' response times.
+//
+// Rather than a polling loop, whose own cycles would limit the resolution,
+// each sample is a single asm block which stores the edge, pads for PAD
+// cycles with dependent adds, and reads GPIO_IN once, so the read is made
+// PAD + 1 cycles after the store.  Each PAD from 0 to MAX_PAD is its own
+// block, so every cycle offset is sampled exactly, SAMPLES times for each
+// edge, starting from the other level each time, settled.
+//
+// The latency logged is the first offset at which every sample read the new
+// level.  Offsets where only some did show a level sampled as it changes.
+// The Pico 2's cycle counts are estimates, as its store and load timing is
+// less deterministic than the M0+'s.  Interrupts are disabled for each
+// sweep.
+
+use core::arch::asm;
+use defmt::{info, warn};
+use embassy_pico_test::gpio_asm::delay_cycles;
+use embassy_pico_test::sio;
+use embassy_rp::gpio::{Level, Output};
+
+use crate::{GPIO_IN, GPIO_OUT_CLR, GPIO_OUT_SET, GPIO2_MASK, SIO_BASE, Test};
+
+// Samples of each edge at each offset.
+const SAMPLES: u32 = 100;
+
+// Largest padding sampled, so the latest read is MAX_PAD + 1 cycles after
+// the store.  The sweeps below list each padding up to it.
+const MAX_PAD: usize = 9;
+
+// Cycles to let GPIO 2 settle at the starting level before each sample.
+const SETTLE_CYCLES: u32 = 32;
+
+// Stores edge and reads GPIO_IN at each offset from 0 to MAX_PAD, returning
+// whether each read saw the new level.
+macro_rules! sweep {
+    ($rising:expr, $($pad:literal),*) => {
+        [$(Test::pad_latency_sample::<$rising, $pad>()),*]
+    };
+}
+
+impl Test {
+    pub async fn pad_latency() -> ! {
+        let p = Self::init();
+
+        info!("Pad latency test");
+        info!(": Using GPIO 2");
+        info!(
+            ": Reads GPIO_IN 1 to {} cycles after each store, {} samples of each",
+            MAX_PAD + 1,
+            SAMPLES
+        );
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+
+        info!(": Starting");
+        for (edge, rising) in [("Rising", true), ("Falling", false)] {
+            let mut seen = [0u32; MAX_PAD + 1];
+            for _ in 0..SAMPLES {
+                let sweep: [bool; MAX_PAD + 1] = cortex_m::interrupt::free(|_| {
+                    if rising {
+                        sweep!(true, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9)
+                    } else {
+                        sweep!(false, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9)
+                    }
+                });
+                for (count, new_level) in seen.iter_mut().zip(sweep) {
+                    *count += new_level as u32;
+                }
+            }
+
+            info!(": {} edge:", edge);
+            for (pad, count) in seen.iter().enumerate() {
+                info!(
+                    ":   {} cycles after the store: new level in {}/{}",
+                    pad + 1,
+                    count,
+                    SAMPLES
+                );
+            }
+            match seen.iter().position(|&count| count == SAMPLES) {
+                Some(pad) => {
+                    let cycles = pad as u32 + 1;
+                    info!(
+                        ": {} edge latency: {} cycles ({}ns)",
+                        edge,
+                        cycles,
+                        Self::cycles_to_ns(cycles)
+                    );
+                }
+                None => warn!(
+                    ": {} edge not reliably read back within {} cycles",
+                    edge,
+                    MAX_PAD + 1
+                ),
+            }
+        }
+
+        info!(": Done");
+        Self::idle()
+    }
+
+    // Settles GPIO 2 at the level before the edge, then stores the edge
+    // and reads GPIO_IN PAD + 1 cycles later, returning whether it read the
+    // new level.
+    #[inline(always)]
+    fn pad_latency_sample<const RISING: bool, const PAD: u32>() -> bool {
+        if RISING {
+            sio::clear_gpios(GPIO2_MASK);
+        } else {
+            sio::set_gpios(GPIO2_MASK);
+        }
+        delay_cycles::<SETTLE_CYCLES>();
+
+        let value: u32;
+        // Safety: Only stores GPIO 2's mask to GPIO_OUT_SET or GPIO_OUT_CLR,
+        // and reads GPIO_IN, using the registers declared.
+        unsafe {
+            asm!(
+                "str r1, [r0, #{store}]",
+                ".rept {pad}",
+                "adds r3, r3, #1",
+                ".endr",
+                "ldr r2, [r0, #{read}]",
+                store = const (if RISING { GPIO_OUT_SET } else { GPIO_OUT_CLR }) - SIO_BASE,
+                pad = const PAD,
+                read = const GPIO_IN - SIO_BASE,
+                in("r0") SIO_BASE,
+                in("r1") GPIO2_MASK,
+                out("r2") value,
+                out("r3") _,
+                options(nostack),
+            );
+        }
+        (value & GPIO2_MASK != 0) == RISING
+    }
+}
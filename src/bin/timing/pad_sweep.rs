@@ -0,0 +1,73 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Pad configuration sweep test.  Toggles GPIO 2 with the same short period
+// with each combination of the pad's Schmitt trigger, off and on, and slew
+// rate, slow and fast, pausing low between each with a logged banner, so a
+// single capture shows any change in the edges across them.  The drive
+// strength is left at the default 4mA - the drive-sweep test steps through
+// those instead.
+//
+// GPIO 2 is a Flex, as embassy's Output doesn't expose the Schmitt
+// trigger.  The Schmitt trigger is on the pad's input buffer, which stays
+// enabled for an output, so it isn't expected to change the edges driven,
+// only when GPIO_IN sees them - it's swept to confirm that.  Slew rate
+// limiting changes the output driver's edges directly.
+//
+// The toggling is GpioToggler::toggle_n_cycles(), for STEP_PERIODS periods
+// at each setting, with interrupts disabled.
+
+use defmt::info;
+use embassy_pico_test::gpio_asm::GpioToggler;
+use embassy_rp::gpio::{Flex, SlewRate};
+use embassy_time::Timer;
+
+use crate::{GPIO2_MASK, Test};
+
+// Cycles GPIO 2 is high and then low for.
+const HIGH_CYCLES: u32 = 10;
+const LOW_CYCLES: u32 = 10;
+
+// Periods at each setting, and the pause low after each.
+const STEP_PERIODS: u32 = 1_000_000;
+const PAUSE_MS: u64 = 20;
+
+const SCHMITT: [(bool, &str); 2] = [(false, "off"), (true, "on")];
+const SLEW_RATES: [(SlewRate, &str); 2] = [(SlewRate::Slow, "slow"), (SlewRate::Fast, "fast")];
+
+impl Test {
+    pub async fn pad_sweep() -> ! {
+        let p = Self::init();
+
+        info!("Pad configuration sweep test");
+        info!(": Using GPIO 2, 4mA drive");
+        let period = HIGH_CYCLES + LOW_CYCLES;
+        info!(
+            ": Period: {} cycles ({}ns), for {} periods at each setting",
+            period,
+            Self::cycles_to_ns(period),
+            STEP_PERIODS
+        );
+
+        let mut pin = Flex::new(p.PIN_2);
+        pin.set_low();
+        pin.set_as_output();
+        let toggler = GpioToggler::new(GPIO2_MASK);
+
+        info!(": Starting");
+        loop {
+            for (schmitt, schmitt_name) in SCHMITT {
+                pin.set_schmitt(schmitt);
+                for (slew_rate, slew_name) in SLEW_RATES {
+                    pin.set_slew_rate(slew_rate);
+                    info!(": Schmitt {}, {} slew", schmitt_name, slew_name);
+                    cortex_m::interrupt::free(|_| {
+                        toggler.toggle_n_cycles::<HIGH_CYCLES, LOW_CYCLES>(STEP_PERIODS)
+                    });
+                    Timer::after_millis(PAUSE_MS).await;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Drive pattern test.  Plays a fixed waveform on GPIO 2, from a list of
+// level and duration steps baked into the firmware at build time, and
+// repeats it every PLAY_PERIOD.
+//
+// build.rs generates PATTERN from pattern.txt, or the file given by the
+// PATTERN_FILE environment variable - see pattern.txt for the format.  It
+// rejects empty patterns, and steps which are 0 or too long to time.
+//
+// Each step's edge is placed at a cycle offset from the start of the
+// previous step, counted with SysTick with interrupts disabled, as in the
+// ws2812 test, so timing errors don't accumulate over the pattern.  Steps
+// are rounded to whole cycles, and the SysTick wait loop takes up to ~10
+// cycles to notice a deadline, so very short steps will be inaccurate.
+// After each play GPIO 2 is left low until the next.
+
+use defmt::{info, warn};
+use embassy_rp::gpio::{Level, Output};
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::{GPIO_OUT_CLR, GPIO_OUT_SET, GPIO2_MASK, SYSTICK_MAX, Test};
+
+// Time from the start of one play of the pattern to the start of the next.
+const PLAY_PERIOD: Duration = Duration::from_millis(100);
+
+// A level to drive GPIO 2 to, and how long to hold it
+pub struct PatternStep {
+    pub high: bool,
+    pub ns: u32,
+}
+
+include!(concat!(env!("OUT_DIR"), "/pattern.rs"));
+
+impl Test {
+    pub async fn play_pattern() -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq() as u64;
+        let total_ns: u64 = PATTERN.iter().map(|step| step.ns as u64).sum();
+        info!("Drive pattern test");
+        info!(": Using GPIO 2");
+        info!(
+            ": Pattern: {} steps, total duration {}ns",
+            PATTERN.len(),
+            total_ns
+        );
+        if total_ns > PLAY_PERIOD.as_micros() * 1000 {
+            warn!(
+                ": Pattern is longer than the {}ms play period, so is played back to back",
+                PLAY_PERIOD.as_millis()
+            );
+        }
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+        #[cfg(feature = "sync-pin")]
+        let _sync = Self::sync_output(p.PIN_3, "start of each play of the pattern");
+
+        Self::systick_start();
+
+        // Convert to cycles once, so playing is just the SysTick waits.
+        // build.rs limits steps to under 2^24 cycles.
+        let mut cycles = [0u32; PATTERN.len()];
+        for (cycles, step) in cycles.iter_mut().zip(PATTERN) {
+            *cycles = ((step.ns as u64 * speed + 500_000_000) / 1_000_000_000) as u32;
+        }
+
+        info!(": Starting");
+        let mut next_play = Instant::now();
+        loop {
+            cortex_m::interrupt::free(|_| Self::send_pattern(&cycles));
+            next_play += PLAY_PERIOD;
+            Timer::at(next_play).await;
+        }
+    }
+
+    // Plays PATTERN, with each step's duration in cycles, then drives GPIO 2
+    // low.  Must be called with interrupts disabled, and with SysTick
+    // started.
+    fn send_pattern(cycles: &[u32]) {
+        #[cfg(not(feature = "sync-pin"))]
+        let mut step_start = Self::systick_now();
+        #[cfg(feature = "sync-pin")]
+        let mut step_start = Self::sync_pulse();
+        for (step, &cycles) in PATTERN.iter().zip(cycles) {
+            let reg = if step.high {
+                GPIO_OUT_SET
+            } else {
+                GPIO_OUT_CLR
+            };
+            Self::sio_write(reg, GPIO2_MASK);
+            Self::wait_until_cycles(step_start, cycles);
+            step_start = step_start.wrapping_sub(cycles) & SYSTICK_MAX;
+        }
+        Self::sio_write(GPIO_OUT_CLR, GPIO2_MASK);
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Measured period reporting, enabled by the measure feature.  The
+// single_gpio! loop calls PeriodMeasure::period() once per period.  Every
+// CHECK_PERIODS periods this reads Instant::now(), and once REPORT_INTERVAL
+// has passed since the last report, logs the mean period and half period
+// over the periods since.  This sanity checks a test's period without a
+// scope.
+//
+// Instant is only read every CHECK_PERIODS periods, and the log is at most
+// once per REPORT_INTERVAL, so the loop is only slightly perturbed - the
+// period which includes a report is longer, but isn't included in the next
+// measurement, as that starts after the log.  Instant has a resolution of
+// 1us, which is small over REPORT_INTERVAL.
+//
+// Only the tests using single_gpio! are measured.  The asm tests and other
+// tests with their own loops are unchanged, as reading Instant would ruin
+// their timing.
+//
+// As well as the readable log, each report, and the test and its expected
+// period at the start, are logged as a line of key=value pairs after
+// "timing: ", for host-side scripts such as tests/hw_timing.py, for example:
+//
+// timing: test=2 expected_ns=20000
+// timing: period_ns=20187 half_period_ns=10093 periods=49000 total=98000
+//
+// periods is since the last report, and total since the test started.  The
+// keys are stable.  expected_ns is omitted for tests with no expected
+// period.  With serial-console, the same pairs are also sent to the host,
+// as TEST and RESULT lines - see console.rs.
+//
+// With the temperature feature, the die temperature is also read and logged
+// after a report, when due - see temperature.rs.  With ci-assert, each
+// report is also checked, and the test halts with a PASS or FAIL line once
+// enough have been - see ci_assert.rs.
+
+use defmt::info;
+use embassy_time::{Duration, Instant};
+
+#[cfg(feature = "ci-assert")]
+use crate::ci_assert::CiAssert;
+#[cfg(feature = "temperature")]
+use crate::temperature::TempSensor;
+
+// Periods between reads of Instant, and the minimum time between reports.
+const CHECK_PERIODS: u32 = 1_000;
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+// Logs the test's number and expected period, for host-side scripts.
+pub fn log_test(test: u8, expected_ns: Option<u32>) {
+    match expected_ns {
+        Some(expected_ns) => {
+            info!("timing: test={} expected_ns={}", test, expected_ns);
+            #[cfg(feature = "serial-console")]
+            crate::console::reply(format_args!(
+                "TEST test={} expected_ns={}",
+                test, expected_ns
+            ));
+        }
+        None => {
+            info!("timing: test={}", test);
+            #[cfg(feature = "serial-console")]
+            crate::console::reply(format_args!("TEST test={}", test));
+        }
+    }
+}
+
+pub struct PeriodMeasure {
+    start: Instant,
+    periods: u32,
+    total: u64,
+    #[cfg(feature = "temperature")]
+    temp: TempSensor,
+    #[cfg(feature = "ci-assert")]
+    ci: CiAssert,
+}
+
+impl PeriodMeasure {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            periods: 0,
+            total: 0,
+            #[cfg(feature = "temperature")]
+            temp: TempSensor::steal(),
+            #[cfg(feature = "ci-assert")]
+            ci: CiAssert::new(),
+        }
+    }
+
+    // Counts a period, reporting the measured period when due.
+    #[inline(always)]
+    pub fn period(&mut self) {
+        self.periods += 1;
+        if self.periods.is_multiple_of(CHECK_PERIODS) {
+            self.check();
+        }
+    }
+
+    #[inline(never)]
+    fn check(&mut self) {
+        let elapsed = Instant::now() - self.start;
+        if elapsed < REPORT_INTERVAL {
+            return;
+        }
+
+        let period_ns = elapsed.as_micros() * 1000 / self.periods as u64;
+        self.total += self.periods as u64;
+        info!(
+            ": Measured period: {}ns, half period: {}ns ({} periods)",
+            period_ns,
+            period_ns / 2,
+            self.periods
+        );
+        info!(
+            "timing: period_ns={} half_period_ns={} periods={} total={}",
+            period_ns,
+            period_ns / 2,
+            self.periods,
+            self.total
+        );
+        // Before start is reset, so the time to send isn't measured.
+        #[cfg(feature = "serial-console")]
+        crate::console::reply(format_args!(
+            "RESULT period_ns={} half_period_ns={} periods={} total={}",
+            period_ns,
+            period_ns / 2,
+            self.periods,
+            self.total
+        ));
+
+        #[cfg(feature = "temperature")]
+        self.temp.poll();
+        #[cfg(feature = "ci-assert")]
+        self.ci.report(period_ns);
+
+        self.periods = 0;
+        self.start = Instant::now();
+    }
+}
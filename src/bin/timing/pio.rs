@@ -0,0 +1,106 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// PIO test.  Toggles GPIO 2 from a PIO state machine, using a two
+// instruction program:
+//
+//   set pins, 1 [DELAY]
+//   set pins, 0 [DELAY]
+//
+// wrapped so the loop back costs nothing.  Each instruction takes 1 + DELAY
+// state machine cycles, so the period is exactly 2 * (1 + DELAY) SM cycles,
+// and an SM cycle is DIVIDER clk_sys cycles.  Like the PWM test, once
+// started the CPU isn't involved, so there's no jitter.
+//
+// The program is assembled at runtime, so DELAY can be anything from 0 to
+// 31 - the full delay field, as no side-set is used.  Only an integer
+// divider is used, as a fractional divider would add jitter to the edges.
+//
+// The RP2350 has three PIO blocks to the RP2040's two, and a newer PIO
+// version with extra instructions.  This program only uses instructions
+// common to both, and PIO0, which both chips have, and GPIO 2 is reachable
+// from PIO0 on both, so the same code runs on the Pico and Pico 2.
+
+use defmt::{error, info};
+use embassy_rp::bind_interrupts;
+use embassy_rp::peripherals::PIO0;
+use embassy_rp::pio::program::{Assembler, RP2040_MAX_PROGRAM_SIZE, SetDestination};
+use embassy_rp::pio::{Config, Direction, InterruptHandler, Pio};
+
+use crate::Test;
+
+// Delay in SM cycles after each edge, from 0 to 31, and the SM clock
+// divider, from 1 to 65535.
+pub const DELAY: u8 = 0;
+pub const DIVIDER: u16 = 1;
+
+// The largest delay which fits in an instruction without side-set.
+const MAX_DELAY: u8 = 31;
+
+bind_interrupts!(struct Irqs {
+    PIO0_IRQ_0 => InterruptHandler<PIO0>;
+});
+
+impl Test {
+    pub async fn pio(delay: u8, divider: u16) -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq() as u64;
+        info!("PIO test");
+        info!(": PIO0 SM0 on GPIO 2");
+
+        if delay > MAX_DELAY || divider == 0 {
+            error!(
+                ": Delay must be from 0 to {}, and divider from 1 to 65535",
+                MAX_DELAY
+            );
+            Self::idle();
+        }
+
+        let mut a = Assembler::<RP2040_MAX_PROGRAM_SIZE>::new();
+        let mut wrap_target = a.label();
+        let mut wrap_source = a.label();
+        a.bind(&mut wrap_target);
+        a.set_with_delay(SetDestination::PINS, 1, delay);
+        a.set_with_delay(SetDestination::PINS, 0, delay);
+        a.bind(&mut wrap_source);
+        let program = a.assemble_with_wrap(wrap_source, wrap_target);
+
+        let Pio {
+            mut common,
+            mut sm0,
+            ..
+        } = Pio::new(p.PIO0, Irqs);
+        let loaded = common.load_program(&program);
+        let pin = common.make_pio_pin(p.PIN_2);
+
+        let mut config = Config::default();
+        config.use_program(&loaded, &[]);
+        config.set_set_pins(&[&pin]);
+        config.clock_divider = divider.into();
+        sm0.set_config(&config);
+        sm0.set_pin_dirs(Direction::Out, &[&pin]);
+
+        let sm_cycles = 2 * (1 + delay as u64);
+        let period_cycles = sm_cycles * divider as u64;
+        info!(
+            ": Delay: {}, divider: {}, SM clock: {} Hz",
+            delay,
+            divider,
+            speed / divider as u64
+        );
+        info!(
+            ": Period: {} SM cycles, {} cycles ({}ns), frequency {} Hz",
+            sm_cycles,
+            period_cycles,
+            period_cycles * 1_000_000_000 / speed,
+            speed / period_cycles
+        );
+
+        sm0.set_enable(true);
+
+        info!(": Running");
+        Self::idle()
+    }
+}
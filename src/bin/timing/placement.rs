@@ -0,0 +1,222 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Toggle placement test.  Toggles OUTPUT_PIN (GPIO 2 by default) with the
+// minimum period asm loop, run from either XIP flash (toggle-in-flash) or
+// RAM (toggle-in-ram), as selected at build time.
+//
+// Before toggling forever, bounded versions of the loop are timed from both
+// flash and RAM, so the jitter of each is reported whichever is selected.
+// The XIP cache's hit and access counters are recorded across each, showing
+// how much of the flash version was served from the cache.  On the Pico, the
+// cache is flushed before the first flash sample, so that it includes the
+// cache misses - the Pico 2's cache has no equivalent flush, so all its
+// samples are likely to be cache hits.
+//
+// The RAM versions are placed in .data.ram_func.  cortex-m-rt's linker
+// script collects .data.* into .data, which is copied from flash to RAM at
+// startup, so no memory.x changes are needed to reserve the RAM - both
+// link/memory.rp2040.x and link/memory.rp235x.x have the single RAM region
+// .data is placed in.  The striped RAM is used, rather than the RP2040's
+// SCRATCH or the RP2350's SRAM4/5 banks, so the stack and any DMA share the
+// loop's banks, but this test has neither running while it toggles.  A
+// MEMORY_X_PATH replacement must keep a RAM region of the same name.
+//
+// The test checks the RAM functions' addresses are within .data, from
+// cortex-m-rt's __sdata and __edata, before timing them, in case a change to
+// the linker script places them elsewhere.  Check the disassembly if
+// changing the build profile, as the RAM version must not call out to
+// anything in flash.
+
+#[cfg(all(feature = "toggle-in-flash", feature = "toggle-in-ram"))]
+compile_error!("Only one of toggle-in-flash and toggle-in-ram can be selected");
+
+use defmt::{assert, info};
+use embassy_pico_test::gpio_asm::{asm_load_gpio_out_addr, set_gpio_high, set_gpio_low};
+use embassy_rp::gpio::{AnyPin, Level, Output};
+use embassy_rp::pac;
+
+use crate::measurement::{Measurement, MeasurementResult};
+use crate::{GPIO_OUT, GPIO_OUT_CLR, GPIO_OUT_SET, OUTPUT_MASK, OUTPUT_PIN, Test};
+
+// Periods to time for each sample.
+const PERIODS: u32 = 1_000;
+
+// Number of samples from each of flash and RAM.
+const SAMPLES: u32 = 32;
+
+// A bounded version of asm_toggle_period_min(), as in the min-period test,
+// with GPIO_OUT passed in r0 explicitly.  Generated once for flash and
+// once for RAM, so both have identical code.
+macro_rules! toggle_periods_min {
+    ($name:ident $(, $attr:meta)*) => {
+        $(#[$attr])*
+        #[inline(never)]
+        fn $name(periods: u32) {
+            for _ in 0..periods {
+                unsafe {
+                    core::arch::asm!(
+                        ".if {mask} < 0x100",
+                        "movs r1, #{mask}",
+                        ".else",
+                        "movs r1, #1",
+                        "lsls r1, r1, #{pin}",
+                        ".endif",
+                        "str r1, [r0, #{set}]",
+                        ".if {mask} < 0x100",
+                        "movs r1, #{mask}",
+                        ".else",
+                        "movs r1, #1",
+                        "lsls r1, r1, #{pin}",
+                        ".endif",
+                        "str r1, [r0, #{clr}]",
+                        mask = const OUTPUT_MASK,
+                        pin = const OUTPUT_PIN,
+                        set = const GPIO_OUT_SET - GPIO_OUT,
+                        clr = const GPIO_OUT_CLR - GPIO_OUT,
+                        in("r0") GPIO_OUT,
+                        out("r1") _,
+                    );
+                }
+            }
+        }
+    };
+}
+
+unsafe extern "C" {
+    // From cortex-m-rt's link.x
+    static __sdata: u8;
+    static __edata: u8;
+}
+
+// Timings from one location
+struct PlacementResult {
+    period: MeasurementResult,
+    cache_hits: u32,
+    cache_accesses: u32,
+}
+
+impl Test {
+    pub async fn toggle_placement() -> ! {
+        let _p = Self::init();
+
+        info!("Toggle placement test");
+        info!(": Using GPIO {}", OUTPUT_PIN);
+        #[cfg(feature = "toggle-in-flash")]
+        info!(": Selected: XIP flash");
+        #[cfg(feature = "toggle-in-ram")]
+        info!(": Selected: RAM");
+
+        // Safety: The typed pin peripherals aren't used by this test, so the
+        // pin is only instantiated once.
+        let pin = unsafe { AnyPin::steal(OUTPUT_PIN) };
+        let mut output = Output::new(pin, Level::Low);
+
+        Self::check_in_ram(
+            "Bounded RAM loop",
+            Self::toggle_periods_min_ram as *const (),
+        );
+        Self::check_in_ram("RAM loop", Self::ram_toggle_period_min as *const ());
+        info!(
+            ": Bounded flash loop at {:#010x}",
+            Self::toggle_periods_min_flash as *const () as usize & !1
+        );
+
+        Self::systick_start();
+
+        #[cfg(feature = "pico")]
+        {
+            pac::XIP_CTRL.flush().write(|w| w.set_flush(true));
+            // Reading stalls until the flush completes.
+            let _ = pac::XIP_CTRL.flush().read();
+        }
+        let flash = Self::time_placement(Self::toggle_periods_min_flash);
+        let ram = Self::time_placement(Self::toggle_periods_min_ram);
+
+        for (location, r) in [("Flash", &flash), ("RAM", &ram)] {
+            info!(
+                ": {}: {}, jitter {}ns, XIP cache hits {}/{}",
+                location,
+                r.period,
+                r.period.max_ns - r.period.min_ns,
+                r.cache_hits,
+                r.cache_accesses
+            );
+        }
+        info!(
+            ": Jitter difference (flash - RAM): {}ns",
+            (flash.period.max_ns - flash.period.min_ns) as i32
+                - (ram.period.max_ns - ram.period.min_ns) as i32
+        );
+
+        output.set_low();
+        info!(": Starting");
+        #[cfg(feature = "toggle-in-flash")]
+        Self::asm_toggle_period_min();
+        #[cfg(feature = "toggle-in-ram")]
+        Self::ram_toggle_period_min();
+    }
+
+    // Logs the address of a function, asserting it was linked into .data,
+    // so runs from RAM.
+    fn check_in_ram(name: &str, function: *const ()) {
+        // Clear the Thumb bit.
+        let addr = function as usize & !1;
+        let start = (&raw const __sdata) as usize;
+        let end = (&raw const __edata) as usize;
+        info!(": {} at {:#010x}", name, addr);
+        assert!(
+            (start..end).contains(&addr),
+            "{} isn't in .data ({:#010x}-{:#010x})",
+            name,
+            start,
+            end
+        );
+    }
+
+    // Times SAMPLES samples of PERIODS periods, with interrupts disabled,
+    // recording the XIP cache counters over them.
+    fn time_placement(toggle: fn(u32)) -> PlacementResult {
+        // Writing any value clears the counters.
+        pac::XIP_CTRL.ctr_hit().write_value(0);
+        pac::XIP_CTRL.ctr_acc().write_value(0);
+
+        // Samples are in thousandths of a cycle per period.
+        let mut measurement = Measurement::new();
+        for _ in 0..SAMPLES {
+            let cycles = cortex_m::interrupt::free(|_| {
+                let start = Self::systick_now();
+                toggle(PERIODS);
+                Self::systick_elapsed(start, Self::systick_now())
+            });
+            measurement.add((cycles as u64 * 1000 / PERIODS as u64) as u32);
+        }
+
+        let speed = embassy_rp::clocks::clk_sys_freq() as u64;
+        PlacementResult {
+            period: measurement.result(|mc| (mc as u64 * 1_000_000 / speed) as u32),
+            cache_hits: pac::XIP_CTRL.ctr_hit().read(),
+            cache_accesses: pac::XIP_CTRL.ctr_acc().read(),
+        }
+    }
+
+    toggle_periods_min!(toggle_periods_min_flash);
+    toggle_periods_min!(toggle_periods_min_ram, unsafe(link_section = ".data.ram_func"));
+
+    // asm_toggle_period_min(), placed in RAM.
+    #[inline(never)]
+    #[unsafe(link_section = ".data.ram_func")]
+    fn ram_toggle_period_min() -> ! {
+        // Safety: r0 holds GPIO_OUT throughout, as the loop is only the asm
+        // helpers, none of which change it.
+        unsafe {
+            asm_load_gpio_out_addr();
+
+            loop {
+                set_gpio_high::<OUTPUT_PIN>(); // 2 cycles
+                set_gpio_low::<OUTPUT_PIN>(); // 2 cycles
+            }
+        }
+    }
+}
@@ -0,0 +1,27 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Pre-test idle, enabled by the pre-test-idle feature, which holds off every
+// test's first edge for IDLE_MS, so a scope triggering on the first edge
+// after a gap, or with a long trigger holdoff, sees a clean one.  Without
+// it, the edges start straight after the banner, only microseconds after
+// reset.
+//
+// The idle is at the end of Test::init(), which every test calls before
+// creating its outputs, so applies to all of them.  The GPIOs aren't driven
+// until then, and are held low by their reset pull-downs, so there are no
+// edges.  With test-sweep, the low gap between tests is IDLE_MS too, in
+// place of its usual 10ms - see test_sweep.rs.
+
+use embassy_time::{Duration, block_for};
+
+// Milliseconds to idle before each test's edges begin.
+pub const IDLE_MS: u64 = 100;
+pub const IDLE: Duration = Duration::from_millis(IDLE_MS);
+
+// Idles for IDLE_MS.  Blocks, as Test::init() isn't async.
+pub fn idle() {
+    detail!(": pre-test-idle: {}ms before the first edge", IDLE_MS);
+    block_for(IDLE);
+}
@@ -0,0 +1,167 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Propagation delay test.  Drives an external gate's input from one GPIO,
+// senses its output on another, and measures the delay between them, over
+// many rising and falling edges.
+//
+// Each edge is timed with SysTick, from just before the drive store until
+// the sense pin is seen to change, polling GPIO_IN.  Inputs pass through a
+// 2 cycle synchroniser, and the polling adds its own overhead, so the same
+// measurement is also made with the drive pin sensing itself, through its
+// own pad.  Subtracting that baseline removes the synchroniser latency and
+// the measurement overhead, leaving the external delay.
+//
+// Individual edges are only resolved to the polling loop's period, a few
+// cycles.  The mean over SAMPLES edges resolves the delay more finely, but
+// only as far as the gate's delay jitters across the polling loop's sample
+// points.
+//
+// The sense pin is timed until it changes, rather than until it reaches a
+// level, so inverting and non-inverting gates both work.  If it doesn't
+// change within TIMEOUT_POLLS polls, the gate is assumed to be unwired.
+
+use defmt::{error, info, warn};
+use embassy_rp::gpio::{AnyPin, Input, Level, Output, Pull};
+use embassy_time::Timer;
+
+use crate::measurement::{Measurement, MeasurementResult};
+use crate::{GPIO_IN, GPIO_OUT_CLR, GPIO_OUT_SET, NUM_GPIOS, Test};
+
+// Pins to drive the gate's input and sense its output.
+pub const DRIVE_PIN: u8 = 2;
+pub const SENSE_PIN: u8 = 3;
+
+// Number of each edge to time.
+const SAMPLES: u32 = 1_000;
+
+// Maximum polls of the sense pin before giving up on an edge.
+const TIMEOUT_POLLS: u32 = 10_000;
+
+// Rising and falling edge propagation delays
+#[derive(Clone, Copy, defmt::Format)]
+pub struct PropDelayResult {
+    pub rising: MeasurementResult,
+    pub falling: MeasurementResult,
+}
+
+impl Test {
+    pub async fn prop_delay(drive_pin: u8, sense_pin: u8) -> Option<PropDelayResult> {
+        let _p = Self::init();
+
+        info!("Propagation delay test");
+        info!(": Drive GPIO {}, sense GPIO {}", drive_pin, sense_pin);
+
+        if drive_pin >= NUM_GPIOS || sense_pin >= NUM_GPIOS || drive_pin == sense_pin {
+            error!(
+                ": Drive and sense must be different GPIOs below {}",
+                NUM_GPIOS
+            );
+            return None;
+        }
+
+        // Safety: The typed pin peripherals aren't used by this test.
+        let _drive = Output::new(unsafe { AnyPin::steal(drive_pin) }, Level::Low);
+        let _sense = Input::new(unsafe { AnyPin::steal(sense_pin) }, Pull::None);
+        let drive_mask = 1 << drive_pin;
+        let sense_mask = 1 << sense_pin;
+
+        Self::systick_start();
+        Timer::after_millis(1).await;
+
+        let inverting = Self::sio_read(GPIO_IN) & sense_mask != 0;
+        info!(
+            ": Gate is {}",
+            if inverting {
+                "inverting"
+            } else {
+                "non-inverting"
+            }
+        );
+
+        info!(": Starting");
+        let Some((base_rise, base_fall)) = Self::time_edges(drive_mask, drive_mask).await else {
+            error!(": GPIO {} didn't see its own edges", drive_pin);
+            return None;
+        };
+        let Some((gate_rise, gate_fall)) = Self::time_edges(drive_mask, sense_mask).await else {
+            warn!(": No response on GPIO {} - is the gate wired?", sense_pin);
+            return None;
+        };
+
+        let base = base_rise.result(|mc| mc);
+        info!(
+            ": Baseline (synchroniser and overhead): {}.{:03} cycles",
+            base.mean_ns / 1000,
+            base.mean_ns % 1000
+        );
+
+        let result = PropDelayResult {
+            rising: Self::prop_delay_result(&gate_rise, &base_rise),
+            falling: Self::prop_delay_result(&gate_fall, &base_fall),
+        };
+        for (edge, r) in [("rising", result.rising), ("falling", result.falling)] {
+            info!(
+                ": Drive {}: min {}ns, typical {}ns, max {}ns",
+                edge, r.min_ns, r.mean_ns, r.max_ns
+            );
+        }
+        info!(": Done");
+
+        Some(result)
+    }
+
+    // Subtracts the baseline from the gate's edge timings, converting to
+    // nanoseconds.  Timings are in thousandths of a cycle.
+    fn prop_delay_result(gate: &Measurement, base: &Measurement) -> MeasurementResult {
+        let gate = gate.result(|mc| mc);
+        let base = base.result(|mc| mc);
+        let to_ns = Self::millicycles_to_ns;
+        MeasurementResult {
+            min_ns: to_ns(gate.min_ns.saturating_sub(base.min_ns)),
+            max_ns: to_ns(gate.max_ns.saturating_sub(base.min_ns)),
+            mean_ns: to_ns(gate.mean_ns.saturating_sub(base.mean_ns)),
+            samples: gate.samples,
+        }
+    }
+
+    fn millicycles_to_ns(millicycles: u32) -> u32 {
+        let speed = embassy_rp::clocks::clk_sys_freq() as u64;
+        (millicycles as u64 * 1_000_000 / speed) as u32
+    }
+
+    // Times SAMPLES rising and falling edges, in thousandths of a cycle,
+    // returning None if any edge times out.  The drive pin must start low.
+    async fn time_edges(drive_mask: u32, sense_mask: u32) -> Option<(Measurement, Measurement)> {
+        let mut rising = Measurement::new();
+        let mut falling = Measurement::new();
+        for _ in 0..SAMPLES {
+            for (reg, measurement) in [(GPIO_OUT_SET, &mut rising), (GPIO_OUT_CLR, &mut falling)] {
+                let cycles =
+                    cortex_m::interrupt::free(|_| Self::time_edge(reg, drive_mask, sense_mask))?;
+                measurement.add(cycles * 1000);
+
+                // Let the gate settle before the next edge.
+                Timer::after_micros(10).await;
+            }
+        }
+        Some((rising, falling))
+    }
+
+    // Writes drive_mask to reg, and returns the cycles until the sense pin
+    // changes.  Not inlined, so the baseline and gate measurements are
+    // timed by identical code.
+    #[inline(never)]
+    fn time_edge(reg: u32, drive_mask: u32, sense_mask: u32) -> Option<u32> {
+        let before = Self::sio_read(GPIO_IN) & sense_mask;
+        let start = Self::systick_now();
+        Self::sio_write(reg, drive_mask);
+        for _ in 0..TIMEOUT_POLLS {
+            if Self::sio_read(GPIO_IN) & sense_mask != before {
+                return Some(Self::systick_elapsed(start, Self::systick_now()));
+            }
+        }
+        None
+    }
+}
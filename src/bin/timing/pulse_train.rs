@@ -0,0 +1,88 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Pulse train test.  Waits for a rising edge on GPIO 3, then emits exactly
+// COUNT pulses at FREQ on GPIO 2, with a 50% duty cycle, and re-arms.  This
+// simulates a stepper motor controller's step output, or an encoder, for
+// testing step-counting devices downstream.
+//
+// Each pulse's edges are placed at cycle offsets from the start of the
+// pulse, counted with SysTick with interrupts disabled, as in the ws2812
+// test.  The period is rounded to a whole number of cycles, so the frequency
+// actually emitted is logged.  The loop counts whole pulses, and GPIO 2 is
+// low both before the first and after the last, so there is never a partial
+// pulse, and the line always returns to idle low.
+//
+// As interrupts are disabled for the whole train, trigger edges during it
+// are ignored - the next train starts on the first rising edge after this
+// one completes.  GPIO 3 is pulled down, so is idle low if unconnected.
+
+use defmt::{error, info};
+use embassy_rp::gpio::{Input, Level, Output, Pull};
+
+use crate::{GPIO_OUT_CLR, GPIO_OUT_SET, GPIO2_MASK, SYSTICK_MAX, Test};
+
+// Number of pulses per train.
+pub const COUNT: u32 = 200;
+
+// Pulse frequency, in Hz.
+pub const FREQ: u32 = 10_000;
+
+// Shortest period which the SysTick wait loop can place edges accurately
+// for, and longest which SysTick can time.
+const MIN_PERIOD_CYCLES: u32 = 64;
+const MAX_PERIOD_CYCLES: u32 = SYSTICK_MAX;
+
+impl Test {
+    pub async fn pulse_train(count: u32, freq: u32) -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        info!("Pulse train test");
+        info!(": Output on GPIO 2, trigger on GPIO 3 rising edge");
+        info!(": Count: {}, frequency: {} Hz", count, freq);
+
+        let period = (speed + freq / 2).checked_div(freq).unwrap_or(0);
+        if !(MIN_PERIOD_CYCLES..=MAX_PERIOD_CYCLES).contains(&period) {
+            error!(
+                ": Frequency must be {} to {} Hz",
+                speed / MAX_PERIOD_CYCLES + 1,
+                speed / MIN_PERIOD_CYCLES
+            );
+            Self::idle();
+        }
+        info!(
+            ": Period: {} cycles, so actual frequency: {}.{:03} Hz",
+            period,
+            speed / period,
+            (speed % period) as u64 * 1000 / period as u64
+        );
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+        let mut trigger = Input::new(p.PIN_3, Pull::Down);
+
+        Self::systick_start();
+
+        loop {
+            info!(": Armed");
+            trigger.wait_for_rising_edge().await;
+            cortex_m::interrupt::free(|_| Self::send_pulses(count, period));
+            info!(": Emitted {} pulses", count);
+        }
+    }
+
+    // Emits count pulses of period cycles on GPIO 2, which must be low.
+    // Must be called with interrupts disabled, and with SysTick started.
+    fn send_pulses(count: u32, period: u32) {
+        let high = period / 2;
+        let mut pulse_start = Self::systick_now();
+        for _ in 0..count {
+            Self::sio_write(GPIO_OUT_SET, GPIO2_MASK);
+            Self::wait_until_cycles(pulse_start, high);
+            Self::sio_write(GPIO_OUT_CLR, GPIO2_MASK);
+            Self::wait_until_cycles(pulse_start, period);
+            pulse_start = pulse_start.wrapping_sub(period) & SYSTICK_MAX;
+        }
+    }
+}
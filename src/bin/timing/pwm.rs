@@ -0,0 +1,72 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// PWM test.  Outputs a 50% duty square wave on GPIO 2 from PWM slice 1's A
+// channel, which is the slice which owns GPIO 2, as a jitter-free baseline
+// to compare the software toggling tests against.  Once configured, the
+// hardware generates the wave, with no CPU involvement.
+//
+// The PWM counter runs at clk_sys divided by an 8 bit integer divider, and
+// wraps at a 16 bit TOP, so the period is TOP + 1 divided clock cycles.
+// The smallest divider which lets TOP fit is used, for the finest
+// resolution, and the frequency actually generated is logged as it is
+// rounded to a whole number of divided clock cycles.  For a second,
+// inverted, output on GPIO 3, see the pwm-pair test.
+
+use defmt::{error, info};
+use embassy_rp::pwm::{Config, Pwm};
+
+use crate::Test;
+
+// Frequency to output, in Hz.
+pub const FREQUENCY: u32 = 100_000;
+
+impl Test {
+    pub async fn pwm(frequency: u32) -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq() as u64;
+        info!("PWM test");
+        info!(": PWM slice 1, A on GPIO 2");
+        info!(": Requested frequency: {} Hz", frequency);
+
+        // Round to the nearest whole number of cycles.
+        let cycles = (speed + frequency as u64 / 2)
+            .checked_div(frequency as u64)
+            .unwrap_or(0);
+        let divider = cycles.div_ceil(1 << 16).max(1);
+        if cycles < 2 || divider > u8::MAX as u64 {
+            error!(
+                ": Frequency must be from {} Hz to {} Hz",
+                speed.div_ceil(255 * 65536),
+                speed / 2
+            );
+            Self::idle();
+        }
+        let top = (cycles / divider - 1) as u16;
+
+        let mut config = Config::default();
+        config.divider = (divider as u8).into();
+        config.top = top;
+        // A is high while the counter is below compare_a, so for half of
+        // the TOP + 1 counts.
+        config.compare_a = (top as u32).div_ceil(2) as u16;
+
+        let period_cycles = (top as u64 + 1) * divider;
+        info!(
+            ": Divider: {}, TOP: {}, compare: {}",
+            divider, top, config.compare_a
+        );
+        info!(
+            ": Actual frequency: {} Hz, period {}ns",
+            speed / period_cycles,
+            period_cycles * 1_000_000_000 / speed
+        );
+
+        let _pwm = Pwm::new_output_a(p.PWM_SLICE1, p.PIN_2, config);
+
+        info!(": Running");
+        Self::idle()
+    }
+}
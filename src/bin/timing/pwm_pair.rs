@@ -0,0 +1,77 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// PWM complementary pair test.  Outputs a 50% duty square wave on GPIO 2
+// from PWM slice 1's A channel, and its inverse on GPIO 3 from the same
+// slice's B channel, using the slice's B output invert.  Once configured,
+// the hardware generates both, with no CPU involvement, so the pair is
+// glitch-free for as long as it runs.
+//
+// Both channels compare against the slice's one counter, with the same
+// compare value, so their edges are inherently phase-locked - B is exactly
+// A inverted, bar any difference in pad delay.  Each slice outputs on a
+// fixed pair of GPIOs, GPIO 2n as A and 2n + 1 as B, with the slices
+// repeating every 16 GPIOs, so GPIOs 2 and 3 (and 18 and 19) are slice 1.
+// Pwm::new_output_ab() only accepts the slice's own A and B pins, so the
+// pair is checked at compile time.
+//
+// The PWM counter runs at clk_sys divided by an 8 bit integer divider, and
+// wraps at a 16 bit TOP, so the period is rounded to a whole number of
+// divided clock cycles.  The period actually generated is logged.
+
+use defmt::{error, info};
+use embassy_rp::pwm::{Config, Pwm};
+use embassy_time::Duration;
+
+use crate::Test;
+
+// Period of the pair.
+pub const PERIOD: Duration = Duration::from_micros(10);
+
+impl Test {
+    pub async fn pwm_pair(period: Duration) -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq() as u64;
+        info!("PWM complementary pair test");
+        info!(": PWM slice 1, A on GPIO 2, inverted B on GPIO 3");
+
+        // Use the smallest divider which lets TOP fit, for the finest
+        // resolution.
+        let cycles = period.as_micros() * speed / 1_000_000;
+        let divider = cycles.div_ceil(1 << 16).max(1);
+        if cycles < 2 || divider > u8::MAX as u64 {
+            error!(
+                ": Period must be from 1us to {}us",
+                255 * 65536 * 1_000_000 / speed
+            );
+            Self::idle();
+        }
+        let top = (cycles / divider - 1) as u16;
+
+        let mut config = Config::default();
+        config.divider = (divider as u8).into();
+        config.top = top;
+        // A is high while the counter is below compare_a, so for half of
+        // the TOP + 1 counts.
+        config.compare_a = (top as u32).div_ceil(2) as u16;
+        config.compare_b = config.compare_a;
+        config.invert_b = true;
+
+        let actual_ns = (top as u64 + 1) * divider * 1_000_000_000 / speed;
+        info!(
+            ": Period: requested {}us, actual {}ns (divider {}, TOP {})",
+            period.as_micros(),
+            actual_ns,
+            divider,
+            top
+        );
+        info!(": Inversion is by the PWM hardware - no CPU involvement");
+
+        let _pwm = Pwm::new_output_ab(p.PWM_SLICE1, p.PIN_2, p.PIN_3, config);
+
+        info!(": Running");
+        Self::idle()
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Remapped pin toggle test.  Toggles a pin that is normally used for
+// something else - either a bank 0 GPIO wired to a board function, or the
+// QSPI flash chip select - by forcing it to the SIO function.
+//
+// QSPI SCLK and SD0-3 are the active flash clock and data lines, and
+// toggling them makes XIP impossible, so they are rejected outright.  QSPI
+// SS (chip select, also the BOOTSEL button) can be toggled, but only from
+// code running from RAM with interrupts disabled, as once it is remapped no
+// code can be fetched from flash.  This is the same technique embassy-rp
+// uses to read the BOOTSEL button, except this never hands SS back to XIP,
+// so nothing can be logged once the toggling starts.
+
+use core::arch::asm;
+use defmt::{error, info, warn};
+use embassy_rp::gpio::{AnyPin, Level, Output};
+use embassy_rp::pac;
+
+use crate::{BOARD, GPIO_OUT_CLR, GPIO_OUT_SET, NUM_GPIOS, Test};
+
+// Pins that may be selected for remapping.
+#[derive(Clone, Copy, defmt::Format)]
+pub enum RemapPin {
+    Gpio(u8),
+    QspiSclk,
+    QspiSs,
+    QspiSd(u8),
+}
+
+// The pin to toggle.
+pub const PIN: RemapPin = RemapPin::QspiSs;
+
+// IO_QSPI pin index and SIO GPIO_HI bit for QSPI SS.  The RP235X's IO_QSPI
+// has the USB PHY pins first, and its GPIO_HI registers put the QSPI pins
+// above GPIOs 32-47 and the USB pins.
+#[cfg(feature = "pico")]
+const QSPI_SS_IO: usize = 1;
+#[cfg(feature = "pico")]
+const QSPI_SS_HI_BIT: u32 = 1;
+#[cfg(feature = "pico2")]
+const QSPI_SS_IO: usize = 3;
+#[cfg(feature = "pico2")]
+const QSPI_SS_HI_BIT: u32 = 27;
+
+// IO function select value for SIO
+const FUNCSEL_SIO: u32 = 5;
+
+impl Test {
+    pub async fn toggle_remapped(pin: RemapPin) {
+        let _p = Self::init();
+
+        info!("Remapped pin toggle test");
+        info!(": Pin: {}", pin);
+
+        match pin {
+            RemapPin::QspiSclk | RemapPin::QspiSd(_) => {
+                error!(": {} is an active flash line - refusing to remap it", pin);
+            }
+            RemapPin::Gpio(gpio) if gpio >= NUM_GPIOS => {
+                error!(": GPIO {} doesn't exist", gpio);
+            }
+            RemapPin::Gpio(gpio) => Self::toggle_board_gpio(gpio),
+            RemapPin::QspiSs => {
+                warn!(": !!! Remapping QSPI SS (flash chip select) to SIO !!!");
+                warn!(": !!! XIP will stop working - only a reset will recover !!!");
+                warn!(": !!! Don't press BOOTSEL - it pulls SS low via 1K !!!");
+                warn!(": No further logging is possible");
+                info!(": Starting");
+
+                let sio_hi_out = pac::SIO.gpio_out(1);
+                let sio_hi_oe = pac::SIO.gpio_oe(1);
+
+                // Safety: Interrupts are disabled, so nothing will execute
+                // from flash once SS has been remapped.
+                cortex_m::interrupt::disable();
+                unsafe {
+                    Self::remap_and_toggle_from_ram(
+                        pac::IO_QSPI.gpio(QSPI_SS_IO).ctrl().as_ptr() as *mut u32,
+                        sio_hi_oe.value_set().as_ptr(),
+                        sio_hi_out.value_set().as_ptr(),
+                        sio_hi_out.value_clr().as_ptr(),
+                        1 << QSPI_SS_HI_BIT,
+                    )
+                }
+            }
+        }
+    }
+
+    // Toggles one of the bank 0 GPIOs, warning if it's wired to something
+    // on the board.
+    fn toggle_board_gpio(gpio: u8) -> ! {
+        #[cfg(not(any(feature = "pico-w", feature = "pico2-w")))]
+        let board_net = match gpio {
+            23 => Some("SMPS power save"),
+            24 => Some("VBUS sense"),
+            25 => Some("the LED"),
+            29 => Some("VSYS/3 ADC sense"),
+            _ => None,
+        };
+        #[cfg(any(feature = "pico-w", feature = "pico2-w"))]
+        let board_net = match gpio {
+            23 => Some("CYW43 power on"),
+            24 => Some("CYW43 SPI data"),
+            25 => Some("CYW43 SPI chip select"),
+            29 => Some("CYW43 SPI clock and VSYS/3 ADC sense"),
+            _ => None,
+        };
+        if let Some(net) = board_net {
+            warn!(
+                ": !!! GPIO {} is wired to {} on the {} !!!",
+                gpio, net, BOARD
+            );
+            #[cfg(not(any(feature = "pico-w", feature = "pico2-w")))]
+            warn!(": !!! On a Pico W/Pico 2 W it is a CYW43 line - don't use !!!");
+        }
+
+        // Safety: The typed pin peripherals aren't used by this test.
+        let _output = Output::new(unsafe { AnyPin::steal(gpio) }, Level::Low);
+        let mask = 1 << gpio;
+
+        info!(": Starting");
+        loop {
+            Self::sio_write(GPIO_OUT_SET, mask);
+            Self::sio_write(GPIO_OUT_CLR, mask);
+        }
+    }
+
+    // Drives the pin low, enables its output, switches it to SIO, and then
+    // toggles it forever.  This is placed in RAM, and is written entirely
+    // in asm so it can't call anything in flash.
+    //
+    // Safety: Interrupts must be disabled, and nothing else (core 1, DMA)
+    // may access flash.
+    #[inline(never)]
+    #[unsafe(link_section = ".data.ram_func")]
+    unsafe fn remap_and_toggle_from_ram(
+        io_ctrl: *mut u32,
+        oe_set: *mut u32,
+        out_set: *mut u32,
+        out_clr: *mut u32,
+        mask: u32,
+    ) -> ! {
+        unsafe {
+            asm!(
+                "str {mask}, [{out_clr}]",
+                "str {mask}, [{oe_set}]",
+                "str {funcsel}, [{io_ctrl}]",
+                "2:",
+                "str {mask}, [{out_set}]",
+                "str {mask}, [{out_clr}]",
+                "b 2b",
+                io_ctrl = in(reg) io_ctrl,
+                oe_set = in(reg) oe_set,
+                out_set = in(reg) out_set,
+                out_clr = in(reg) out_clr,
+                mask = in(reg) mask,
+                funcsel = in(reg) FUNCSEL_SIO,
+                options(noreturn, nostack),
+            );
+        }
+    }
+}
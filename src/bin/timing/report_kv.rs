@@ -0,0 +1,92 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Key=value result reporting.  Writes a measurement result as a single line
+// of plain text key=value pairs, so it can be scraped by a trivial host-side
+// script without any defmt tooling, for example:
+//
+// test=min-period mean_ns=2140 min_ns=2010 max_ns=2600 samples=8 board=pico
+//
+// report_kv() writes to any core::fmt::Write sink.  KvUart provides one over
+// UART0 TX on GPIO 0, at KV_BAUD, 8N1.  This is in addition to the defmt
+// output, which is unchanged.  KvUart is also the serial console's
+// transmitter, with serial-console.
+//
+// Values never contain spaces, so the board is given by its feature name
+// rather than BOARD.
+
+use core::fmt::{self, Write};
+use defmt::warn;
+use embassy_rp::peripherals::{PIN_0, UART0};
+use embassy_rp::uart::{Blocking, Config, UartTx};
+
+#[cfg(feature = "serial-report")]
+use crate::measurement::MeasurementResult;
+#[cfg(feature = "serial-report")]
+use crate::{IS_PICO2, Test};
+
+#[cfg(feature = "serial-report")]
+pub const KV_BAUD: u32 = 115_200;
+
+// A core::fmt::Write sink over UART0 TX.
+pub struct KvUart {
+    tx: UartTx<'static, UART0, Blocking>,
+}
+
+impl KvUart {
+    pub fn new(baud: u32) -> Self {
+        let mut config = Config::default();
+        config.baudrate = baud;
+        // Safety: The test selected doesn't use UART0 or GPIO 0.  With
+        // serial-console, the selector's UartRx shares UART0, but this is
+        // configured the same, and only touches GPIO 0.
+        let (uart, pin) = unsafe { (UART0::steal(), PIN_0::steal()) };
+        Self {
+            tx: UartTx::new_blocking(uart, pin, config),
+        }
+    }
+}
+
+impl Write for KvUart {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.tx.blocking_write(s.as_bytes()).map_err(|_| fmt::Error)
+    }
+}
+
+impl Drop for KvUart {
+    fn drop(&mut self) {
+        // Make sure the line has left the FIFO.
+        let _ = self.tx.blocking_flush();
+    }
+}
+
+#[cfg(feature = "serial-report")]
+impl Test {
+    // Writes result, from the named test, as a line of key=value pairs.
+    pub fn report_kv(
+        test: &str,
+        result: &MeasurementResult,
+        writer: &mut impl Write,
+    ) -> fmt::Result {
+        write!(
+            writer,
+            "test={} mean_ns={} min_ns={} max_ns={} samples={} board={}\r\n",
+            test,
+            result.mean_ns,
+            result.min_ns,
+            result.max_ns,
+            result.samples,
+            if IS_PICO2 { "pico2" } else { "pico" }
+        )
+    }
+
+    // Writes result, from the named test, as a line of key=value pairs over
+    // UART0.
+    pub fn report_kv_uart(test: &str, result: &MeasurementResult) {
+        let mut uart = KvUart::new(KV_BAUD);
+        if Self::report_kv(test, result, &mut uart).is_err() {
+            warn!(": Failed to write key=value result to UART");
+        }
+    }
+}
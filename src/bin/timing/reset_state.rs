@@ -0,0 +1,208 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Reset state test.  Captures what GPIO 2 looks like coming out of reset,
+// before anything has configured it, and how long it is until the pin is
+// actively driven.
+//
+// After reset GPIO 2's function is NULL (so it is undriven) and its pad has
+// the pull-down enabled, so the pin is only weakly held low until it is
+// configured.  Note that embassy_rp::init() resets IO_BANK0 and PADS_BANK0,
+// so even with the early-drive feature, which drives the pin low at the very
+// top of main(), the pin returns to this undriven state during init until
+// Output::new() is called.
+//
+// Time from reset to main() (bootrom, boot2 and runtime init) can't be
+// measured from firmware, as nothing accurate is clocked until
+// embassy_rp::init() runs - use a scope on RUN and GPIO 2 for that.  Before
+// init, the core runs from the imprecise ROSC, so time from main() to the
+// first drive is reported in cycles only.
+
+use defmt::{info, warn};
+use embassy_rp::gpio::{Level, Output};
+use embassy_rp::pac;
+use embassy_time::Instant;
+
+use crate::{GPIO_OUT_CLR, Test};
+
+const GPIO: usize = 2;
+const GPIO_MASK: u32 = 1 << GPIO;
+
+// IO_BANK0 GPIO function select values
+const FUNCSEL_SIO: u8 = 5;
+const FUNCSEL_NULL: u8 = 0x1f;
+
+// GPIO 2's IO and pad configuration
+#[derive(Clone, Copy)]
+struct PinState {
+    io_out_of_reset: bool,
+    pads_out_of_reset: bool,
+    ctrl: pac::io::regs::GpioCtrl,
+    status: pac::io::regs::GpioStatus,
+    pad: pac::pads::regs::GpioCtrl,
+}
+
+impl PinState {
+    // Reads GPIO 2's current state.  IO_BANK0 and PADS_BANK0 may still be
+    // held in reset, in which case their registers aren't read.
+    fn read() -> Self {
+        let done = pac::RESETS.reset_done().read();
+        let io_out_of_reset = done.io_bank0();
+        let pads_out_of_reset = done.pads_bank0();
+        let (ctrl, status) = if io_out_of_reset {
+            let gpio = pac::IO_BANK0.gpio(GPIO);
+            (gpio.ctrl().read(), gpio.status().read())
+        } else {
+            (Default::default(), Default::default())
+        };
+        let pad = if pads_out_of_reset {
+            pac::PADS_BANK0.gpio(GPIO).read()
+        } else {
+            Default::default()
+        };
+        Self {
+            io_out_of_reset,
+            pads_out_of_reset,
+            ctrl,
+            status,
+            pad,
+        }
+    }
+
+    fn log(&self) {
+        if self.io_out_of_reset {
+            let funcsel = self.ctrl.funcsel();
+            info!(
+                ":   IO: funcsel {}{}, output enabled to pad {}, level to pad {}",
+                funcsel,
+                if funcsel == FUNCSEL_NULL {
+                    " (NULL)"
+                } else {
+                    ""
+                },
+                self.status.oetopad(),
+                self.status.outtopad()
+            );
+        } else {
+            info!(":   IO: IO_BANK0 held in reset (pin undriven)");
+        }
+        if self.pads_out_of_reset {
+            info!(
+                ":   Pad: pull-down {}, pull-up {}, input enabled {}, output disabled {}",
+                self.pad.pde(),
+                self.pad.pue(),
+                self.pad.ie(),
+                self.pad.od()
+            );
+            #[cfg(feature = "pico2")]
+            info!(":   Pad: isolated {}", self.pad.iso());
+        } else {
+            info!(":   Pad: PADS_BANK0 held in reset (pull-down default)");
+        }
+    }
+}
+
+// GPIO 2's state captured at the very top of main()
+pub struct ResetState {
+    pin: PinState,
+    entry_cycles: u32,
+    early_drive_cycles: Option<u32>,
+}
+
+impl Test {
+    // Captures GPIO 2's state as it comes out of reset, and, with the
+    // early-drive feature, drives it low as soon as possible.  Must be the
+    // first thing main() does.
+    pub fn capture_reset_state() -> ResetState {
+        Self::systick_start();
+        let entry_cycles = Self::systick_now();
+        let pin = PinState::read();
+
+        #[cfg(not(feature = "early-drive"))]
+        let early_drive_cycles = None;
+        #[cfg(feature = "early-drive")]
+        let early_drive_cycles = {
+            Self::raw_drive_low();
+            Some(Self::systick_now())
+        };
+
+        ResetState {
+            pin,
+            entry_cycles,
+            early_drive_cycles,
+        }
+    }
+
+    // Drives GPIO 2 low using raw register accesses, without reference to
+    // embassy, taking IO_BANK0 and PADS_BANK0 out of reset if required.
+    #[cfg(feature = "early-drive")]
+    fn raw_drive_low() {
+        pac::RESETS.reset().modify(|w| {
+            w.set_io_bank0(false);
+            w.set_pads_bank0(false);
+        });
+        loop {
+            let done = pac::RESETS.reset_done().read();
+            if done.io_bank0() && done.pads_bank0() {
+                break;
+            }
+        }
+
+        Self::sio_write(GPIO_OUT_CLR, GPIO_MASK);
+        pac::SIO.gpio_oe(0).value_set().write_value(GPIO_MASK);
+        pac::PADS_BANK0.gpio(GPIO).modify(|w| {
+            w.set_ie(true);
+            w.set_od(false);
+            #[cfg(feature = "pico2")]
+            w.set_iso(false);
+        });
+        pac::IO_BANK0
+            .gpio(GPIO)
+            .ctrl()
+            .write(|w| w.set_funcsel(FUNCSEL_SIO));
+    }
+
+    pub async fn reset_state(state: ResetState) -> ! {
+        let init_start_cycles = Self::systick_now();
+        let p = Self::init();
+        let after_init = PinState::read();
+        let _output = Output::new(p.PIN_2, Level::Low);
+        let first_drive_cycles = Self::systick_now();
+        let undriven_since_init = Instant::now();
+
+        info!("Reset state test");
+        info!(": Using GPIO {}", GPIO);
+        info!(": State at entry to main():");
+        state.pin.log();
+        info!(": State after embassy_rp::init():");
+        after_init.log();
+
+        let entry = state.entry_cycles;
+        match state.early_drive_cycles {
+            Some(early) => {
+                info!(
+                    ": Early drive: main() to driven low: {} cycles",
+                    Self::systick_elapsed(entry, early)
+                );
+                warn!(": Early drive was undone by embassy_rp::init() resetting IO_BANK0");
+            }
+            None => info!(": Early drive: disabled"),
+        }
+        info!(
+            ": main() to embassy_rp::init(): {} cycles",
+            Self::systick_elapsed(entry, init_start_cycles)
+        );
+        info!(
+            ": main() to Output::new() driving: {} cycles (clk_sys changes during init)",
+            Self::systick_elapsed(entry, first_drive_cycles)
+        );
+        info!(
+            ": Undriven from init's IO reset to Output::new(): ~{}us",
+            undriven_since_init.as_micros()
+        );
+
+        info!(": Done");
+        Self::idle()
+    }
+}
@@ -0,0 +1,241 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Result log.  Keeps a history of measurement results in flash, along with
+// the git hash of the firmware that took them, so timing regressions can be
+// spotted across firmware versions.
+//
+// build.rs reserves SECTORS sectors at the end of flash for the log.  Each
+// entry has a sequence number, and is written to the next erased slot after
+// the latest entry, so the log forms a ring.  Flash is only erased when the
+// log wraps into a sector, which erases the oldest entries, and leaves the
+// other sector's history intact.  Nothing is erased at boot, and each
+// sector is erased once every ENTRIES results.
+//
+// An interrupted write leaves an entry which fails its checksum.  It is
+// skipped, and the next result is written to the following erased slot.
+
+use defmt::{info, warn};
+use embassy_rp::flash::{Blocking, ERASE_SIZE, Error, FLASH_BASE, Flash};
+use embassy_rp::peripherals::FLASH;
+
+use crate::Test;
+use crate::measurement::MeasurementResult;
+
+// Flash size, from the FLASH region of the memory.x the build used - the
+// board's, or MEMORY_X_PATH's - as exposed by build.rs.
+const FLASH_SIZE: usize = parse_size(env!("FLASH_SIZE"));
+
+// Number of sectors in the log.  Must match build.rs's RESULT_LOG_SIZE.
+const SECTORS: u32 = 2;
+
+// Entries are 8 words - magic, sequence number, git hash, the result, and a
+// checksum.
+const ENTRY_WORDS: usize = 8;
+const ENTRY_SIZE: u32 = (ENTRY_WORDS * 4) as u32;
+const ENTRIES_PER_SECTOR: u32 = ERASE_SIZE as u32 / ENTRY_SIZE;
+const ENTRIES: u32 = SECTORS * ENTRIES_PER_SECTOR;
+
+// Marks a written entry ("RLOG").
+const MAGIC: u32 = 0x524c_4f47;
+
+// Number of results dump_result_history() reports.
+const HISTORY_ENTRIES: u32 = 16;
+
+unsafe extern "C" {
+    // Start of the flash reserved for the log, from memory.x
+    static __result_log_start: u8;
+}
+
+type LogFlash = Flash<'static, FLASH, Blocking, FLASH_SIZE>;
+
+// Parses build.rs's decimal FLASH_SIZE, at compile time.
+const fn parse_size(size: &str) -> usize {
+    let digits = size.as_bytes();
+    assert!(!digits.is_empty(), "FLASH_SIZE is empty");
+    let mut value = 0;
+    let mut i = 0;
+    while i < digits.len() {
+        assert!(digits[i].is_ascii_digit(), "FLASH_SIZE isn't a number");
+        value = value * 10 + (digits[i] - b'0') as usize;
+        i += 1;
+    }
+    value
+}
+
+#[derive(Clone, Copy)]
+struct Entry {
+    seq: u32,
+    git_hash: u32,
+    result: MeasurementResult,
+}
+
+impl Entry {
+    fn to_words(self) -> [u32; ENTRY_WORDS] {
+        let r = self.result;
+        let mut words = [
+            MAGIC,
+            self.seq,
+            self.git_hash,
+            r.min_ns,
+            r.max_ns,
+            r.mean_ns,
+            r.samples,
+            0,
+        ];
+        words[ENTRY_WORDS - 1] = checksum(&words);
+        words
+    }
+
+    fn from_words(words: &[u32; ENTRY_WORDS]) -> Option<Self> {
+        if words[0] != MAGIC || words[ENTRY_WORDS - 1] != checksum(words) {
+            return None;
+        }
+        Some(Self {
+            seq: words[1],
+            git_hash: words[2],
+            result: MeasurementResult {
+                min_ns: words[3],
+                max_ns: words[4],
+                mean_ns: words[5],
+                samples: words[6],
+            },
+        })
+    }
+}
+
+// Checksums all but the last (checksum) word.
+fn checksum(words: &[u32; ENTRY_WORDS]) -> u32 {
+    !words[..ENTRY_WORDS - 1]
+        .iter()
+        .fold(0, |acc: u32, word| acc.rotate_left(7) ^ word)
+}
+
+// The firmware's git hash, or 0 if it wasn't known at build time.
+fn git_hash() -> u32 {
+    u32::from_str_radix(env!("GIT_HASH"), 16).unwrap_or(0)
+}
+
+// Offset of the log from the start of flash
+fn log_offset() -> u32 {
+    (&raw const __result_log_start) as u32 - FLASH_BASE as u32
+}
+
+fn slot_offset(slot: u32) -> u32 {
+    log_offset() + slot * ENTRY_SIZE
+}
+
+fn read_slot(flash: &mut LogFlash, slot: u32) -> Result<[u32; ENTRY_WORDS], Error> {
+    let mut bytes = [0; ENTRY_SIZE as usize];
+    flash.blocking_read(slot_offset(slot), &mut bytes)?;
+    let mut words = [0; ENTRY_WORDS];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+        *word = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    Ok(words)
+}
+
+// Returns the slot and entry with the highest sequence number.
+fn latest(flash: &mut LogFlash) -> Result<Option<(u32, Entry)>, Error> {
+    let mut latest: Option<(u32, Entry)> = None;
+    for slot in 0..ENTRIES {
+        if let Some(entry) = Entry::from_words(&read_slot(flash, slot)?)
+            && latest.is_none_or(|(_, l)| entry.seq > l.seq)
+        {
+            latest = Some((slot, entry));
+        }
+    }
+    Ok(latest)
+}
+
+fn log_flash() -> LogFlash {
+    // Safety: Nothing else uses the flash peripheral.
+    Flash::new_blocking(unsafe { FLASH::steal() })
+}
+
+impl Test {
+    // Appends result to the log.
+    pub fn log_result_to_flash(result: MeasurementResult) {
+        let mut flash = log_flash();
+        match Self::append_entry(&mut flash, result) {
+            Ok((slot, seq)) => info!(": Logged result #{} to flash slot {}", seq, slot),
+            Err(e) => warn!(": Failed to log result to flash: {}", e),
+        }
+    }
+
+    // Reports the most recent results in the log, newest first.
+    pub fn dump_result_history() {
+        let mut flash = log_flash();
+        if let Err(e) = Self::dump_entries(&mut flash) {
+            warn!(": Failed to read result log: {}", e);
+        }
+    }
+
+    fn append_entry(flash: &mut LogFlash, result: MeasurementResult) -> Result<(u32, u32), Error> {
+        let (mut slot, seq) = match latest(flash)? {
+            Some((slot, entry)) => ((slot + 1) % ENTRIES, entry.seq.wrapping_add(1)),
+            None => (0, 0),
+        };
+
+        // Find the next erased slot.  If the log wraps into the next sector
+        // first, that holds the oldest entries, so erase it.
+        loop {
+            if read_slot(flash, slot)?.iter().all(|&word| word == u32::MAX) {
+                break;
+            }
+            if slot % ENTRIES_PER_SECTOR == 0 {
+                let start = slot_offset(slot);
+                flash.blocking_erase(start, start + ERASE_SIZE as u32)?;
+                break;
+            }
+            slot = (slot + 1) % ENTRIES;
+        }
+
+        let entry = Entry {
+            seq,
+            git_hash: git_hash(),
+            result,
+        };
+        let mut bytes = [0; ENTRY_SIZE as usize];
+        for (chunk, word) in bytes.chunks_exact_mut(4).zip(entry.to_words()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        flash.blocking_write(slot_offset(slot), &bytes)?;
+
+        Ok((slot, seq))
+    }
+
+    fn dump_entries(flash: &mut LogFlash) -> Result<(), Error> {
+        info!(": Firmware git hash: {}", env!("GIT_HASH"));
+        let Some((mut slot, latest)) = latest(flash)? else {
+            info!(": Result log is empty");
+            return Ok(());
+        };
+
+        // Walk back from the latest entry, stopping once the sequence
+        // numbers stop decreasing, as that is where the log wrapped.
+        info!(": Last results, newest first:");
+        let mut next_seq = latest.seq.wrapping_add(1);
+        let mut reported = 0;
+        for _ in 0..ENTRIES {
+            if let Some(entry) = Entry::from_words(&read_slot(flash, slot)?) {
+                if entry.seq >= next_seq {
+                    break;
+                }
+                info!(
+                    ":   #{} ({:08x}): {}",
+                    entry.seq, entry.git_hash, entry.result
+                );
+                next_seq = entry.seq;
+                reported += 1;
+                if reported == HISTORY_ENTRIES {
+                    break;
+                }
+            }
+            slot = (slot + ENTRIES - 1) % ENTRIES;
+        }
+
+        Ok(())
+    }
+}
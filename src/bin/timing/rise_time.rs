@@ -0,0 +1,167 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Rise time test.  Drives a rising edge on GPIO 2, samples the node with the
+// ADC on GPIO 26 (ADC0) as fast as it will go during the transition, and
+// reports the 10-90% rise time, for each drive strength in turn.
+//
+// Wire GPIO 2 to GPIO 26, with a capacitor from GPIO 26 to ground, such as
+// 1uF.  The unloaded pad's edges are a few ns, far too fast for the ADC, so
+// the capacitor slows them.  The rise time is then set by the capacitor and
+// the pad's output impedance, which falls as the drive strength goes up, so
+// stronger drive gives faster edges.  GPIO 2 is set to the slow slew rate,
+// although with a capacitor this large that makes little difference.
+//
+// Each ADC conversion takes 96 of the ADC's 48MHz clock cycles, so samples
+// are at best 2us apart.  Each sample is timestamped with SysTick, and the
+// 10% and 90% crossings are interpolated between samples.  At least
+// MIN_EDGE_SAMPLES samples must fall between the two crossings for the
+// result to mean anything, so the minimum measurable rise time is around
+// 6us.  Faster edges are reported with a warning, and no result - use a
+// bigger capacitor.  Slower edges must still settle within the SAMPLES
+// sample capture window, around 2ms.
+
+use defmt::{info, warn};
+use embassy_rp::adc::{self, Adc, Channel};
+use embassy_rp::gpio::{Drive, Level, Output, Pull, SlewRate};
+use embassy_time::{Duration, Timer};
+
+use crate::{GPIO_OUT_SET, GPIO2_MASK, Test};
+
+// Number of ADC samples to capture, from the start of the edge.
+const SAMPLES: usize = 1024;
+
+// Samples at the end of the capture averaged for the settled high level.
+const SETTLED_SAMPLES: usize = 16;
+
+// Minimum samples between the 10% and 90% crossings.
+const MIN_EDGE_SAMPLES: usize = 3;
+
+// Minimum low to high swing, in ADC counts (of 4096), to count as an edge.
+const MIN_SWING: u16 = 1024;
+
+// Time the node is held low before each edge, to fully discharge.
+const DISCHARGE: Duration = Duration::from_millis(50);
+
+// Time between each set of measurements.
+const REPORT_INTERVAL: Duration = Duration::from_secs(2);
+
+impl Test {
+    pub async fn rise_time() -> ! {
+        let p = Self::init();
+
+        info!("Rise time test");
+        info!(": Driving GPIO 2, sampling GPIO 26 (ADC0)");
+
+        let mut output = Output::new(p.PIN_2, Level::Low);
+        output.set_slew_rate(SlewRate::Slow);
+        let mut adc = Adc::new_blocking(p.ADC, adc::Config::default());
+        let mut channel = Channel::new_pin(p.PIN_26, Pull::None);
+
+        Self::systick_start();
+
+        info!(": Starting");
+        loop {
+            for (drive, name) in [
+                (Drive::_2mA, "2mA"),
+                (Drive::_4mA, "4mA"),
+                (Drive::_8mA, "8mA"),
+                (Drive::_12mA, "12mA"),
+            ] {
+                if let Some(ns) =
+                    Self::measure_rise_time(&mut output, &mut adc, &mut channel, drive).await
+                {
+                    info!(": {}: rise time (10-90%) {}ns", name, ns);
+                }
+            }
+            Timer::after(REPORT_INTERVAL).await;
+        }
+    }
+
+    // Drives a rising edge with the given drive strength, and returns its
+    // 10-90% rise time in ns, or None if it couldn't be measured.  Leaves
+    // the output low.
+    async fn measure_rise_time(
+        output: &mut Output<'_>,
+        adc: &mut Adc<'_, adc::Blocking>,
+        channel: &mut Channel<'_>,
+        drive: Drive,
+    ) -> Option<u32> {
+        output.set_drive_strength(drive);
+        output.set_low();
+        Timer::after(DISCHARGE).await;
+
+        // Timestamps are cycles since the edge was driven.
+        let mut values = [0u16; SAMPLES];
+        let mut times = [0u32; SAMPLES];
+        let mut failed = false;
+        let low = cortex_m::interrupt::free(|_| {
+            let low = adc.blocking_read(channel).unwrap_or(0);
+            let start = Self::systick_now();
+            Self::sio_write(GPIO_OUT_SET, GPIO2_MASK);
+            for (value, time) in values.iter_mut().zip(times.iter_mut()) {
+                *value = adc.blocking_read(channel).unwrap_or_else(|_| {
+                    failed = true;
+                    0
+                });
+                *time = Self::systick_elapsed(start, Self::systick_now());
+            }
+            low
+        });
+        output.set_low();
+
+        if failed {
+            warn!(": ADC read failed");
+            return None;
+        }
+
+        let settled = &values[SAMPLES - SETTLED_SAMPLES..];
+        let high = (settled.iter().map(|&v| v as u32).sum::<u32>() / SETTLED_SAMPLES as u32) as u16;
+        if high < low + MIN_SWING {
+            warn!(
+                ": No edge seen ({} to {}) - is GPIO 2 wired to GPIO 26?",
+                low, high
+            );
+            return None;
+        }
+        let swing = high - low;
+        if settled.iter().any(|&v| v < high - swing / 50) {
+            warn!(
+                ": Edge still rising at end of {}us capture - use a smaller capacitor",
+                Self::cycles_to_ns(times[SAMPLES - 1]) / 1000
+            );
+            return None;
+        }
+
+        let (i10, t10) = Self::crossing(&values, &times, low, low + swing / 10)?;
+        let (i90, t90) = Self::crossing(&values, &times, low, low + swing - swing / 10)?;
+        if i90 - i10 < MIN_EDGE_SAMPLES {
+            let interval = times[SAMPLES - 1] / (SAMPLES as u32 - 1);
+            warn!(
+                ": Edge too fast for the ADC to resolve - minimum measurable rise time {}ns",
+                Self::cycles_to_ns(MIN_EDGE_SAMPLES as u32 * interval)
+            );
+            return None;
+        }
+
+        Some(Self::cycles_to_ns(t90 - t10))
+    }
+
+    // Returns the index of the first sample at or over threshold, and the
+    // interpolated time, in cycles, at which the threshold was crossed.  The
+    // node was at low when the edge was driven, at time 0.
+    fn crossing(values: &[u16], times: &[u32], low: u16, threshold: u16) -> Option<(usize, u32)> {
+        let i = values.iter().position(|&v| v >= threshold)?;
+        let (prev_value, prev_time) = match i {
+            0 => (low, 0),
+            _ => (values[i - 1], times[i - 1]),
+        };
+        let rise = (values[i] - prev_value.min(values[i])).max(1) as u32;
+        let fraction = threshold.saturating_sub(prev_value) as u32;
+        Some((
+            i,
+            prev_time + (times[i] - prev_time) * fraction.min(rise) / rise,
+        ))
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Runtime test selector, enabled by the runtime-select feature.  Rather than
+// running the single GPIO test selected by a number feature, main blocks
+// until a test number is entered as a line on UART0 RX (GPIO 1, at
+// SELECT_BAUD, 8N1), and then runs it.  So T1 to T28 can be swept without
+// rebuilding or reflashing - reset the board (for example with
+// "probe-rs reset") to select the next.
+//
+// A line is a test number, optionally prefixed by T or by the serial
+// console's RUN command, such as "7", "T7" or "RUN 7", ended by CR or LF.
+// If a number feature is also enabled, an empty line runs that test, so
+// existing workflows only need an extra Enter.  Invalid lines are logged and
+// ignored.  With serial-console, the selector also replies to each line on
+// UART0 TX - see console.rs.
+//
+// Only the single GPIO tests are selectable - the other test types are
+// compiled in by their features, as before.
+
+use defmt::{info, warn};
+use embassy_rp::peripherals::{PIN_1, UART0};
+use embassy_rp::uart::{Blocking, Config, UartRx};
+
+use crate::{AVAILABLE_TESTS, TestNum};
+
+pub const SELECT_BAUD: u32 = 115_200;
+
+// Longest line accepted.
+const MAX_LINE: usize = 12;
+
+// Reads the single GPIO test to run from UART0 RX.
+pub struct TestSelector<'d> {
+    rx: UartRx<'d, UART0, Blocking>,
+    // Whether the last byte was a CR, so the LF of a CRLF isn't taken as an
+    // empty line.
+    last_cr: bool,
+}
+
+impl<'d> TestSelector<'d> {
+    pub fn new(uart: &'d mut UART0, pin: &'d mut PIN_1) -> Self {
+        let mut config = Config::default();
+        config.baudrate = SELECT_BAUD;
+        Self {
+            rx: UartRx::new_blocking(uart, pin, config),
+            last_cr: false,
+        }
+    }
+
+    // Blocks until a valid test number, or an empty line with a default, is
+    // received.
+    pub fn select(&mut self, default: Option<TestNum>) -> TestNum {
+        info!(
+            "Test selector: enter a test number ({}) on UART0 RX (GPIO 1, {} 8N1)",
+            AVAILABLE_TESTS, SELECT_BAUD
+        );
+        if let Some(default) = default {
+            info!(": Or an empty line for test {}", default as i32);
+        }
+        #[cfg(feature = "serial-console")]
+        {
+            info!(
+                ": Replies on UART0 TX (GPIO 0, {} 8N1)",
+                crate::console::CONSOLE_BAUD
+            );
+            crate::console::reply(format_args!("READY tests=1-{}", TestNum::ALL.len()));
+        }
+
+        loop {
+            let mut line = [0u8; MAX_LINE];
+            let Some(len) = self.read_line(&mut line) else {
+                warn!(": Line too long or UART error - ignored");
+                #[cfg(feature = "serial-console")]
+                crate::console::reply(format_args!("ERR line too long"));
+                continue;
+            };
+
+            let line = line[..len].trim_ascii();
+            let test_num = if line.is_empty() {
+                match default {
+                    Some(default) => default,
+                    None => continue,
+                }
+            } else {
+                match Self::parse(line) {
+                    Some(test_num) => test_num,
+                    None => {
+                        warn!(": Invalid test number {=[u8]:a} - ignored", line);
+                        #[cfg(feature = "serial-console")]
+                        crate::console::reply(format_args!(
+                            "ERR invalid test {}",
+                            core::str::from_utf8(line).unwrap_or("?")
+                        ));
+                        continue;
+                    }
+                }
+            };
+
+            info!(": Selected test {}", test_num as i32);
+            #[cfg(feature = "serial-console")]
+            crate::console::reply(format_args!("OK RUN {}", test_num as u8));
+            return test_num;
+        }
+    }
+
+    // Parses a non-empty line, such as "7", "T7" or "RUN 7", returning the
+    // test, if valid.
+    fn parse(line: &[u8]) -> Option<TestNum> {
+        let digits = if line.len() > 3 && line[..3].eq_ignore_ascii_case(b"RUN") {
+            line[3..].trim_ascii_start()
+        } else {
+            line.strip_prefix(b"T")
+                .or(line.strip_prefix(b"t"))
+                .unwrap_or(line)
+        };
+        let number = core::str::from_utf8(digits).ok()?.parse::<u8>().ok()?;
+        TestNum::from_number(number)
+    }
+
+    // Reads bytes into line until CR or LF, returning the line's length, or
+    // None if it overflowed or a byte was lost.  The rest of a bad line is
+    // consumed.
+    fn read_line(&mut self, line: &mut [u8]) -> Option<usize> {
+        let mut len = 0;
+        let mut ok = true;
+        loop {
+            let mut byte = [0u8];
+            if self.rx.blocking_read(&mut byte).is_err() {
+                ok = false;
+                continue;
+            }
+            let last_cr = core::mem::replace(&mut self.last_cr, byte[0] == b'\r');
+            match byte[0] {
+                b'\n' if last_cr && len == 0 && ok => continue,
+                b'\r' | b'\n' => return if ok { Some(len) } else { None },
+                b if len < line.len() => {
+                    line[len] = b;
+                    len += 1;
+                }
+                _ => ok = false,
+            }
+        }
+    }
+}
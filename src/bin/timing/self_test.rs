@@ -0,0 +1,166 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Cycle helper self-test, enabled by the self-test feature.  Runs once at
+// startup, before the selected test, and times each of the asm pause
+// helpers, and a few delay_cycles() lengths, with SysTick, logging the
+// cycles each took and warning about any which don't take the cycles their
+// name or documentation claim.  This catches a compiler or architecture
+// surprise, such as a helper no longer being inlined, or the M33
+// dual-issuing instructions the counts assume are issued one at a time.
+//
+// SysTick counts core cycles on both the Pico and the Pico 2, so it's used
+// on both, rather than the Pico 2's DWT cycle counter, so the two boards
+// are timed the same way.  Each helper is bracketed by two SysTick reads,
+// with interrupts disabled, SAMPLES times, and the fastest taken, so the
+// first run's flash cache misses don't count.  The cost of the bracket
+// itself, timed the same way with nothing between the reads, is subtracted.
+//
+// GpioToggler::toggle_n_cycles() is then timed per period, for a few half
+// periods, with Test::systick_measure().  Each is run for SHORT_PERIODS and
+// LONG_PERIODS periods, the fastest of SAMPLES runs of each taken, and the
+// difference divided by the extra periods, so its setup and the SysTick
+// reads cancel out.  It toggles GPIO 2's output latch, but the pad isn't
+// driven, as the test hasn't configured it yet.
+//
+// The helpers are hand counted for the M0+, so on the Pico a mismatch is a
+// real problem.  The Pico 2's counts are estimates, and a helper which
+// pairs with the bracket's reads can measure a cycle or so either side, so
+// a mismatch there is worth a look, not necessarily a fault.  Either way,
+// the test selected still runs.
+
+use defmt::{info, warn};
+use embassy_pico_test::gpio_asm::{
+    GpioToggler, asm_1_cycle_r2, asm_2_cycles_add_r2, asm_3_cycles_add_r2, asm_5_cycles_r2,
+    asm_9_cycles_add_r2, asm_9_cycles_nop, asm_10_cycles_add_r2, asm_10_cycles_nop, delay_cycles,
+};
+
+use crate::{GPIO2_MASK, IS_PICO2, Test};
+
+// Times each helper is run, of which the fastest is taken.
+const SAMPLES: u32 = 8;
+
+// Periods of each of the two toggle_n_cycles() runs, whose difference is
+// timed.
+const SHORT_PERIODS: u32 = 10;
+const LONG_PERIODS: u32 = 110;
+
+// Returns the fewest cycles, over SAMPLES runs, between two SysTick reads
+// around the code given, including the reads themselves.
+macro_rules! time_cycles {
+    ($($code:tt)*) => {{
+        let mut fewest = u32::MAX;
+        for _ in 0..SAMPLES {
+            let start = Test::systick_now();
+            $($code)*
+            let end = Test::systick_now();
+            fewest = fewest.min(Test::systick_elapsed(start, end));
+        }
+        fewest
+    }};
+}
+
+// Times a helper, returning its name, its expected cycles and the fewest
+// cycles it was measured taking, including the SysTick reads.
+macro_rules! time_helper {
+    ($helper:path, $expected:expr) => {
+        (stringify!($helper), $expected, time_cycles!($helper();))
+    };
+}
+
+impl Test {
+    pub fn self_test() {
+        info!("Cycle helper self-test");
+        if IS_PICO2 {
+            info!(": Pico 2 counts are estimates - see self_test.rs");
+        }
+        Self::systick_start();
+
+        let toggler = GpioToggler::new(GPIO2_MASK);
+        let (overhead, results, toggles) = cortex_m::interrupt::free(|_| {
+            let overhead = time_cycles!();
+            let results = [
+                time_helper!(asm_1_cycle_r2, 1),
+                time_helper!(asm_2_cycles_add_r2, 2),
+                time_helper!(asm_3_cycles_add_r2, 3),
+                time_helper!(asm_5_cycles_r2, 5),
+                time_helper!(asm_9_cycles_add_r2, 9),
+                time_helper!(asm_10_cycles_add_r2, 10),
+                time_helper!(asm_9_cycles_nop, 9),
+                time_helper!(asm_10_cycles_nop, 10),
+                time_helper!(delay_cycles::<20>, 20),
+                time_helper!(delay_cycles::<100>, 100),
+            ];
+            let toggles = [
+                Self::time_toggler::<
+                    { GpioToggler::MIN_HIGH_CYCLES },
+                    { GpioToggler::MIN_LOW_CYCLES },
+                >(&toggler),
+                Self::time_toggler::<5, 7>(&toggler),
+                Self::time_toggler::<10, 10>(&toggler),
+            ];
+            (overhead, results, toggles)
+        });
+
+        // Leave SysTick as it was at reset, for the test selected.
+        // Safety: Nothing else (including embassy) uses SysTick.
+        unsafe { cortex_m::Peripherals::steal() }
+            .SYST
+            .disable_counter();
+
+        info!(": Bracket overhead: {} cycles, subtracted", overhead);
+        let mut mismatches = 0;
+        for (name, expected, cycles) in results {
+            let cycles = cycles.saturating_sub(overhead);
+            if cycles == expected {
+                info!(": {}: {} cycles", name, cycles);
+            } else {
+                warn!(": {}: {} cycles, expected {}", name, cycles, expected);
+                mismatches += 1;
+            }
+        }
+
+        for (high, low, cycles) in toggles {
+            if cycles == high + low {
+                info!(
+                    ": GpioToggler::toggle_n_cycles::<{}, {}>(): {} cycles per period",
+                    high, low, cycles
+                );
+            } else {
+                warn!(
+                    ": GpioToggler::toggle_n_cycles::<{}, {}>(): {} cycles per period, expected {}",
+                    high,
+                    low,
+                    cycles,
+                    high + low
+                );
+                mismatches += 1;
+            }
+        }
+
+        let total = results.len() + toggles.len();
+        if mismatches == 0 {
+            info!(": All {} helpers match", total);
+        } else {
+            warn!(": {} of {} helpers don't match", mismatches, total);
+        }
+    }
+
+    // Returns HIGH and LOW, and the cycles per period toggler's
+    // toggle_n_cycles() takes with them.
+    fn time_toggler<const HIGH: u32, const LOW: u32>(toggler: &GpioToggler) -> (u32, u32, u32) {
+        let mut short = u32::MAX;
+        let mut long = u32::MAX;
+        for _ in 0..SAMPLES {
+            short = short.min(Self::systick_measure(|| {
+                toggler.toggle_n_cycles::<HIGH, LOW>(SHORT_PERIODS)
+            }));
+            long = long.min(Self::systick_measure(|| {
+                toggler.toggle_n_cycles::<HIGH, LOW>(LONG_PERIODS)
+            }));
+        }
+        let cycles = long.saturating_sub(short) / (LONG_PERIODS - SHORT_PERIODS);
+        (HIGH, LOW, cycles)
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Soak test, for leaving running for hours or days.  Toggles GPIO 2 with
+// GpioToggler::toggle_n_cycles() in bursts, forever, feeding the watchdog
+// after each burst and logging a heartbeat, with the number of bursts so
+// far, every HEARTBEAT_SECS.  If the loop stalls, the watchdog isn't fed
+// and resets the chip after WATCHDOG_MS, and the next boot logs that the
+// reset was the watchdog's.
+//
+// The watchdog is paused while a debugger halts the cores, so stepping
+// through with a probe doesn't reset the chip.  The burst count restarts
+// from zero after a reset.
+//
+// Each burst is with interrupts disabled, and is much shorter than the
+// watchdog period, so there's no risk of a healthy loop being reset.
+//
+// With the temperature feature, the die temperature is also read and logged
+// between bursts, when due - see temperature.rs.
+
+use defmt::{info, warn};
+use embassy_pico_test::gpio_asm::GpioToggler;
+use embassy_rp::gpio::{Level, Output};
+use embassy_rp::watchdog::{ResetReason, Watchdog};
+use embassy_time::{Duration, Instant};
+
+#[cfg(feature = "temperature")]
+use crate::temperature::TempSensor;
+use crate::{GPIO2_MASK, Test};
+
+// Cycles GPIO 2 is high and then low for.
+const HIGH_CYCLES: u32 = 10;
+const LOW_CYCLES: u32 = 10;
+
+// Periods per burst, around 16ms on a Pico at 125MHz.
+const BURST_PERIODS: u32 = 100_000;
+
+// Watchdog period, and the time between heartbeats.
+const WATCHDOG_MS: u64 = 1_000;
+const HEARTBEAT_SECS: u64 = 5;
+
+impl Test {
+    pub async fn soak() -> ! {
+        let p = Self::init();
+
+        let period = HIGH_CYCLES + LOW_CYCLES;
+        info!("Soak test");
+        info!(": Using GPIO 2");
+        info!(
+            ": Period: {} cycles ({}ns), {} periods per burst",
+            period,
+            Self::cycles_to_ns(period),
+            BURST_PERIODS
+        );
+        info!(
+            ": Watchdog: {}ms, heartbeat every {}s",
+            WATCHDOG_MS, HEARTBEAT_SECS
+        );
+
+        let mut watchdog = Watchdog::new(p.WATCHDOG);
+        match watchdog.reset_reason() {
+            Some(ResetReason::TimedOut) => warn!(": Last reset was a watchdog timeout"),
+            Some(ResetReason::Forced) => info!(": Last reset was forced by the watchdog"),
+            None => info!(": Last reset was not by the watchdog"),
+        }
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+        let toggler = GpioToggler::new(GPIO2_MASK);
+        #[cfg(feature = "temperature")]
+        let mut temp = TempSensor::new(p.ADC, p.ADC_TEMP_SENSOR);
+
+        watchdog.pause_on_debug(true);
+        watchdog.start(Duration::from_millis(WATCHDOG_MS));
+
+        info!(": Starting");
+        let heartbeat = Duration::from_secs(HEARTBEAT_SECS);
+        let start = Instant::now();
+        let mut next_heartbeat = start + heartbeat;
+        let mut bursts: u64 = 0;
+        loop {
+            cortex_m::interrupt::free(|_| {
+                toggler.toggle_n_cycles::<HIGH_CYCLES, LOW_CYCLES>(BURST_PERIODS)
+            });
+            watchdog.feed();
+            bursts += 1;
+            #[cfg(feature = "temperature")]
+            temp.poll();
+
+            let now = Instant::now();
+            if now >= next_heartbeat {
+                info!(
+                    ": Heartbeat: {} bursts, {}s",
+                    bursts,
+                    (now - start).as_secs()
+                );
+                next_heartbeat += heartbeat;
+            }
+        }
+    }
+}
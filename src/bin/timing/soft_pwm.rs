@@ -0,0 +1,94 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Software PWM test.  Toggles GPIO 2 with a period of PERIOD_NS, high for
+// DUTY_PERCENT of it, from GpioToggler::toggle_forever(), as the target
+// period test does for a 50% duty.  For checking asymmetric waveforms on a
+// scope - the default is a 10% duty at 10kHz.
+//
+// The period is rounded to whole cycles at CLK_SYS_HZ, and the high half to
+// the nearest cycle of DUTY_PERCENT of that.  Either half may then be
+// shorter than toggle_forever() can produce - its store, plus the branch
+// back for the low half - for a duty close to 0% or 100%, or a very short
+// period.  If so, that half is clamped to the minimum, lengthening the
+// period, and a warning is logged.  The Pico 2's branch timings are
+// estimates, as for delay_cycles().
+//
+// Interrupts are disabled while toggling.
+
+use defmt::{info, warn};
+use embassy_pico_test::gpio_asm::{GpioToggler, cycles_for_period};
+use embassy_rp::gpio::{Level, Output};
+
+use crate::{GPIO2_MASK, IS_PICO2, NOMINAL_SPEED, Test};
+
+// Period to toggle GPIO 2 with, and the percentage of it to be high for.
+pub const PERIOD_NS: u32 = 100_000;
+pub const DUTY_PERCENT: u32 = 10;
+const _: () = assert!(DUTY_PERCENT <= 100, "DUTY_PERCENT must be 0-100");
+
+// clk_sys frequency the period is computed for.
+#[cfg(not(any(feature = "sysclk-200", feature = "sysclk-250")))]
+const CLK_SYS_HZ: u32 = NOMINAL_SPEED;
+#[cfg(any(feature = "sysclk-200", feature = "sysclk-250"))]
+const CLK_SYS_HZ: u32 = crate::sysclk::SYSCLK_HZ;
+
+// The half periods requested, and those used, after clamping.
+const PERIOD_CYCLES: u32 = cycles_for_period(PERIOD_NS, CLK_SYS_HZ);
+const REQUESTED_HIGH_CYCLES: u32 = ((PERIOD_CYCLES as u64 * DUTY_PERCENT as u64 + 50) / 100) as u32;
+const REQUESTED_LOW_CYCLES: u32 = PERIOD_CYCLES - REQUESTED_HIGH_CYCLES;
+const HIGH_CYCLES: u32 = max(REQUESTED_HIGH_CYCLES, GpioToggler::FOREVER_MIN_HIGH_CYCLES);
+const LOW_CYCLES: u32 = max(REQUESTED_LOW_CYCLES, GpioToggler::FOREVER_MIN_LOW_CYCLES);
+
+const fn max(a: u32, b: u32) -> u32 {
+    if a > b { a } else { b }
+}
+
+impl Test {
+    pub async fn soft_pwm() -> ! {
+        let p = Self::init();
+
+        info!("Software PWM test");
+        info!(": Target period: {}ns, duty {}%", PERIOD_NS, DUTY_PERCENT);
+        info!(
+            ": {} cycles at {} Hz, requested high {} low {}",
+            PERIOD_CYCLES, CLK_SYS_HZ, REQUESTED_HIGH_CYCLES, REQUESTED_LOW_CYCLES
+        );
+        if HIGH_CYCLES != REQUESTED_HIGH_CYCLES {
+            warn!(
+                ": High clamped to the minimum {} cycles",
+                GpioToggler::FOREVER_MIN_HIGH_CYCLES
+            );
+        }
+        if LOW_CYCLES != REQUESTED_LOW_CYCLES {
+            warn!(
+                ": Low clamped to the minimum {} cycles",
+                GpioToggler::FOREVER_MIN_LOW_CYCLES
+            );
+        }
+        let period = HIGH_CYCLES + LOW_CYCLES;
+        info!(
+            ": Using high {} low {}, so {}ns high of {}ns",
+            HIGH_CYCLES,
+            LOW_CYCLES,
+            Self::cycles_to_ns(HIGH_CYCLES),
+            Self::cycles_to_ns(period)
+        );
+        if embassy_rp::clocks::clk_sys_freq() != CLK_SYS_HZ {
+            warn!(
+                ": clk_sys is {} Hz, so the period is scaled",
+                embassy_rp::clocks::clk_sys_freq()
+            );
+        }
+        if IS_PICO2 {
+            warn!(": Cycle counts are estimates on the Pico 2 - measure the period");
+        }
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+
+        info!(": Starting");
+        cortex_m::interrupt::disable();
+        GpioToggler::new(GPIO2_MASK).toggle_forever::<HIGH_CYCLES, LOW_CYCLES>()
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// SPI test.  Continuously clocks PATTERN out of SPI0 in blocking mode, with
+// SCK on GPIO 2 and MOSI on GPIO 3, so SCK's edge placement from a real
+// peripheral can be scoped and compared against the software toggles.
+//
+// SCK is clk_peri divided by an even prescaler of 2-254, and then by a
+// postdivider of 1-256, so only some frequencies are possible.  embassy
+// picks the dividers for the requested frequency, rounding down, and the
+// frequency actually generated is logged from the dividers read back.
+//
+// embassy's blocking write waits for each byte to be clocked out before
+// writing the next, so there is a short gap in SCK between bytes, of a few
+// clk_sys cycles plus the SPI block's synchronisation.  Within each byte,
+// SCK is purely the peripheral's.  Interrupts are disabled while clocking,
+// so the gaps are consistent.
+
+use defmt::{error, info};
+use embassy_rp::pac;
+use embassy_rp::spi::{Config, Spi};
+
+use crate::Test;
+
+// SCK frequency to request, in Hz.
+pub const FREQUENCY: u32 = 1_000_000;
+
+// Bytes to clock out, repeatedly.
+const PATTERN: [u8; 4] = [0xa5, 0x0f, 0xff, 0x00];
+
+impl Test {
+    pub async fn spi(frequency: u32) -> ! {
+        let p = Self::init();
+
+        let clk_peri = embassy_rp::clocks::clk_peri_freq();
+        info!("SPI test");
+        info!(": SPI0, SCK on GPIO 2, MOSI on GPIO 3");
+        info!(": Pattern: {=[u8]:#04x}", PATTERN);
+        info!(": Requested frequency: {} Hz", frequency);
+
+        // embassy panics on a frequency its dividers can't reach.
+        let min = clk_peri.div_ceil(254 * 256);
+        let max = clk_peri / 2;
+        if frequency < min || frequency > max {
+            error!(": Frequency must be from {} Hz to {} Hz", min, max);
+            Self::idle();
+        }
+
+        let mut config = Config::default();
+        config.frequency = frequency;
+        let mut spi = Spi::new_blocking_txonly(p.SPI0, p.PIN_2, p.PIN_3, config);
+
+        let prescaler = pac::SPI0.cpsr().read().cpsdvsr() as u32;
+        let postdiv = pac::SPI0.cr0().read().scr() as u32 + 1;
+        let actual = clk_peri / (prescaler * postdiv);
+        info!(
+            ": Prescaler: {}, postdivider: {}, from clk_peri {} Hz",
+            prescaler, postdiv, clk_peri
+        );
+        info!(
+            ": Actual frequency: {} Hz, period {}ns",
+            actual,
+            1_000_000_000 / actual
+        );
+
+        info!(": Starting");
+        cortex_m::interrupt::disable();
+        loop {
+            // Infallible, as embassy's blocking SPI writes never fail.
+            let _ = spi.blocking_write(&PATTERN);
+        }
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Sync pulse support, enabled by the sync-pin feature.  Drives a narrow
+// pulse on GPIO 3 at the start of each repetition of a test's output on
+// GPIO 2, for triggering a logic analyzer or scope on the repetition
+// boundary.
+//
+// Supported by the pattern, ir-nec and ws2812 tests, each of which sends a
+// repetition with interrupts disabled, timed with SysTick.  sync_pulse() is
+// called at the start of each, and returns the SysTick value the
+// repetition's first step is timed from.  The pulse's rising edge is always
+// SYNC_WIDTH_CYCLES + SYNC_LEAD_CYCLES before that, so the first data edge
+// is at a fixed offset after the sync pulse - bar the few cycles the
+// SysTick wait loop takes to notice each deadline.
+
+use defmt::info;
+use embassy_rp::gpio::{Level, Output};
+use embassy_rp::peripherals::PIN_3;
+
+use crate::{GPIO_OUT_CLR, GPIO_OUT_SET, SYSTICK_MAX, Test};
+
+// Width of the sync pulse, and the gap from its falling edge to the start of
+// the repetition, in clk_sys cycles.
+const SYNC_WIDTH_CYCLES: u32 = 32;
+const SYNC_LEAD_CYCLES: u32 = 64;
+
+const GPIO3_MASK: u32 = 1 << 3;
+
+impl Test {
+    // Configures GPIO 3 as the sync output, logging the boundary it marks.
+    pub fn sync_output(pin: PIN_3, boundary: &str) -> Output<'static> {
+        info!(": Sync pulse on GPIO 3, at the {}", boundary);
+        info!(
+            ": Sync pulse is {}ns wide, {}ns before the first data step",
+            Self::cycles_to_ns(SYNC_WIDTH_CYCLES),
+            Self::cycles_to_ns(SYNC_LEAD_CYCLES)
+        );
+        Output::new(pin, Level::Low)
+    }
+
+    // Sends the sync pulse, returning the SysTick value to time the
+    // repetition from.  Must be called with interrupts disabled, and with
+    // SysTick started.
+    pub fn sync_pulse() -> u32 {
+        let start = Self::systick_now();
+        Self::sio_write(GPIO_OUT_SET, GPIO3_MASK);
+        Self::wait_until_cycles(start, SYNC_WIDTH_CYCLES);
+        Self::sio_write(GPIO_OUT_CLR, GPIO3_MASK);
+        Self::wait_until_cycles(start, SYNC_WIDTH_CYCLES + SYNC_LEAD_CYCLES);
+        start.wrapping_sub(SYNC_WIDTH_CYCLES + SYNC_LEAD_CYCLES) & SYSTICK_MAX
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Overclocking, enabled by the sysclk-200 and sysclk-250 features, which run
+// clk_sys at 200MHz or 250MHz, rather than the Pico's 125MHz or the Pico
+// 2's 150MHz.  clk_sys is the system PLL's output, so the PLL is configured
+// from the 12MHz crystal for the requested frequency, with the VCO within
+// its 750-1600MHz range:
+//
+//   200MHz: 12MHz * 100 = 1200MHz VCO, / 6 / 1
+//   250MHz: 12MHz * 125 = 1500MHz VCO, / 6 / 1
+//
+// clk_peri stays on clk_sys, but embassy computes UART baud rate divisors
+// from its frequency, so they are still correct, and embassy time runs from
+// the crystal, so Timer and Delay durations are unaffected.  The asm tests,
+// though, are fixed numbers of cycles, so their periods shrink with the
+// overclock - test 15's "200ns" is 25 cycles on the Pico, which is 100ns at
+// 250MHz.  Their expected periods are computed from clk_sys, so are logged
+// correctly.
+//
+// At 250MHz the RP2040's core voltage is raised from 1.10V to 1.15V first,
+// as is usual for this overclock.  The Pico 2's voltage is left at its
+// default, which may not be enough for 250MHz on every chip.
+
+use defmt::info;
+use embassy_rp::clocks::{ClockConfig, PllConfig};
+use embassy_rp::config::Config;
+
+use crate::{NOMINAL_SPEED, Test};
+
+#[cfg(all(feature = "sysclk-200", feature = "sysclk-250"))]
+compile_error!("Enable only one of the sysclk-200 and sysclk-250 features");
+
+// The requested clk_sys frequency, and the system PLL's feedback divider
+// for it.
+#[cfg(feature = "sysclk-200")]
+pub const SYSCLK_HZ: u32 = 200_000_000;
+#[cfg(feature = "sysclk-200")]
+const FBDIV: u16 = 100;
+#[cfg(feature = "sysclk-250")]
+pub const SYSCLK_HZ: u32 = 250_000_000;
+#[cfg(feature = "sysclk-250")]
+const FBDIV: u16 = 125;
+
+const CRYSTAL_HZ: u32 = 12_000_000;
+const POST_DIV1: u8 = 6;
+const POST_DIV2: u8 = 1;
+const _: () =
+    assert!(CRYSTAL_HZ * FBDIV as u32 / (POST_DIV1 as u32 * POST_DIV2 as u32) == SYSCLK_HZ);
+
+// RP2040 VREG VSEL for 1.15V.
+#[cfg(all(feature = "pico", feature = "sysclk-250"))]
+const VSEL_1V15: u8 = 0b1100;
+
+impl Test {
+    // Initialises embassy with clk_sys overclocked to SYSCLK_HZ.
+    pub fn init_overclocked() -> embassy_rp::Peripherals {
+        #[cfg(all(feature = "pico", feature = "sysclk-250"))]
+        {
+            embassy_rp::pac::VREG_AND_CHIP_RESET
+                .vreg()
+                .modify(|w| w.set_vsel(VSEL_1V15));
+            // Let the voltage settle - well over the regulator's response
+            // time, at the ROSC's or 12MHz crystal's speed.
+            cortex_m::asm::delay(10_000);
+        }
+
+        let mut clocks = ClockConfig::crystal(CRYSTAL_HZ);
+        if let Some(xosc) = clocks.xosc.as_mut() {
+            xosc.sys_pll = Some(PllConfig {
+                refdiv: 1,
+                fbdiv: FBDIV,
+                post_div1: POST_DIV1,
+                post_div2: POST_DIV2,
+            });
+        }
+        let p = embassy_rp::init(Config::new(clocks));
+
+        info!(
+            "Overclocked clk_sys: {} Hz, from {} Hz",
+            embassy_rp::clocks::clk_sys_freq(),
+            NOMINAL_SPEED
+        );
+        p
+    }
+}
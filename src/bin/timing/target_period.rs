@@ -0,0 +1,74 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Target period test.  Toggles GPIO 2 with a period of PERIOD_NS, from
+// GpioToggler::toggle_forever(), whose half periods are worked out at
+// compile time by cycles_for_period(), rather than hand counted.  Change
+// PERIOD_NS to get another period - there's no cycle bookkeeping to redo.
+//
+// Each half period is its store to GPIO_OUT_SET or GPIO_OUT_CLR, then the
+// same counted subs/bne delay loop and adds padding as delay_cycles(), with
+// the branch back in the low half.  The period is rounded to whole cycles at
+// CLK_SYS_HZ, which follows the sysclk features, so the period achieved is
+// logged alongside the target.  For an odd number of cycles, the high half
+// is a cycle shorter than the low.  The Pico 2's branch timings are
+// estimates, as for delay_cycles().
+//
+// Interrupts are disabled while toggling.
+
+use defmt::{info, warn};
+use embassy_pico_test::gpio_asm::{GpioToggler, cycles_for_period};
+use embassy_rp::gpio::{Level, Output};
+
+use crate::{GPIO2_MASK, IS_PICO2, NOMINAL_SPEED, Test};
+
+// Period to toggle GPIO 2 with.
+pub const PERIOD_NS: u32 = 1_000;
+
+// clk_sys frequency the period is computed for.
+#[cfg(not(any(feature = "sysclk-200", feature = "sysclk-250")))]
+const CLK_SYS_HZ: u32 = NOMINAL_SPEED;
+#[cfg(any(feature = "sysclk-200", feature = "sysclk-250"))]
+const CLK_SYS_HZ: u32 = crate::sysclk::SYSCLK_HZ;
+
+const PERIOD_CYCLES: u32 = cycles_for_period(PERIOD_NS, CLK_SYS_HZ);
+const HIGH_CYCLES: u32 = PERIOD_CYCLES / 2;
+const LOW_CYCLES: u32 = PERIOD_CYCLES - HIGH_CYCLES;
+const _: () = assert!(
+    HIGH_CYCLES >= GpioToggler::FOREVER_MIN_HIGH_CYCLES
+        && LOW_CYCLES >= GpioToggler::FOREVER_MIN_LOW_CYCLES,
+    "PERIOD_NS is too short"
+);
+
+impl Test {
+    pub async fn target_period() -> ! {
+        let p = Self::init();
+
+        info!("Target period test");
+        info!(": Target period: {}ns", PERIOD_NS);
+        info!(
+            ": {} cycles at {} Hz, high {} low {}, so {}ns",
+            PERIOD_CYCLES,
+            CLK_SYS_HZ,
+            HIGH_CYCLES,
+            LOW_CYCLES,
+            Self::cycles_to_ns(PERIOD_CYCLES)
+        );
+        if embassy_rp::clocks::clk_sys_freq() != CLK_SYS_HZ {
+            warn!(
+                ": clk_sys is {} Hz, so the period is scaled",
+                embassy_rp::clocks::clk_sys_freq()
+            );
+        }
+        if IS_PICO2 {
+            warn!(": Cycle counts are estimates on the Pico 2 - measure the period");
+        }
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+
+        info!(": Starting");
+        cortex_m::interrupt::disable();
+        GpioToggler::new(GPIO2_MASK).toggle_forever::<HIGH_CYCLES, LOW_CYCLES>()
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Die temperature logging, enabled by the temperature feature, for
+// correlating timing drift over long runs, such as the ROSC's with
+// clk-rosc, with temperature.  Reads the internal temperature sensor with
+// the ADC every TEMP_INTERVAL, and logs it in degrees C with the time since
+// boot, alongside the soak test's heartbeats and the measure feature's
+// reports.
+//
+// Only read from those, between bursts or after a report, so never from a
+// test's timing loop.  A blocking read takes a few microseconds.  Like the
+// rest of measure's reporting, the time taken is excluded from the next
+// measurement.
+//
+// The conversion is the datasheet's, T = 27 - (V - 0.706) / 0.001721, for
+// both the RP2040 and RP2350, with a 3.3V reference.  The sensor is only
+// accurate to a few degrees, and is noisy, but tracks changes well.  Each
+// reading is also logged as a "timing: " line, as temp_mc, in thousandths
+// of a degree.
+
+use defmt::{info, warn};
+use embassy_rp::adc::{self, Adc, Blocking, Channel};
+use embassy_rp::peripherals::{ADC, ADC_TEMP_SENSOR};
+use embassy_time::{Duration, Instant};
+
+#[cfg(not(any(feature = "soak", feature = "measure")))]
+compile_error!("The temperature feature only applies to the soak test and the measure feature");
+
+// Minimum time between readings.
+const TEMP_INTERVAL: Duration = Duration::from_secs(10);
+
+// ADC reference, in uV, and full scale.
+const VREF_UV: i64 = 3_300_000;
+const ADC_MAX: i64 = 4096;
+
+pub struct TempSensor {
+    adc: Adc<'static, Blocking>,
+    channel: Channel<'static>,
+    next: Instant,
+}
+
+impl TempSensor {
+    pub fn new(adc: ADC, sensor: ADC_TEMP_SENSOR) -> Self {
+        info!(
+            ": Logging die temperature every {}s",
+            TEMP_INTERVAL.as_secs()
+        );
+        Self {
+            adc: Adc::new_blocking(adc, adc::Config::default()),
+            channel: Channel::new_temp_sensor(sensor),
+            next: Instant::now(),
+        }
+    }
+
+    // Creates the sensor from stolen peripherals, for the measure feature,
+    // whose single GPIO tests don't otherwise use the ADC.
+    pub fn steal() -> Self {
+        // Safety: Nothing else uses the ADC when this is used.
+        unsafe { Self::new(ADC::steal(), ADC_TEMP_SENSOR::steal()) }
+    }
+
+    // Reads and logs the temperature, if TEMP_INTERVAL has passed since the
+    // last reading.
+    #[inline(never)]
+    pub fn poll(&mut self) {
+        let now = Instant::now();
+        if now < self.next {
+            return;
+        }
+        self.next = now + TEMP_INTERVAL;
+
+        match self.adc.blocking_read(&mut self.channel) {
+            Ok(raw) => {
+                let uv = raw as i64 * VREF_UV / ADC_MAX;
+                let temp_mc = (27_000 - (uv - 706_000) * 1000 / 1721) as i32;
+                info!(
+                    ": Die temperature: {}.{=i32:03}C at {}s",
+                    temp_mc / 1000,
+                    (temp_mc % 1000).abs(),
+                    now.as_secs()
+                );
+                info!("timing: temp_mc={} uptime_ms={}", temp_mc, now.as_millis());
+            }
+            Err(_) => warn!(": Temperature sensor read failed"),
+        }
+    }
+}
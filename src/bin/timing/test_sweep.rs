@@ -0,0 +1,129 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Test sweep.  Runs every single GPIO test in turn, for DURATION each, with
+// a banner logged before each, so one scope capture contains all of the
+// periods back to back.  GPIO 2 is held low for GAP between tests, to
+// separate them on the trace.  Once every test has run, the sweep restarts.
+//
+// The tests run from the same code as single-gpio, with their loops ending
+// once a SweepTimer expires.  Reading Instant every period would lengthen
+// the faster tests' periods, so SweepTimer only reads it about every
+// CHECK_INTERVAL, working out how many periods that is from the mean period
+// so far.  The other periods cost only a counter decrement and branch,
+// which is still visible on the tests with no delay, such as 13 and 25.
+//
+// The asm tests never return, so are replaced by bounded versions, from
+// asm_toggle_bounded(), run for the number of periods which fit in
+// DURATION.  Their duty cycles differ slightly - see it for details.
+//
+// Tests 26 and 27 are skipped on the Pico, as they need the Pico 2's DWT.
+
+use defmt::info;
+use embassy_rp::gpio::{Drive, Output};
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::{IS_PICO2, Test, TestNum};
+
+// How long to run each test for, and to hold GPIO 2 low between tests.
+// With pre-test-idle, the gap is its idle instead.
+pub const DURATION: Duration = Duration::from_secs(2);
+#[cfg(not(feature = "pre-test-idle"))]
+const GAP: Duration = Duration::from_millis(10);
+#[cfg(feature = "pre-test-idle")]
+const GAP: Duration = crate::pre_test_idle::IDLE;
+
+// Roughly how often SweepTimer reads Instant, and the most periods between
+// reads.
+const CHECK_INTERVAL: Duration = Duration::from_millis(1);
+const MAX_CHECK_PERIODS: u64 = 1_000_000;
+
+impl Test {
+    pub async fn test_sweep() -> ! {
+        let _p = Self::init();
+
+        info!("Test sweep");
+        info!(
+            ": {}ms per test, {}ms low between tests",
+            DURATION.as_millis(),
+            GAP.as_millis()
+        );
+        let mut output = Self::single_gpio_output();
+
+        loop {
+            for test_num in TestNum::ALL {
+                if !IS_PICO2 && matches!(test_num, TestNum::T26 | TestNum::T27) {
+                    continue;
+                }
+
+                // Undo anything the previous test changed.
+                output.set_low();
+                output.set_drive_strength(Drive::_4mA);
+                Timer::after(GAP).await;
+
+                info!("Single GPIO Timing test #{}", test_num as i32);
+                info!(": {}", test_num.info().description);
+                if test_num.is_asm() {
+                    Self::asm_toggle_bounded(&mut output, test_num, Self::sweep_periods);
+                } else {
+                    Self::run_single_gpio(&mut output, test_num).await;
+                }
+            }
+            info!("Sweep complete, restarting");
+        }
+    }
+
+    // Returns the number of periods of period_cycles which fit in DURATION,
+    // for the bounded asm tests.
+    fn sweep_periods(period_cycles: u32) -> u32 {
+        let speed = embassy_rp::clocks::clk_sys_freq() as u64;
+        (DURATION.as_micros() * speed / 1_000_000 / period_cycles as u64) as u32
+    }
+}
+
+// Ends a test's loop after a duration.  Call expired() once per period.
+pub struct SweepTimer {
+    start: Instant,
+    deadline: Instant,
+    periods: u64,
+    check_periods: u32,
+    remaining: u32,
+}
+
+impl SweepTimer {
+    pub fn new(duration: Duration) -> Self {
+        let start = Instant::now();
+        Self {
+            start,
+            deadline: start + duration,
+            periods: 0,
+            check_periods: 1,
+            remaining: 1,
+        }
+    }
+
+    // Counts a period, returning true once the duration has passed.
+    #[inline(always)]
+    pub fn expired(&mut self) -> bool {
+        self.remaining -= 1;
+        self.remaining == 0 && self.check()
+    }
+
+    #[inline(never)]
+    fn check(&mut self) -> bool {
+        let now = Instant::now();
+        if now >= self.deadline {
+            return true;
+        }
+
+        // Check again after about CHECK_INTERVAL, from the mean period so
+        // far.
+        self.periods += self.check_periods as u64;
+        let elapsed = (now - self.start).as_ticks().max(1);
+        self.check_periods =
+            (self.periods * CHECK_INTERVAL.as_ticks() / elapsed).clamp(1, MAX_CHECK_PERIODS) as u32;
+        self.remaining = self.check_periods;
+        false
+    }
+}
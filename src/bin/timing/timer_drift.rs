@@ -0,0 +1,101 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Timer drift test.  Toggles GPIO 2 with a HALF_PERIOD high and low for a
+// WINDOW, first with Timer::after(), as single-gpio tests 1-3 do, then with
+// Timer::at() and an accumulating deadline, as test 19 does, and logs the
+// periods each completed.  Timer::after() starts each wait from when it is
+// called, so the time taken to wake, toggle and re-arm is added to every
+// half period, and the periods fall steadily behind.  Timer::at()'s
+// deadlines are fixed in advance, so its late wakes shorten the following
+// wait instead, and it completes the expected WINDOW / period.
+//
+// The window ends at the first period completed at or after WINDOW from
+// its start, by Instant::now(), so either count may be a period over.  The
+// shortfall of each from the expected count is logged, along with the
+// average time Timer::after() added to each period.  The two are run
+// alternately, a pair at a time, with interrupts enabled, as embassy-time
+// needs them.
+
+use defmt::info;
+use embassy_rp::gpio::{Level, Output};
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::Test;
+
+// Time GPIO 2 is high and then low for.
+const HALF_PERIOD: Duration = Duration::from_micros(10);
+
+// Time to run each approach for.
+const WINDOW: Duration = Duration::from_secs(1);
+
+impl Test {
+    pub async fn timer_drift() -> ! {
+        let p = Self::init();
+
+        let period = HALF_PERIOD * 2;
+        let expected = WINDOW.as_ticks() / period.as_ticks();
+        info!("Timer drift test");
+        info!(": Using GPIO 2");
+        info!(
+            ": Period: {}us, window: {}ms, so {} periods expected",
+            period.as_micros(),
+            WINDOW.as_millis(),
+            expected
+        );
+
+        let mut output = Output::new(p.PIN_2, Level::Low);
+
+        info!(": Starting");
+        loop {
+            let after = Self::periods_with_after(&mut output).await;
+            let at = Self::periods_with_at(&mut output).await;
+            for (name, periods) in [("Timer::after()", after), ("Timer::at()", at)] {
+                info!(
+                    ": {}: {} periods, {} short",
+                    name,
+                    periods,
+                    expected.saturating_sub(periods)
+                );
+            }
+            // At least one period always completes, so after is never 0.
+            let added_ns =
+                (WINDOW.as_micros() * 1000 / after).saturating_sub(period.as_micros() * 1000);
+            info!(": Timer::after() added {}ns per period", added_ns);
+        }
+    }
+
+    // Returns the periods completed in WINDOW when waiting with
+    // Timer::after().
+    async fn periods_with_after(output: &mut Output<'_>) -> u64 {
+        let end = Instant::now() + WINDOW;
+        let mut periods = 0;
+        while Instant::now() < end {
+            output.set_high();
+            Timer::after(HALF_PERIOD).await;
+            output.set_low();
+            Timer::after(HALF_PERIOD).await;
+            periods += 1;
+        }
+        periods
+    }
+
+    // Returns the periods completed in WINDOW when waiting with Timer::at()
+    // for deadlines accumulated from the start.
+    async fn periods_with_at(output: &mut Output<'_>) -> u64 {
+        let mut expires = Instant::now();
+        let end = expires + WINDOW;
+        let mut periods = 0;
+        while Instant::now() < end {
+            output.set_high();
+            expires += HALF_PERIOD;
+            Timer::at(expires).await;
+            output.set_low();
+            expires += HALF_PERIOD;
+            Timer::at(expires).await;
+            periods += 1;
+        }
+        periods
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Timer accuracy sweep.  For each of a table of target delays from 1us to
+// 1s, awaits Timer::at() for the target, measures the actual delay with
+// Instant, and logs the target, actual and error.
+//
+// Each delay is from its own Instant::now(), to an absolute Timer::at()
+// deadline, so errors (including log time) never carry over from one
+// measurement to the next, as they would with repeated Timer::after().
+//
+// Both Timer and Instant run from the 1MHz TIMER tick, so this measures
+// embassy-time's scheduling error - tick rounding, alarm latency and
+// executor overhead - rather than the accuracy of the crystal.  The start
+// Instant may be read up to a tick after the tick started, so short delays
+// can read up to 1us long.
+//
+// The 1s delays make a full sweep take around 20s.  The timer-sweep-short
+// feature caps the sweep at SHORT_MAX_TARGET.
+
+use defmt::info;
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::Test;
+use crate::measurement::Measurement;
+
+// Target delays, in us.
+const TARGETS_US: &[u64] = &[
+    1, 2, 5, 10, 20, 50, 100, 200, 500, 1_000, 2_000, 5_000, 10_000, 20_000, 50_000, 100_000,
+    200_000, 500_000, 1_000_000,
+];
+
+// Longest target delay swept with timer-sweep-short.
+#[cfg(feature = "timer-sweep-short")]
+const SHORT_MAX_TARGET: Duration = Duration::from_millis(10);
+
+// Delays measured per target.
+const SAMPLES: u32 = 10;
+
+impl Test {
+    pub async fn timer_accuracy_sweep() {
+        let _p = Self::init();
+
+        info!("Timer accuracy sweep");
+        #[cfg(feature = "timer-sweep-short")]
+        info!(": Capped at {}us", SHORT_MAX_TARGET.as_micros());
+        info!(": Samples per target: {}", SAMPLES);
+
+        info!(": Starting");
+        for &target_us in TARGETS_US {
+            let target = Duration::from_micros(target_us);
+            #[cfg(feature = "timer-sweep-short")]
+            if target > SHORT_MAX_TARGET {
+                break;
+            }
+
+            // Samples are in ns, so the mean keeps its fraction of a tick.
+            let mut measurement = Measurement::new();
+            for _ in 0..SAMPLES {
+                let start = Instant::now();
+                Timer::at(start + target).await;
+                let elapsed = Instant::now() - start;
+                measurement.add((elapsed.as_micros() * 1000) as u32);
+            }
+
+            let r = measurement.result(|ns| ns);
+            let target_ns = target_us as i64 * 1000;
+            let error_ppm = (r.mean_ns as i64 - target_ns) * 1_000_000 / target_ns;
+            info!(
+                ": Target {}us: actual mean {}ns, min {}ns, max {}ns, error {}ppm",
+                target_us, r.mean_ns, r.min_ns, r.max_ns, error_ppm
+            );
+        }
+        info!(": Done");
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// UART frame test.  Bit-bangs BYTE on GPIO 2 as a UART-like frame - a low
+// start bit, 8 data bits and a high stop bit, 8N1 - each BIT_NS long, and
+// repeats it forever, with IDLE_BITS of the high idle level between frames.
+// For validating a bit-banged protocol, or a receiver, against a known
+// signal, such as with a logic analyser's UART decoder.
+//
+// The data bits are sent LSB first, as a UART does, or MSB first if
+// MSB_FIRST is set.  BYTE is asymmetric, so the order is visible on a scope.
+//
+// Each bit is a store of GPIO 2's mask to GPIO_OUT_SET or GPIO_OUT_CLR,
+// followed by delay_loop(), the counted delay loop, for the rest of the bit
+// period, with interrupts disabled for each frame.  The store, the bit
+// selection and the loop around them are estimated at LOOP_CYCLES per bit
+// and subtracted, and the delay loop rounds to its iterations, so the bit
+// rate is within a few cycles per bit of BIT_NS - a small fraction of UART's
+// tolerance at common baud rates, but check it with a scope at high ones.
+// The Pico 2's counts are estimates.
+
+use defmt::{error, info};
+use embassy_pico_test::gpio_asm::{cycles_for_period, delay_loop};
+use embassy_pico_test::sio;
+use embassy_rp::gpio::{Level, Output};
+
+use crate::{GPIO2_MASK, Test};
+
+// Byte to send, and the bit period - 8680ns is 115200 baud.
+const BYTE: u8 = 0x4b;
+const BIT_NS: u32 = 8_680;
+
+// Send the data bits MSB first, instead of a UART's LSB first.
+const MSB_FIRST: bool = false;
+
+// Bit periods of idle (high) between the stop bit and the next start bit.
+const IDLE_BITS: u32 = 2;
+
+// Bits per frame - start, 8 data and stop.
+const FRAME_BITS: u32 = 10;
+
+// Estimated cycles of each bit other than the delay - the store, selecting
+// it and the loop around it.
+const LOOP_CYCLES: u32 = 8;
+
+impl Test {
+    pub async fn uart_frame() -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        info!("UART frame test");
+        info!(": Using GPIO 2");
+        info!(
+            ": Byte: {:#04x} ({=u8:08b}), {} first",
+            BYTE,
+            BYTE,
+            if MSB_FIRST { "MSB" } else { "LSB" }
+        );
+        info!(
+            ": Bit period: {}ns, {} bits per second, 8N1",
+            BIT_NS,
+            1_000_000_000 / BIT_NS
+        );
+        info!(": Idle: {} bits high between frames", IDLE_BITS);
+
+        let bit_cycles = cycles_for_period(BIT_NS, speed);
+        if bit_cycles <= LOOP_CYCLES {
+            error!(
+                ": Bit period of {} cycles is too short - at least {} are needed",
+                bit_cycles,
+                LOOP_CYCLES + 1
+            );
+            Self::idle();
+        }
+        let delay = bit_cycles - LOOP_CYCLES;
+        info!(": {} cycles per bit", bit_cycles);
+
+        let frame = Self::uart_frame_bits(BYTE, MSB_FIRST);
+        let _output = Output::new(p.PIN_2, Level::High);
+
+        info!(": Starting");
+        loop {
+            cortex_m::interrupt::free(|_| {
+                // Bit 0 is sent first.
+                for bit in 0..FRAME_BITS + IDLE_BITS {
+                    if (frame >> bit) & 1 != 0 {
+                        sio::set_gpios(GPIO2_MASK);
+                    } else {
+                        sio::clear_gpios(GPIO2_MASK);
+                    }
+                    delay_loop(delay);
+                }
+            });
+        }
+    }
+
+    // Returns the frame's levels, first bit sent in bit 0, followed by high
+    // idle bits.
+    fn uart_frame_bits(byte: u8, msb_first: bool) -> u32 {
+        let data = if msb_first { byte.reverse_bits() } else { byte };
+        // Start bit low, then the data, then the stop bit and idle high.
+        ((data as u32) << 1) | (!0u32 << (FRAME_BITS - 1))
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// UART RX jitter test.  Toggles GPIO 2 as fast as possible while a UART RX
+// interrupt fires once per received byte, and measures how much each
+// interrupt delays the toggle loop.
+//
+// UART0 is put into the PL011's internal loopback mode, and its TX is fed
+// continuously by DMA, so bytes are received at baud/10 bytes per second
+// (8N1) with no external wiring and no CPU involvement on the TX side.  The
+// RX FIFO is disabled, so the RX interrupt fires for every byte.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+use defmt::info;
+use embassy_rp::dma;
+use embassy_rp::gpio::{Level, Output};
+use embassy_rp::interrupt::{InterruptExt, UART0_IRQ};
+use embassy_rp::pac::dma::vals::TreqSel;
+use embassy_rp::pac::{self, interrupt};
+use embassy_rp::uart::{Config, Uart};
+
+use crate::Test;
+use crate::measurement::{Measurement, MeasurementResult};
+
+// UART baud rate.  At 115200 baud an RX interrupt fires every ~87us.
+pub const BAUD: u32 = 115_200;
+
+// Number of toggle loop iterations between reports.
+const REPORT_ITERATIONS: u32 = 1 << 21;
+
+// Toggle loop iteration times, split by whether an RX interrupt ran during
+// the iteration.
+#[derive(Clone, Copy, defmt::Format)]
+pub struct RxJitterResult {
+    pub with_rx: MeasurementResult,
+    pub without_rx: MeasurementResult,
+}
+
+// Bytes received by the RX interrupt handler.
+static RX_BYTES: AtomicU32 = AtomicU32::new(0);
+
+#[interrupt]
+fn UART0_IRQ() {
+    // Reading the data register clears the RX interrupt.
+    let _ = pac::UART0.uartdr().read();
+    RX_BYTES.store(RX_BYTES.load(Ordering::Relaxed) + 1, Ordering::Relaxed);
+}
+
+impl Test {
+    // Each loop iteration reads SysTick and toggles GPIO 2.  If RX_BYTES
+    // changed during an iteration, an RX interrupt ran during it, so it's
+    // counted as an RX iteration.  The shortest iteration without RX is the
+    // baseline, and anything longer is extra delay.  The maximum, rather
+    // than the average, is reported, as it's the worst case spike that
+    // breaks time-critical output.
+    pub async fn toggle_with_uart_rx(baud: u32) -> ! {
+        let p = Self::init();
+
+        info!("UART RX jitter test");
+        info!(": Using GPIO 2, UART0 in internal loopback");
+        info!(": Baud: {}, {} bytes/s", baud, baud / 10);
+
+        let mut output = Output::new(p.PIN_2, Level::Low);
+
+        let mut config = Config::default();
+        config.baudrate = baud;
+        let _uart = Uart::new_blocking(p.UART0, p.PIN_0, p.PIN_1, config);
+
+        // Loopback, no FIFOs (so an interrupt per byte), TX DMA enabled.
+        let regs = pac::UART0;
+        regs.uartcr().modify(|w| w.set_lbe(true));
+        regs.uartlcr_h().modify(|w| w.set_fen(false));
+        regs.uartdmacr().write(|w| w.set_txdmae(true));
+        regs.uartimsc().write(|w| w.set_rxim(true));
+        UART0_IRQ.unpend();
+        unsafe { UART0_IRQ.enable() };
+
+        // Continuously write bytes to TX, paced by the UART's TX DREQ.  This
+        // will run for several days before completing.
+        let _tx = unsafe {
+            dma::write_repeated(
+                p.DMA_CH0,
+                regs.uartdr().as_ptr() as *mut u8,
+                u32::MAX as usize,
+                TreqSel::UART0_TX,
+            )
+        };
+
+        Self::systick_start();
+
+        info!(": Starting");
+        loop {
+            let result = Self::measure_uart_rx_jitter(&mut output, REPORT_ITERATIONS);
+            let with_rx = result.with_rx;
+            let without_rx = result.without_rx;
+            let baseline = without_rx.min_ns;
+            info!(
+                ": RX bytes: {}, baseline: {}ns, worst with RX: +{}ns, worst without: +{}ns",
+                RX_BYTES.load(Ordering::Relaxed),
+                baseline,
+                with_rx.max_ns.saturating_sub(baseline),
+                without_rx.max_ns.saturating_sub(baseline),
+            );
+            info!(": With RX: {}", with_rx);
+            info!(": Without RX: {}", without_rx);
+        }
+    }
+
+    // Runs the toggle loop for the given number of iterations, measuring
+    // each iteration.  SysTick must have been started.
+    fn measure_uart_rx_jitter(output: &mut Output<'_>, iterations: u32) -> RxJitterResult {
+        let mut with_rx = Measurement::new();
+        let mut without_rx = Measurement::new();
+        let mut last_rx = RX_BYTES.load(Ordering::Relaxed);
+        let mut last = Self::systick_now();
+        for _ in 0..iterations {
+            output.toggle();
+
+            let now = Self::systick_now();
+            let rx = RX_BYTES.load(Ordering::Relaxed);
+            let cycles = Self::systick_elapsed(last, now);
+            last = now;
+
+            if rx != last_rx {
+                with_rx.add(cycles);
+            } else {
+                without_rx.add(cycles);
+            }
+            last_rx = rx;
+        }
+
+        RxJitterResult {
+            with_rx: with_rx.result(Self::cycles_to_ns),
+            without_rx: without_rx.result(Self::cycles_to_ns),
+        }
+    }
+}
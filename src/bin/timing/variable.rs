@@ -0,0 +1,158 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Variable period toggle test.  Toggles GPIO 2 from an asm loop whose delay
+// is a count passed in a register at runtime, rather than fixed at compile
+// time like the other asm loops, so the period can be changed (for example
+// from a UART command) without recompiling.
+//
+// The period is given as a string, such as "5us" or "200kHz", parsed by
+// parse_freq_or_period() and converted to the nearest count, as a command
+// handler would.  Periods outside the loop's range are clamped, with a
+// warning.  asm_toggle_variable() takes a count directly.
+//
+// Each half period is a store to GPIO_OUT_SET or GPIO_OUT_CLR, followed by
+// a subs/bne delay loop run count times.  On the Pico, the period is:
+//
+//   cycles = VARIABLE_CYCLES_PER_COUNT * count + VARIABLE_OVERHEAD_CYCLES
+//          = 6 * count + 6
+//
+// so a desired period converts to a count with:
+//
+//   count = (period_cycles - 6) / 6
+//
+// The cost is 3 cycles per count per half (subs 1, taken bne 2), and the
+// overhead per half is the store, reloading the counter, the final untaken
+// bne and either 2 nops or the branch back, so both halves are 3 * count + 3
+// cycles and the duty cycle is exactly 50% for any count.  The loop runs
+// from RAM with interrupts disabled, so these are stable.  The Pico 2's
+// branch timings differ, so there the formula is only an estimate.
+
+use core::arch::asm;
+use defmt::{error, info, warn};
+use embassy_pico_test::parse::parse_freq_or_period;
+use embassy_rp::gpio::{Level, Output};
+
+use crate::{GPIO_OUT_CLR, GPIO_OUT_SET, IS_PICO2, SIO_BASE, Test};
+
+// Period, parsed at runtime, as a command handler would.
+pub const PERIOD: &str = "5us";
+
+// Cycles per period on the Pico are VARIABLE_CYCLES_PER_COUNT per delay loop
+// count, plus VARIABLE_OVERHEAD_CYCLES.
+pub const VARIABLE_CYCLES_PER_COUNT: u32 = 6;
+pub const VARIABLE_OVERHEAD_CYCLES: u32 = 6;
+
+// Register offsets from SIO_BASE, for use as asm immediates.
+const SET_OFFSET: u32 = GPIO_OUT_SET - SIO_BASE;
+const CLR_OFFSET: u32 = GPIO_OUT_CLR - SIO_BASE;
+
+impl Test {
+    pub async fn toggle_variable(period: &str) -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        info!("Variable period toggle test");
+        info!(": Using GPIO 2");
+
+        let spec = match parse_freq_or_period(period) {
+            Ok(spec) => spec,
+            Err(e) => {
+                error!(": Invalid period \"{}\": {}", period, e);
+                Self::idle();
+            }
+        };
+        info!(": Requested: {}, parsed as {}", period, spec);
+
+        // Round to the nearest count, clamped to the loop's range.
+        let cycles = spec.cycles(speed);
+        let min_cycles = (VARIABLE_CYCLES_PER_COUNT + VARIABLE_OVERHEAD_CYCLES) as u64;
+        let max_cycles =
+            u32::MAX as u64 * VARIABLE_CYCLES_PER_COUNT as u64 + VARIABLE_OVERHEAD_CYCLES as u64;
+        if cycles < min_cycles {
+            warn!(
+                ": Requested {} cycles is below the minimum {} cycles ({}ns) - using minimum",
+                cycles,
+                min_cycles,
+                min_cycles * 1_000_000_000 / speed as u64
+            );
+        } else if cycles > max_cycles {
+            warn!(
+                ": Requested {} cycles is above the maximum {} cycles - using maximum",
+                cycles, max_cycles
+            );
+        }
+        let cycles = cycles.clamp(min_cycles, max_cycles);
+        let count = (cycles - VARIABLE_OVERHEAD_CYCLES as u64
+            + VARIABLE_CYCLES_PER_COUNT as u64 / 2)
+            / VARIABLE_CYCLES_PER_COUNT as u64;
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+        Self::asm_toggle_variable(count as u32);
+    }
+
+    // Toggles GPIO 2, which must already be configured as an output, with a
+    // period of count delay loop counts, forever.
+    pub fn asm_toggle_variable(count: u32) -> ! {
+        let speed = embassy_rp::clocks::clk_sys_freq();
+
+        if count == 0 {
+            error!(": Count must be non-zero");
+            Self::idle();
+        }
+
+        let cycles =
+            count as u64 * VARIABLE_CYCLES_PER_COUNT as u64 + VARIABLE_OVERHEAD_CYCLES as u64;
+        info!(
+            ": Count: {}, period: {} cycles ({}ns)",
+            count,
+            cycles,
+            cycles * 1_000_000_000 / speed as u64
+        );
+        info!(
+            ": Period is {} * count + {} cycles",
+            VARIABLE_CYCLES_PER_COUNT, VARIABLE_OVERHEAD_CYCLES
+        );
+        if IS_PICO2 {
+            warn!(": Period is an estimate on the Pico 2 - measure it");
+        }
+
+        info!(": Starting");
+
+        // Disable interrupts so nothing disturbs the timing.
+        cortex_m::interrupt::disable();
+        Self::asm_variable_loop(count);
+    }
+
+    // r0: SIO_BASE, r3: GPIO 2 mask, r4: count, r5: delay loop counter.
+    // Registers are free to clobber, as this never returns.
+    #[inline(never)]
+    #[unsafe(link_section = ".data.ram_func")]
+    fn asm_variable_loop(count: u32) -> ! {
+        unsafe {
+            asm!(
+                "2:",
+                "str r3, [r0, #{set}]",   // High
+                "mov r5, r4",
+                "3:",
+                "subs r5, #1",
+                "bne 3b",
+                "nop",
+                "nop",
+                "str r3, [r0, #{clr}]",   // Low
+                "mov r5, r4",
+                "4:",
+                "subs r5, #1",
+                "bne 4b",
+                "b 2b",
+                in("r0") SIO_BASE,
+                in("r3") 1u32 << 2,
+                in("r4") count,
+                set = const SET_OFFSET,
+                clr = const CLR_OFFSET,
+                options(noreturn, nostack),
+            );
+        }
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Toggle with verify test.  Toggles GPIO 2, and after each edge reads the
+// pin back from GPIO_IN to check the pad actually reached the commanded
+// level, counting any mismatches.  This catches a pin that is overloaded,
+// shorted, or being fought by something else on the net.
+//
+// A read immediately after the store can legitimately see the old level.
+// The pad's input passes through a 2 cycle synchroniser before reaching
+// GPIO_IN, and the pad itself takes time to slew, longer with a heavier
+// load.  So each read is made SETTLE_CYCLES after the store, timed with
+// SysTick, comfortably past the synchroniser, so only a pad which genuinely
+// failed to follow is counted.  If a heavily loaded pin reports mismatches,
+// increase SETTLE_CYCLES to see whether it is just slow.
+
+use defmt::info;
+use embassy_rp::gpio::{Level, Output};
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::{GPIO_IN, GPIO_OUT_CLR, GPIO_OUT_SET, GPIO2_MASK, Test};
+
+// Toggle period.  Note embassy-time Durations have 1us resolution.
+pub const PERIOD: Duration = Duration::from_micros(20);
+
+// Cycles between each store and its read back.
+const SETTLE_CYCLES: u32 = 16;
+
+// How often to log the mismatch counts.
+const LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+impl Test {
+    pub async fn toggle_with_verify(period: Duration) -> ! {
+        let p = Self::init();
+
+        info!("Toggle with verify test");
+        info!(": Using GPIO 2");
+        info!(": Period: {}us", period.as_micros());
+        info!(
+            ": Read back {} cycles ({}ns) after each edge",
+            SETTLE_CYCLES,
+            Self::cycles_to_ns(SETTLE_CYCLES)
+        );
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+        let half = period / 2;
+
+        Self::systick_start();
+
+        info!(": Starting");
+        let mut edges: u64 = 0;
+        let mut high_faults: u64 = 0;
+        let mut low_faults: u64 = 0;
+        let mut expires = Instant::now();
+        let mut next_log = expires + LOG_INTERVAL;
+        loop {
+            if !Self::set_and_verify(GPIO_OUT_SET, true) {
+                high_faults += 1;
+            }
+            expires += half;
+            Timer::at(expires).await;
+
+            if !Self::set_and_verify(GPIO_OUT_CLR, false) {
+                low_faults += 1;
+            }
+            expires += half;
+            Timer::at(expires).await;
+
+            edges += 2;
+            if expires >= next_log {
+                info!(
+                    ": Edges: {}, mismatches: {} high, {} low ({} ppm)",
+                    edges,
+                    high_faults,
+                    low_faults,
+                    (high_faults + low_faults) * 1_000_000 / edges
+                );
+                next_log += LOG_INTERVAL;
+            }
+        }
+    }
+
+    // Writes GPIO 2 to reg, waits for the pad and synchroniser to settle,
+    // and returns whether GPIO_IN shows the expected level.
+    #[inline(always)]
+    fn set_and_verify(reg: u32, high: bool) -> bool {
+        let start = Self::systick_now();
+        Self::sio_write(reg, GPIO2_MASK);
+        Self::wait_until_cycles(start, SETTLE_CYCLES);
+        (Self::sio_read(GPIO_IN) & GPIO2_MASK != 0) == high
+    }
+}
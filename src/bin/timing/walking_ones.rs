@@ -0,0 +1,97 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Walking-ones test.  Drives a contiguous block of GPIOs high one at a time,
+// in sequence, simulating the row (or column) drive of a scanned display or
+// keypad.
+
+use defmt::{error, info, warn};
+use embassy_rp::gpio::{AnyPin, Level, Output};
+use embassy_time::{Duration, Instant, Timer};
+use heapless::Vec;
+
+use crate::{GPIO_IN, GPIO_OUT_CLR, GPIO_OUT_SET, NUM_GPIOS, Test};
+
+// Scan GPIOs 2-5, with a full scan (all 4 pins) taking 400us, so each pin is
+// high for 100us.
+pub const BASE: u8 = 2;
+pub const WIDTH: u8 = 4;
+pub const SCAN_PERIOD: Duration = Duration::from_micros(400);
+
+impl Test {
+    // Each step clears the previous pin via GPIO_OUT_CLR and then sets the
+    // next via GPIO_OUT_SET.  These are two separate single-cycle SIO
+    // stores, so there is a ~1 cycle window where no pins are high, but
+    // never one where two pins are high - which is what matters for row/
+    // column scan correctness.
+    //
+    // As the pads' input buffers are enabled, GPIO_IN is also read back just
+    // before each step (when the levels have long since settled) to verify
+    // that no more than one pin in the block is high.  This is only
+    // meaningful if nothing external is driving these pins.
+    pub async fn walking_ones(base: u8, width: u8, scan_period: Duration) {
+        let _p = Self::init();
+
+        info!("Walking ones test");
+        if width == 0 || base as u32 + width as u32 > NUM_GPIOS as u32 {
+            error!(
+                ": Invalid GPIO block - base {} width {} (max GPIO {})",
+                base,
+                width,
+                NUM_GPIOS - 1
+            );
+            return;
+        }
+
+        let dwell = scan_period / width as u32;
+        let scan_rate = 1_000_000 / scan_period.as_micros().max(1);
+        info!(": Using GPIOs {} to {}", base, base + width - 1);
+        info!(": Width: {} pins", width);
+        info!(
+            ": Scan period: {}us ({}Hz), {}us per pin",
+            scan_period.as_micros(),
+            scan_rate,
+            dwell.as_micros()
+        );
+
+        // Configure every pin in the block as a low output.  The Outputs
+        // must be kept, as dropping them would deconfigure the pins.
+        let mut outputs: Vec<Output<'static>, { NUM_GPIOS as usize }> = Vec::new();
+        for pin in base..base + width {
+            // Safety: The typed pin peripherals aren't used by this test, so
+            // each of these pins is only instantiated once.
+            let pin = unsafe { AnyPin::steal(pin) };
+            let _ = outputs.push(Output::new(pin, Level::Low));
+        }
+
+        let block_mask = (((1u64 << width) - 1) as u32) << base;
+        let mut prev_mask = 0;
+        let mut overlaps: u32 = 0;
+
+        info!(": Starting");
+        let mut expires = Instant::now();
+        loop {
+            for pin in base..base + width {
+                // Verify the previous step left at most one pin high.
+                let high = Self::sio_read(GPIO_IN) & block_mask;
+                if high.count_ones() > 1 {
+                    overlaps += 1;
+                    warn!(
+                        ": Multiple pins high: 0x{:08x} ({} overlaps so far)",
+                        high, overlaps
+                    );
+                }
+
+                // Clear-then-set, so two pins are never high at once.
+                let mask = 1 << pin;
+                Self::sio_write(GPIO_OUT_CLR, prev_mask);
+                Self::sio_write(GPIO_OUT_SET, mask);
+                prev_mask = mask;
+
+                expires += dwell;
+                Timer::at(expires).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// WS2812 (NeoPixel) test.  Bit-bangs a fixed pattern of colours to a strip
+// of WS2812 LEDs on GPIO 2, repeated every FRAME_PERIOD, so the LED wiring
+// and timing margins can be checked.
+//
+// Each LED takes 24 bits, green then red then blue, MSB first.  Every bit
+// starts with the line high, and goes low after T0H (0 bit) or T1H (1 bit),
+// with each bit taking ~1.25us.  The WS2812B datasheet requires:
+//
+//   T0H: 400ns +/- 150ns
+//   T1H: 800ns +/- 150ns
+//   T0L: 850ns +/- 150ns
+//   T1L: 450ns +/- 150ns
+//
+// The line is then held low for RESET to latch the colours.  The original
+// WS2812 latches after 50us, but newer parts need 280us.
+//
+// Edges are placed at cycle offsets from the start of each bit, counted with
+// SysTick with interrupts disabled, so they don't depend on the compiled
+// code's timing.  The SysTick wait loop takes up to ~10 cycles to notice a
+// deadline, so each edge is up to ~80ns (Pico) or ~67ns (Pico 2) late, and
+// as every edge is late by a similar amount the high and low times are
+// typically within a few cycles of nominal - well within the +/-150ns
+// margins.
+
+use defmt::{info, warn};
+use embassy_rp::gpio::{Level, Output};
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::{GPIO_OUT_CLR, GPIO_OUT_SET, GPIO2_MASK, NOMINAL_SPEED, SYSTICK_MAX, Test};
+
+// Colours to send, as 0xRRGGBB.
+pub const COLORS: &[u32] = &[0xff0000, 0x00ff00, 0x0000ff, 0xffffff, 0x000000, 0x102040];
+
+// WS2812 timings, in clk_sys cycles, for NOMINAL_SPEED.  The Pico runs at
+// 125MHz (8ns per cycle), and the Pico 2 at 150MHz (6.67ns per cycle).
+#[cfg(feature = "pico")]
+mod cycles {
+    pub const T0H: u32 = 50; // 400ns
+    pub const T1H: u32 = 100; // 800ns
+    pub const BIT: u32 = 156; // 1248ns, so T0L 848ns, T1L 448ns
+}
+#[cfg(feature = "pico2")]
+mod cycles {
+    pub const T0H: u32 = 60; // 400ns
+    pub const T1H: u32 = 120; // 800ns
+    pub const BIT: u32 = 188; // 1253ns, so T0L 853ns, T1L 453ns
+}
+
+// Time the line is held low after the colours, to latch them.
+const RESET: Duration = Duration::from_micros(300);
+
+// Time from the start of one frame to the start of the next.
+const FRAME_PERIOD: Duration = Duration::from_millis(100);
+
+impl Test {
+    pub async fn ws2812(colors: &[u32]) -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        info!("WS2812 test");
+        info!(": Using GPIO 2");
+        info!(": {} LEDs: {:06x}", colors.len(), colors);
+        info!(
+            ": T0H: {} cycles ({}ns), T0L: {} cycles ({}ns)",
+            cycles::T0H,
+            Self::cycles_to_ns(cycles::T0H),
+            cycles::BIT - cycles::T0H,
+            Self::cycles_to_ns(cycles::BIT - cycles::T0H)
+        );
+        info!(
+            ": T1H: {} cycles ({}ns), T1L: {} cycles ({}ns)",
+            cycles::T1H,
+            Self::cycles_to_ns(cycles::T1H),
+            cycles::BIT - cycles::T1H,
+            Self::cycles_to_ns(cycles::BIT - cycles::T1H)
+        );
+        info!(": Reset: {}us", RESET.as_micros());
+        if speed != NOMINAL_SPEED {
+            warn!(
+                ": Cycle counts are for {} Hz, but clk_sys is {} Hz",
+                NOMINAL_SPEED, speed
+            );
+        }
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+        #[cfg(feature = "sync-pin")]
+        let _sync = Self::sync_output(p.PIN_3, "start of each frame, before its first bit");
+
+        Self::systick_start();
+
+        info!(": Starting");
+        let mut next_frame = Instant::now();
+        loop {
+            // The reset is always honoured, even if the frame overran.
+            cortex_m::interrupt::free(|_| Self::send_ws2812(colors));
+            Timer::after(RESET).await;
+            next_frame += FRAME_PERIOD;
+            Timer::at(next_frame).await;
+        }
+    }
+
+    // Sends the colours.  Must be called with interrupts disabled, and with
+    // SysTick started.
+    fn send_ws2812(colors: &[u32]) {
+        #[cfg(not(feature = "sync-pin"))]
+        let mut bit_start = Self::systick_now();
+        #[cfg(feature = "sync-pin")]
+        let mut bit_start = Self::sync_pulse();
+        for &color in colors {
+            // Reorder 0xRRGGBB to GRB, in the top 24 bits.
+            let grb = ((color & 0x00ff00) << 16) | (color & 0xff0000) | ((color & 0xff) << 8);
+            for bit in 0..24 {
+                let high = if grb & (0x8000_0000 >> bit) != 0 {
+                    cycles::T1H
+                } else {
+                    cycles::T0H
+                };
+                Self::sio_write(GPIO_OUT_SET, GPIO2_MASK);
+                Self::wait_until_cycles(bit_start, high);
+                Self::sio_write(GPIO_OUT_CLR, GPIO2_MASK);
+                Self::wait_until_cycles(bit_start, cycles::BIT);
+                bit_start = bit_start.wrapping_sub(cycles::BIT) & SYSTICK_MAX;
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// IRQ jitter under XIP thrash test.  Toggles GPIO 2 from a periodic SysTick
+// interrupt, and measures the interrupt's latency, alternately with the
+// background (thread mode) code idle, and with it thrashing the XIP cache.
+//
+// The handler, and the vector table, are in flash, so are normally fetched
+// from the XIP cache.  The thrash reads flash at scattered addresses, which
+// evicts them, so the interrupt often has to wait for its vector and code to
+// be fetched over QSPI - and for any fetch the background was part way
+// through.  This is the flash contention jitter that interrupt handlers run
+// from flash see in real firmware.  Reads evict cache lines the same as
+// instruction fetches, so the thrash just reads.
+//
+// SysTick counts down from its reload value, and pends its interrupt as it
+// reloads, so the cycles from then until the handler reads it are exactly
+// the latency.  Jitter is the spread between the shortest and longest.  The
+// XIP cache's hit rate during each phase is also reported, to show how hard
+// the cache was thrashed.
+//
+// As SysTick is used for the interrupt, it isn't free running in this test,
+// so systick_now() and friends mustn't be used.
+
+use core::cell::RefCell;
+use cortex_m::interrupt::Mutex;
+use cortex_m::peripheral::SYST;
+use cortex_m::peripheral::syst::SystClkSource;
+use cortex_m_rt::exception;
+use defmt::info;
+use embassy_rp::flash::FLASH_BASE;
+use embassy_rp::gpio::{Level, Output};
+use embassy_rp::pac;
+use embassy_time::{Duration, Instant};
+
+use crate::measurement::{Measurement, MeasurementResult};
+use crate::{GPIO_OUT_XOR, GPIO2_MASK, Test};
+
+// Interrupt period, and so half GPIO 2's period.
+const IRQ_PERIOD_US: u32 = 10;
+
+// Duration of each phase, idle and thrashing.
+const PHASE: Duration = Duration::from_secs(1);
+
+// Flash the thrash reads from, and the step between reads.  The step is an
+// odd number of 8 byte cache lines, so successive reads fall in different
+// cache sets, and the span is 64 times the 16KB cache.
+const THRASH_SPAN: u32 = 1024 * 1024;
+const THRASH_STEP: u32 = 4096 * 16 + 8;
+
+// Latencies, in cycles, since the last phase started.
+static LATENCY: Mutex<RefCell<Measurement>> = Mutex::new(RefCell::new(Measurement::new()));
+
+#[exception]
+fn SysTick() {
+    let now = SYST::get_current();
+    let latency = SYST::get_reload() - now;
+    Test::sio_write(GPIO_OUT_XOR, GPIO2_MASK);
+    cortex_m::interrupt::free(|cs| LATENCY.borrow(cs).borrow_mut().add(latency));
+}
+
+// Interrupt latency and XIP cache hit rate from one phase
+struct PhaseResult {
+    latency: MeasurementResult,
+    cache_hits: u32,
+    cache_accesses: u32,
+}
+
+impl Test {
+    pub async fn irq_jitter_under_xip_thrash() -> ! {
+        let p = Self::init();
+
+        let speed = embassy_rp::clocks::clk_sys_freq();
+        info!("IRQ jitter under XIP thrash test");
+        info!(": Toggling GPIO 2 from SysTick interrupt");
+        info!(": Interrupt period: {}us", IRQ_PERIOD_US);
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+
+        // Safety: Nothing else (including embassy) uses SysTick.
+        let mut syst = unsafe { cortex_m::Peripherals::steal() }.SYST;
+        syst.set_clock_source(SystClkSource::Core);
+        syst.set_reload(speed / 1_000_000 * IRQ_PERIOD_US - 1);
+        syst.clear_current();
+        syst.enable_counter();
+        syst.enable_interrupt();
+
+        info!(": Starting");
+        loop {
+            let idle = Self::run_phase(|until| {
+                while Instant::now() < until {
+                    core::hint::spin_loop();
+                }
+            });
+            let thrash = Self::run_phase(Self::thrash_xip);
+
+            for (phase, r) in [("Idle", &idle), ("XIP thrash", &thrash)] {
+                info!(
+                    ": {}: latency {}, jitter {}ns, XIP cache hits {}/{}",
+                    phase,
+                    r.latency,
+                    r.latency.max_ns - r.latency.min_ns,
+                    r.cache_hits,
+                    r.cache_accesses
+                );
+            }
+            info!(
+                ": Worst case jitter: idle {}ns, XIP thrash {}ns",
+                idle.latency.max_ns - idle.latency.min_ns,
+                thrash.latency.max_ns - thrash.latency.min_ns
+            );
+        }
+    }
+
+    // Runs background for PHASE, and returns the interrupt latencies and XIP
+    // cache counters while it ran.
+    fn run_phase(background: impl Fn(Instant)) -> PhaseResult {
+        // Writing any value clears the counters.
+        pac::XIP_CTRL.ctr_hit().write_value(0);
+        pac::XIP_CTRL.ctr_acc().write_value(0);
+        cortex_m::interrupt::free(|cs| LATENCY.borrow(cs).replace(Measurement::new()));
+
+        background(Instant::now() + PHASE);
+
+        let latency =
+            cortex_m::interrupt::free(|cs| LATENCY.borrow(cs).replace(Measurement::new()));
+        PhaseResult {
+            latency: latency.result(Self::cycles_to_ns),
+            cache_hits: pac::XIP_CTRL.ctr_hit().read(),
+            cache_accesses: pac::XIP_CTRL.ctr_acc().read(),
+        }
+    }
+
+    // Reads flash at scattered addresses until the given time, to evict
+    // everything else from the XIP cache.
+    #[inline(never)]
+    fn thrash_xip(until: Instant) {
+        let mut offset = 0;
+        while Instant::now() < until {
+            for _ in 0..64 {
+                offset = (offset + THRASH_STEP) % THRASH_SPAN;
+                // Safety: The address is within flash, which is always
+                // readable through XIP.
+                unsafe { core::ptr::read_volatile((FLASH_BASE as u32 + offset) as *const u32) };
+            }
+        }
+    }
+}
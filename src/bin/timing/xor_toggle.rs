@@ -0,0 +1,130 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// XOR toggle test.  Toggles GPIO 2 with a single store of its mask to
+// GPIO_OUT_XOR per edge, rather than alternating stores to GPIO_OUT_SET and
+// GPIO_OUT_CLR, so consecutive stores are all identical and the loop needs
+// no other instructions.  This gives the shortest period the core can
+// produce - a store per cycle, so a 2 cycle period.
+//
+// The stores are unrolled UNROLL times, so the branch back only stretches
+// one low half period in every UNROLL / 2, by its cycles.  At startup, the
+// average cycles per period of both this and GpioToggler's two store
+// minimum period loop are timed with SysTick and logged, for comparison.
+// Each is timed for LOOPS and 2 * LOOPS loops, with interrupts disabled,
+// and the difference used, so the setup cancels out.  The Pico 2's store
+// and branch timings are estimates, as for the other asm loops.
+//
+// At 125MHz, a 2 cycle period is 62.5MHz, beyond what the pad can follow
+// cleanly, so expect a small swing on a scope.
+
+use core::arch::asm;
+
+use defmt::info;
+use embassy_pico_test::gpio_asm::GpioToggler;
+use embassy_rp::gpio::{Level, Output};
+
+use crate::{GPIO_OUT_XOR, GPIO2_MASK, Test};
+
+// Stores per loop.  Must be even, so each loop ends with GPIO 2 as it
+// started.
+const UNROLL: u32 = 32;
+const _: () = assert!(UNROLL.is_multiple_of(2), "UNROLL must be even");
+
+// Loops to time, for the XOR loop, and periods for the two store loop.
+// 2 * LOOPS loops must take under SysTick's 2^24 cycles.
+const LOOPS: u32 = 10_000;
+const PERIODS: u32 = LOOPS * UNROLL / 2;
+
+impl Test {
+    pub async fn xor_toggle() -> ! {
+        let p = Self::init();
+
+        info!("XOR toggle test");
+        info!(": Using GPIO 2");
+        info!(": {} stores to GPIO_OUT_XOR per loop", UNROLL);
+
+        let _output = Output::new(p.PIN_2, Level::Low);
+        let toggler = GpioToggler::new(GPIO2_MASK);
+
+        // Thousandths of a cycle per period.
+        let (two_store, xor) = cortex_m::interrupt::free(|_| {
+            let single = Self::time_two_store(&toggler, PERIODS);
+            let double = Self::time_two_store(&toggler, 2 * PERIODS);
+            let two_store = (double - single) as u64 * 1000 / PERIODS as u64;
+
+            let single = Self::time_xor(LOOPS);
+            let double = Self::time_xor(2 * LOOPS);
+            let xor = (double - single) as u64 * 1000 / PERIODS as u64;
+
+            (two_store as u32, xor as u32)
+        });
+        for (name, mc) in [("Two store minimum", two_store), ("XOR", xor)] {
+            info!(
+                ": {}: {}.{:03} cycles per period ({}ns)",
+                name,
+                mc / 1000,
+                mc % 1000,
+                Self::cycles_to_ns(mc) / 1000
+            );
+        }
+        info!(
+            ": XOR saves {}.{:03} cycles per period",
+            two_store.saturating_sub(xor) / 1000,
+            two_store.saturating_sub(xor) % 1000
+        );
+
+        info!(": Starting");
+        cortex_m::interrupt::disable();
+        // Safety: Only stores GPIO 2's mask to GPIO_OUT_XOR, using the
+        // registers declared.
+        unsafe {
+            asm!(
+                "2:",
+                ".rept {unroll}",
+                "str r1, [r0]",
+                ".endr",
+                "b 2b",
+                unroll = const UNROLL,
+                in("r0") GPIO_OUT_XOR,
+                in("r1") GPIO2_MASK,
+                options(noreturn, nostack),
+            );
+        }
+    }
+
+    // Returns the cycles taken by GpioToggler's minimum period loop for
+    // periods periods.
+    fn time_two_store(toggler: &GpioToggler, periods: u32) -> u32 {
+        Self::systick_measure(|| {
+            toggler
+                .toggle_n_cycles::<{ GpioToggler::MIN_HIGH_CYCLES }, { GpioToggler::MIN_LOW_CYCLES }>(
+                    periods,
+                );
+        })
+    }
+
+    // Returns the cycles taken by loops loops of the XOR loop.
+    fn time_xor(loops: u32) -> u32 {
+        Self::systick_measure(|| {
+            // Safety: Only stores GPIO 2's mask to GPIO_OUT_XOR, using the
+            // registers declared.
+            unsafe {
+                asm!(
+                    "2:",
+                    ".rept {unroll}",
+                    "str r1, [r0]",
+                    ".endr",
+                    "subs r2, r2, #1",
+                    "bne 2b",
+                    unroll = const UNROLL,
+                    in("r0") GPIO_OUT_XOR,
+                    in("r1") GPIO2_MASK,
+                    inout("r2") loops => _,
+                    options(nostack),
+                );
+            }
+        })
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+// Yield with tasks test.  Runs test 11's toggle loop (a blocking delay then
+// yield_now() each half period) alongside a number of background tasks
+// which also yield_now(), and measures the period as the tasks are added.
+//
+// Tests 9-11 run as the only task, so yield_now() returns almost at once.
+// Each yield actually puts the task to the back of the executor's run
+// queue, so with other ready tasks, it only resumes once each of them has
+// run up to its own next yield.  The cost of each half period therefore
+// grows linearly with the number of tasks - by BUSY_CYCLES plus the
+// executor's per-task poll overhead each - which often surprises.
+//
+// Tasks can't be stopped once spawned, so one is added per round, from 0 up
+// to the number requested, with the period measured over PERIODS periods
+// in each round.  The loop then continues toggling with all of the tasks
+// running.
+
+use defmt::{error, info};
+use embassy_executor::Spawner;
+use embassy_futures::yield_now;
+use embassy_rp::gpio::{Level, Output};
+use embassy_time::{Delay, Instant};
+use embedded_hal::delay::DelayNs;
+
+use crate::Test;
+
+// Number of background tasks, and the cycles each busy-waits before every
+// yield (0 for tasks which only yield).
+pub const TASKS: usize = 4;
+const BUSY_CYCLES: u32 = 0;
+
+// Maximum number of background tasks.
+const MAX_TASKS: usize = 8;
+
+// Periods measured in each round.
+const PERIODS: u32 = 10_000;
+
+// Blocking delay each half period, as test 11.
+const DELAY_US: u32 = 1;
+
+#[embassy_executor::task(pool_size = MAX_TASKS)]
+async fn yield_task(busy_cycles: u32) {
+    loop {
+        if busy_cycles > 0 {
+            cortex_m::asm::delay(busy_cycles);
+        }
+        yield_now().await;
+    }
+}
+
+impl Test {
+    pub async fn toggle_yield_with_tasks(tasks: usize) -> ! {
+        let p = Self::init();
+
+        info!("Yield with tasks test");
+        info!(": Using GPIO 2");
+        info!(
+            ": {}us blocking delay then yield_now() each half period",
+            DELAY_US
+        );
+        info!(
+            ": Up to {} background tasks, busy for {} cycles per yield",
+            tasks, BUSY_CYCLES
+        );
+
+        if tasks > MAX_TASKS {
+            error!(": At most {} background tasks supported", MAX_TASKS);
+            Self::idle();
+        }
+
+        let spawner = Spawner::for_current_executor().await;
+        let mut output = Output::new(p.PIN_2, Level::Low);
+
+        info!(": Starting");
+        let mut baseline_ns = 0;
+        for count in 0..=tasks {
+            if count > 0 {
+                spawner.spawn(yield_task(BUSY_CYCLES)).unwrap();
+            }
+
+            let start = Instant::now();
+            for _ in 0..PERIODS {
+                Self::toggle_yield(&mut output).await;
+            }
+            let elapsed = Instant::now() - start;
+
+            let period_ns = (elapsed.as_micros() * 1000 / PERIODS as u64) as u32;
+            if count == 0 {
+                baseline_ns = period_ns;
+                info!(": Tasks: 0, period: {}ns", period_ns);
+            } else {
+                let added_ns = period_ns.saturating_sub(baseline_ns);
+                info!(
+                    ": Tasks: {}, period: {}ns (+{}ns, {}ns per task)",
+                    count,
+                    period_ns,
+                    added_ns,
+                    added_ns / count as u32
+                );
+            }
+        }
+
+        info!(": Continuing with {} background tasks", tasks);
+        loop {
+            Self::toggle_yield(&mut output).await;
+        }
+    }
+
+    // One period of test 11's loop.
+    async fn toggle_yield(output: &mut Output<'_>) {
+        output.set_high();
+        Delay.delay_us(DELAY_US);
+        yield_now().await;
+        output.set_low();
+        Delay.delay_us(DELAY_US);
+        yield_now().await;
+    }
+}
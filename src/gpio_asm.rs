@@ -0,0 +1,660 @@
+//! Hand counted asm GPIO helpers.
+//!
+//! Cycle counts are for the Pico's Cortex-M0+, where each SIO access and
+//! ALU instruction takes a cycle, and a taken branch two.  The Pico 2's
+//! Cortex-M33 can dual-issue some instructions, and its branch timing
+//! differs, so there they are estimates - measure the result.
+//!
+//! There are two levels of API:
+//! - [`GpioToggler`], which toggles a set of GPIOs for a number of periods,
+//!   or forever, as a single asm block, so its timing can't be disturbed by
+//!   the compiler.  It is safe.
+//! - The raw helpers, which a timing loop can be built from.
+//!   [`asm_load_gpio_out_addr()`], [`set_gpio_high()`] and [`set_gpio_low()`]
+//!   pass the GPIO_OUT address between separate asm blocks in r0, without
+//!   the compiler knowing, so are unsafe.  The padding helpers, and
+//!   [`asm_read_gpio_in()`], only use registers they declare, so are safe.
+
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+use core::arch::asm;
+
+use crate::sio::{self, GPIO_IN, GPIO_OUT, GPIO_OUT_CLR, GPIO_OUT_SET};
+
+/// Toggles a set of GPIOs together, with stores of their mask to
+/// GPIO_OUT_SET and GPIO_OUT_CLR, so other GPIOs are untouched.
+///
+/// The GPIOs must already be configured as SIO outputs, such as with
+/// embassy's `Output`, for the toggling to be seen.
+#[derive(Clone, Copy)]
+pub struct GpioToggler {
+    mask: u32,
+}
+
+impl GpioToggler {
+    /// The shortest high half period of [`toggle_n_cycles()`](Self::toggle_n_cycles) - the
+    /// falling edge's store.
+    pub const MIN_HIGH_CYCLES: u32 = 1;
+
+    /// The shortest low half period of [`toggle_n_cycles()`](Self::toggle_n_cycles) - the
+    /// loop counter's subs and bne, and the rising edge's store.
+    #[cfg(feature = "pico")]
+    pub const MIN_LOW_CYCLES: u32 = 4;
+    /// The shortest low half period of [`toggle_n_cycles()`](Self::toggle_n_cycles) - the
+    /// loop counter's subs and bne, and the rising edge's store.
+    #[cfg(feature = "pico2")]
+    pub const MIN_LOW_CYCLES: u32 = 3;
+
+    /// The shortest high half period of [`toggle_forever()`](Self::toggle_forever) - the
+    /// falling edge's store.
+    pub const FOREVER_MIN_HIGH_CYCLES: u32 = 1;
+
+    /// The shortest low half period of [`toggle_forever()`](Self::toggle_forever) - the
+    /// branch back, and the rising edge's store.
+    #[cfg(feature = "pico")]
+    pub const FOREVER_MIN_LOW_CYCLES: u32 = 3;
+    /// The shortest low half period of [`toggle_forever()`](Self::toggle_forever) - the
+    /// branch back, and the rising edge's store.
+    #[cfg(feature = "pico2")]
+    pub const FOREVER_MIN_LOW_CYCLES: u32 = 2;
+
+    /// Creates a toggler for the GPIOs whose bits are set in mask.
+    pub const fn new(mask: u32) -> Self {
+        Self { mask }
+    }
+
+    /// Returns the GPIOs' mask.
+    pub const fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    /// Drives the GPIOs high.
+    #[inline(always)]
+    pub fn set_high(&self) {
+        sio::set_gpios(self.mask)
+    }
+
+    /// Drives the GPIOs low.
+    #[inline(always)]
+    pub fn set_low(&self) {
+        sio::clear_gpios(self.mask)
+    }
+
+    /// Toggles the GPIOs for `periods` periods, each high for HIGH_CYCLES
+    /// and then low for LOW_CYCLES, then returns, leaving them low.
+    ///
+    /// HIGH_CYCLES and LOW_CYCLES must be at least
+    /// [`MIN_HIGH_CYCLES`](Self::MIN_HIGH_CYCLES) and
+    /// [`MIN_LOW_CYCLES`](Self::MIN_LOW_CYCLES), which is checked at
+    /// compile time.  The rest of each half period is padded with a chain
+    /// of dependent adds, as the Pico 2 can dual-issue nops.
+    #[inline(always)]
+    pub fn toggle_n_cycles<const HIGH_CYCLES: u32, const LOW_CYCLES: u32>(&self, periods: u32) {
+        const {
+            assert!(
+                HIGH_CYCLES >= Self::MIN_HIGH_CYCLES && LOW_CYCLES >= Self::MIN_LOW_CYCLES,
+                "Half periods are too short"
+            );
+        }
+
+        // r0: GPIO_OUT_SET, r1: mask, r2: periods remaining, r3: padding.
+        // No periods is checked within the asm, rather than before it, as
+        // the compiler doesn't know the .rept padding's size, so may branch
+        // over it with a cbz, which can't reach.
+        unsafe {
+            asm!(
+                "cmp r2, #0",
+                "beq 3f",
+                "2:",
+                "str r1, [r0]",          // High
+                ".rept {high} - {min_high}",
+                "adds r3, r3, #1",
+                ".endr",
+                "str r1, [r0, #{clr}]",  // Low
+                ".rept {low} - {min_low}",
+                "adds r3, r3, #1",
+                ".endr",
+                "subs r2, r2, #1",
+                "bne 2b",
+                "3:",
+                in("r0") GPIO_OUT_SET,
+                in("r1") self.mask,
+                inout("r2") periods => _,
+                out("r3") _,
+                clr = const GPIO_OUT_CLR - GPIO_OUT_SET,
+                high = const HIGH_CYCLES,
+                low = const LOW_CYCLES,
+                min_high = const Self::MIN_HIGH_CYCLES,
+                min_low = const Self::MIN_LOW_CYCLES,
+                options(nostack),
+            );
+        }
+    }
+
+    /// Toggles the GPIOs forever, each period high for HIGH_CYCLES and then
+    /// low for LOW_CYCLES.
+    ///
+    /// HIGH_CYCLES and LOW_CYCLES must be at least
+    /// [`FOREVER_MIN_HIGH_CYCLES`](Self::FOREVER_MIN_HIGH_CYCLES) and
+    /// [`FOREVER_MIN_LOW_CYCLES`](Self::FOREVER_MIN_LOW_CYCLES), and within
+    /// [`delay_cycles()`]'s range, which is checked at compile time.  The rest
+    /// of each half period is the same counted loop and padding as
+    /// [`delay_cycles()`], so half periods can be long without unrolling.
+    #[cfg(feature = "pico")]
+    pub fn toggle_forever<const HIGH_CYCLES: u32, const LOW_CYCLES: u32>(&self) -> ! {
+        // r0: GPIO_OUT_SET, r1: mask, r2: delay count and padding.
+        unsafe {
+            asm!(
+                "2:",
+                "str r1, [r0]",          // High
+                ".if {high_count}",
+                "movs r2, #({high_count} >> 8)",
+                "lsls r2, r2, #8",
+                "adds r2, #({high_count} & 0xff)",
+                "3:",
+                "subs r2, r2, #1",
+                "bne 3b",
+                ".endif",
+                ".rept {high_pad}",
+                "adds r2, r2, #1",
+                ".endr",
+                "str r1, [r0, #{clr}]",  // Low
+                ".if {low_count}",
+                "movs r2, #({low_count} >> 8)",
+                "lsls r2, r2, #8",
+                "adds r2, #({low_count} & 0xff)",
+                "4:",
+                "subs r2, r2, #1",
+                "bne 4b",
+                ".endif",
+                ".rept {low_pad}",
+                "adds r2, r2, #1",
+                ".endr",
+                "b 2b",
+                in("r0") GPIO_OUT_SET,
+                in("r1") self.mask,
+                clr = const GPIO_OUT_CLR - GPIO_OUT_SET,
+                high_count = const ForeverDelays::<HIGH_CYCLES, LOW_CYCLES>::HIGH_COUNT,
+                high_pad = const ForeverDelays::<HIGH_CYCLES, LOW_CYCLES>::HIGH_PAD,
+                low_count = const ForeverDelays::<HIGH_CYCLES, LOW_CYCLES>::LOW_COUNT,
+                low_pad = const ForeverDelays::<HIGH_CYCLES, LOW_CYCLES>::LOW_PAD,
+                options(noreturn, nostack),
+            );
+        }
+    }
+
+    /// Toggles the GPIOs forever, each period high for HIGH_CYCLES and then
+    /// low for LOW_CYCLES.
+    ///
+    /// HIGH_CYCLES and LOW_CYCLES must be at least
+    /// [`FOREVER_MIN_HIGH_CYCLES`](Self::FOREVER_MIN_HIGH_CYCLES) and
+    /// [`FOREVER_MIN_LOW_CYCLES`](Self::FOREVER_MIN_LOW_CYCLES), and within
+    /// [`delay_cycles()`]'s range, which is checked at compile time.  The rest
+    /// of each half period is the same counted loop and padding as
+    /// [`delay_cycles()`], so half periods can be long without unrolling.
+    #[cfg(feature = "pico2")]
+    pub fn toggle_forever<const HIGH_CYCLES: u32, const LOW_CYCLES: u32>(&self) -> ! {
+        // r0: GPIO_OUT_SET, r1: mask, r2: delay count and padding.
+        unsafe {
+            asm!(
+                "2:",
+                "str r1, [r0]",          // High
+                ".if {high_count}",
+                "movw r2, #{high_count}",
+                "3:",
+                "subs r2, r2, #1",
+                "bne 3b",
+                ".endif",
+                ".rept {high_pad}",
+                "adds r2, r2, #1",
+                ".endr",
+                "str r1, [r0, #{clr}]",  // Low
+                ".if {low_count}",
+                "movw r2, #{low_count}",
+                "4:",
+                "subs r2, r2, #1",
+                "bne 4b",
+                ".endif",
+                ".rept {low_pad}",
+                "adds r2, r2, #1",
+                ".endr",
+                "b 2b",
+                in("r0") GPIO_OUT_SET,
+                in("r1") self.mask,
+                clr = const GPIO_OUT_CLR - GPIO_OUT_SET,
+                high_count = const ForeverDelays::<HIGH_CYCLES, LOW_CYCLES>::HIGH_COUNT,
+                high_pad = const ForeverDelays::<HIGH_CYCLES, LOW_CYCLES>::HIGH_PAD,
+                low_count = const ForeverDelays::<HIGH_CYCLES, LOW_CYCLES>::LOW_COUNT,
+                low_pad = const ForeverDelays::<HIGH_CYCLES, LOW_CYCLES>::LOW_PAD,
+                options(noreturn, nostack),
+            );
+        }
+    }
+}
+
+// toggle_forever()'s delays after each half period's store, and the low
+// half's branch back, for use as asm const operands.
+struct ForeverDelays<const HIGH_CYCLES: u32, const LOW_CYCLES: u32>;
+
+impl<const HIGH_CYCLES: u32, const LOW_CYCLES: u32> ForeverDelays<HIGH_CYCLES, LOW_CYCLES> {
+    const HIGH: (u32, u32) = {
+        assert!(
+            HIGH_CYCLES >= GpioToggler::FOREVER_MIN_HIGH_CYCLES,
+            "Half periods are too short"
+        );
+        delay_loop_split(HIGH_CYCLES - GpioToggler::FOREVER_MIN_HIGH_CYCLES)
+    };
+    const LOW: (u32, u32) = {
+        assert!(
+            LOW_CYCLES >= GpioToggler::FOREVER_MIN_LOW_CYCLES,
+            "Half periods are too short"
+        );
+        delay_loop_split(LOW_CYCLES - GpioToggler::FOREVER_MIN_LOW_CYCLES)
+    };
+    const HIGH_COUNT: u32 = Self::HIGH.0;
+    const HIGH_PAD: u32 = Self::HIGH.1;
+    const LOW_COUNT: u32 = Self::LOW.0;
+    const LOW_PAD: u32 = Self::LOW.1;
+}
+
+/// Loads the GPIO_OUT register address into register r0, for
+/// [`set_gpio_high()`] and [`set_gpio_low()`].
+///
+/// # Safety
+///
+/// r0 is passed to the set helpers without the compiler knowing, so nothing
+/// the compiler generates between this and them may change r0.  Only use
+/// these in a loop made up of nothing but these helpers and the padding
+/// helpers, and check the generated code.
+#[cfg(feature = "pico")]
+#[inline(always)]
+pub unsafe fn asm_load_gpio_out_addr() {
+    // SIO base is 0xd0000000
+    // GPIO_OUT register is SIO_base + 0x10
+    unsafe {
+        asm!(
+            "movs r1, #0xd0",
+            "lsls r1, r1, #24", // Shift left 3 bytes, 24 bits
+            "movs r2, #0x10",
+            "adds r0, r1, r2",  // Add SIO based and GPIO_OUT offset
+            out("r0") _,  // Tell compiler what registers we used
+            out("r1") _,
+            out("r2") _,
+            options(nomem, nostack),
+        );
+    }
+}
+
+/// Loads the GPIO_OUT register address into register r0, for
+/// [`set_gpio_high()`] and [`set_gpio_low()`].  On the Pico 2, movw/movt
+/// load the address in two instructions.
+///
+/// # Safety
+///
+/// r0 is passed to the set helpers without the compiler knowing, so nothing
+/// the compiler generates between this and them may change r0.  Only use
+/// these in a loop made up of nothing but these helpers and the padding
+/// helpers, and check the generated code.
+#[cfg(feature = "pico2")]
+#[inline(always)]
+pub unsafe fn asm_load_gpio_out_addr() {
+    unsafe {
+        asm!(
+            "movw r0, #{lo}",
+            "movt r0, #{hi}",
+            lo = const GPIO_OUT & 0xffff,
+            hi = const GPIO_OUT >> 16,
+            out("r0") _,
+            options(nomem, nostack),
+        );
+    }
+}
+
+/// Returns GPIO_IN, the current level of every GPIO, for checking the raw
+/// helpers' stores reached the pins.  3 cycles on the Pico.
+///
+/// Only uses r1, as the set helpers do, so doesn't disturb r0.  However,
+/// whatever the compiler generates to handle the value returned may, so
+/// reload it with [`asm_load_gpio_out_addr()`] before setting again.
+#[cfg(feature = "pico")]
+#[inline(always)]
+pub fn asm_read_gpio_in() -> u32 {
+    let value;
+    // Safety: Reading GPIO_IN has no side effects.
+    unsafe {
+        asm!(
+            "movs r1, #0xd0",
+            "lsls r1, r1, #24",  // Shift left 3 bytes, 24 bits
+            "ldr r1, [r1, #{offset}]", // Load GPIO_IN, at SIO base + 0x04
+            offset = const GPIO_IN - sio::SIO_BASE,
+            out("r1") value,
+        );
+    }
+    value
+}
+
+/// Returns GPIO_IN, the current level of every GPIO, for checking the raw
+/// helpers' stores reached the pins.  On the Pico 2, movw/movt load the
+/// address in two instructions.
+///
+/// Only uses r1, as the set helpers do, so doesn't disturb r0.  However,
+/// whatever the compiler generates to handle the value returned may, so
+/// reload it with [`asm_load_gpio_out_addr()`] before setting again.
+#[cfg(feature = "pico2")]
+#[inline(always)]
+pub fn asm_read_gpio_in() -> u32 {
+    let value;
+    // Safety: Reading GPIO_IN has no side effects.
+    unsafe {
+        asm!(
+            "movw r1, #{lo}",
+            "movt r1, #{hi}",
+            "ldr r1, [r1]",
+            lo = const GPIO_IN & 0xffff,
+            hi = const GPIO_IN >> 16,
+            out("r1") value,
+        );
+    }
+    value
+}
+
+/// Whether [`set_gpio_high()`] and [`set_gpio_low()`] store to GPIO_OUT_SET
+/// and GPIO_OUT_CLR, leaving other GPIOs untouched, as they do by default.
+/// With the raw-gpio-out feature they store to GPIO_OUT directly instead.
+pub const ATOMIC_SET_CLR: bool = !cfg!(feature = "raw-gpio-out");
+
+/// Sets GPIO PIN high, by writing its bit to GPIO_OUT_SET, which is at a
+/// fixed offset from GPIO_OUT, so other GPIOs are untouched.  A str with an
+/// immediate offset takes the same cycle as one without.
+///
+/// With the raw-gpio-out feature, the bit is instead written to GPIO_OUT
+/// itself, which drives every other SIO output low, for comparing the two.
+/// Both take the same cycles, so the asm tests' periods don't change.
+///
+/// The mask is computed from PIN at compile time.  movs can only load
+/// 0-255, so for GPIOs 0-7 this is 2 cycles, as the timing binary's asm
+/// loops assume.  Higher GPIOs need a shift as well, taking a cycle more.
+///
+/// # Safety
+///
+/// r0 must hold GPIO_OUT, from [`asm_load_gpio_out_addr()`] - see its safety
+/// section.
+#[inline(always)]
+pub unsafe fn set_gpio_high<const PIN: u8>() {
+    unsafe {
+        asm!(
+            ".if {mask} < 0x100",
+            "movs r1, #{mask}", // Set r1 to PIN's bit
+            ".else",
+            "movs r1, #1",
+            "lsls r1, r1, #{pin}",
+            ".endif",
+            "str r1, [r0, #{set}]", // Store r1 to GPIO_OUT_SET, or GPIO_OUT
+            mask = const 1u32 << PIN,
+            pin = const PIN,
+            set = const SET_OFFSET,
+            out("r1") _,
+            options(nostack),
+        );
+    }
+}
+
+/// Sets GPIO PIN low, by writing its bit to GPIO_OUT_CLR, which is at a
+/// fixed offset from GPIO_OUT, so other GPIOs are untouched.
+///
+/// The mask is computed from PIN at compile time, as for
+/// [`set_gpio_high()`], so for GPIOs 0-7 this is 2 cycles.
+///
+/// With the raw-gpio-out feature, 0 is instead written to GPIO_OUT, driving
+/// every SIO output low.  That's always 2 cycles, as 0 needs no shift, so
+/// on GPIOs above 7 the low edge is a cycle shorter than by default.
+///
+/// # Safety
+///
+/// r0 must hold GPIO_OUT, from [`asm_load_gpio_out_addr()`] - see its safety
+/// section.
+#[inline(always)]
+pub unsafe fn set_gpio_low<const PIN: u8>() {
+    unsafe {
+        asm!(
+            ".if {direct}",
+            "movs r1, #0",
+            ".elseif {mask} < 0x100",
+            "movs r1, #{mask}",   // Set r1 to PIN's bit
+            ".else",
+            "movs r1, #1",
+            "lsls r1, r1, #{pin}",
+            ".endif",
+            "str r1, [r0, #{clr}]", // Store r1 to GPIO_OUT_CLR, or GPIO_OUT
+            direct = const !ATOMIC_SET_CLR as u32,
+            mask = const 1u32 << PIN,
+            pin = const PIN,
+            clr = const CLR_OFFSET,
+            out("r1") _,
+            options(nostack),
+        );
+    }
+}
+
+// Offsets from GPIO_OUT of the registers the set helpers store to.
+const SET_OFFSET: u32 = if ATOMIC_SET_CLR {
+    GPIO_OUT_SET - GPIO_OUT
+} else {
+    0
+};
+const CLR_OFFSET: u32 = if ATOMIC_SET_CLR {
+    GPIO_OUT_CLR - GPIO_OUT
+} else {
+    0
+};
+
+/// Cycles per count of [`delay_cycles()`]'s subs/bne loop - 1 for the subs
+/// and 2 for the taken branch on the Pico.  The Pico 2's is an estimate,
+/// assuming a taken branch takes 1 cycle, as its other asm loops do.
+#[cfg(feature = "pico")]
+pub const DELAY_LOOP_CYCLES_PER_COUNT: u32 = 3;
+/// Cycles per count of [`delay_cycles()`]'s subs/bne loop - 1 for the subs
+/// and 2 for the taken branch on the Pico.  The Pico 2's is an estimate,
+/// assuming a taken branch takes 1 cycle, as its other asm loops do.
+#[cfg(feature = "pico2")]
+pub const DELAY_LOOP_CYCLES_PER_COUNT: u32 = 2;
+
+/// Cycles of [`delay_cycles()`]'s loop other than those per count - loading
+/// the count with movs, lsls and adds, less the cycle saved by the final bne
+/// not being taken.
+#[cfg(feature = "pico")]
+pub const DELAY_LOOP_FIXED_CYCLES: u32 = 2;
+/// Cycles of [`delay_cycles()`]'s loop other than those per count - loading
+/// the count with movw.
+#[cfg(feature = "pico2")]
+pub const DELAY_LOOP_FIXED_CYCLES: u32 = 1;
+
+/// The largest count [`delay_cycles()`]'s loop can load.
+pub const DELAY_LOOP_MAX_COUNT: u32 = 0xffff;
+
+/// Returns the number of clk_sys cycles in ns at sysclk_hz, rounded to the
+/// nearest cycle.
+pub const fn cycles_for_period(ns: u32, sysclk_hz: u32) -> u32 {
+    ((ns as u64 * sysclk_hz as u64 + 500_000_000) / 1_000_000_000) as u32
+}
+
+/// Splits a delay of cycles into a count for [`delay_cycles()`]'s loop, and
+/// the 0 to [`DELAY_LOOP_CYCLES_PER_COUNT`] - 1 cycles of adds to pad it
+/// with.  Delays too short for the loop are all padding.  Panics, at
+/// compile time when used in a const, if the count would be over
+/// [`DELAY_LOOP_MAX_COUNT`].
+pub const fn delay_loop_split(cycles: u32) -> (u32, u32) {
+    if cycles < DELAY_LOOP_FIXED_CYCLES + DELAY_LOOP_CYCLES_PER_COUNT {
+        return (0, cycles);
+    }
+    let count = (cycles - DELAY_LOOP_FIXED_CYCLES) / DELAY_LOOP_CYCLES_PER_COUNT;
+    assert!(count <= DELAY_LOOP_MAX_COUNT, "Delay is too long");
+    (
+        count,
+        cycles - DELAY_LOOP_FIXED_CYCLES - count * DELAY_LOOP_CYCLES_PER_COUNT,
+    )
+}
+
+// delay_loop_split() of a const generic, for use as asm const operands.
+struct DelayLoop<const CYCLES: u32>;
+
+impl<const CYCLES: u32> DelayLoop<CYCLES> {
+    const COUNT: u32 = delay_loop_split(CYCLES).0;
+    const PAD: u32 = delay_loop_split(CYCLES).1;
+}
+
+/// Spins for exactly CYCLES cycles (an estimate on the Pico 2), as a
+/// counted subs/bne loop, padded with up to 2 adds, rather than an unrolled
+/// chain of adds.  Use with [`cycles_for_period()`] to delay for a time
+/// rather than a hand counted number of cycles.
+///
+/// CYCLES can be up to about 196,000 on the Pico, and 131,000 on the Pico
+/// 2, which is checked at compile time.
+#[cfg(feature = "pico")]
+#[inline(always)]
+pub fn delay_cycles<const CYCLES: u32>() {
+    unsafe {
+        asm!(
+            ".if {count}",
+            "movs r2, #({count} >> 8)",
+            "lsls r2, r2, #8",
+            "adds r2, #({count} & 0xff)",
+            "2:",
+            "subs r2, r2, #1",
+            "bne 2b",
+            ".endif",
+            ".rept {pad}",
+            "adds r2, r2, #1",
+            ".endr",
+            count = const DelayLoop::<CYCLES>::COUNT,
+            pad = const DelayLoop::<CYCLES>::PAD,
+            out("r2") _,
+            options(nomem, nostack),
+        );
+    }
+}
+
+/// Spins for exactly CYCLES cycles (an estimate on the Pico 2), as a
+/// counted subs/bne loop, padded with up to 1 add, rather than an unrolled
+/// chain of adds.  Use with [`cycles_for_period()`] to delay for a time
+/// rather than a hand counted number of cycles.
+///
+/// CYCLES can be up to about 196,000 on the Pico, and 131,000 on the Pico
+/// 2, which is checked at compile time.
+#[cfg(feature = "pico2")]
+#[inline(always)]
+pub fn delay_cycles<const CYCLES: u32>() {
+    unsafe {
+        asm!(
+            ".if {count}",
+            "movw r2, #{count}",
+            "2:",
+            "subs r2, r2, #1",
+            "bne 2b",
+            ".endif",
+            ".rept {pad}",
+            "adds r2, r2, #1",
+            ".endr",
+            count = const DelayLoop::<CYCLES>::COUNT,
+            pad = const DelayLoop::<CYCLES>::PAD,
+            out("r2") _,
+            options(nomem, nostack),
+        );
+    }
+}
+
+/// Spins for about `cycles` cycles, from a runtime count, as a subs/bhi
+/// loop taking [`DELAY_LOOP_CYCLES_PER_COUNT`] cycles per iteration, rather
+/// than an unrolled chain of adds.  Use [`delay_cycles()`] instead when the
+/// delay is known at compile time, as that is exact.
+///
+/// Each iteration subtracts its own cycles from `cycles`, so there's no
+/// division, and the loop exits on the iteration that takes it to 0 or
+/// below.  The delay is therefore `cycles` rounded up to a whole number of
+/// iterations, less, on the Pico, the cycle saved by the final branch not
+/// being taken.  That is within a cycle either way on the Pico, and up to a
+/// cycle long on the Pico 2, except that 0 takes 2 cycles, plus loading
+/// `cycles` into r2.  The Pico 2's iteration is an estimate, as for
+/// [`delay_cycles()`].
+///
+/// Only uses r2, so, like the padding helpers, can be used between
+/// [`asm_load_gpio_out_addr()`] and the set helpers.
+#[inline(always)]
+pub fn delay_loop(cycles: u32) {
+    unsafe {
+        asm!(
+            "2:",
+            "subs r2, r2, #{per_count}",
+            "bhi 2b",
+            per_count = const DELAY_LOOP_CYCLES_PER_COUNT,
+            inout("r2") cycles => _,
+            options(nomem, nostack),
+        );
+    }
+}
+
+/// 1 cycle, using r2
+#[inline(always)]
+pub fn asm_1_cycle_r2() {
+    unsafe {
+        asm!("movs r2, #1", out("r2") _);
+    }
+}
+
+/// 2 cycles, using r2
+#[inline(always)]
+pub fn asm_2_cycles_add_r2() {
+    unsafe {
+        asm!("movs r2, #1", "adds r2, r2, #1", out("r2") _);
+    }
+}
+
+/// 3 cycles, using r2
+#[inline(always)]
+pub fn asm_3_cycles_add_r2() {
+    unsafe {
+        asm!("movs r2, #1", ".rept 2", "adds r2, r2, #1", ".endr", out("r2") _);
+    }
+}
+
+/// 5 cycles, using r2
+#[inline(always)]
+pub fn asm_5_cycles_r2() {
+    unsafe {
+        asm!("movs r2, #1", ".rept 4", "adds r2, r2, #1", ".endr", out("r2") _);
+    }
+}
+
+/// 9 cycles, using r2 - 72ns on the Pico
+#[inline(always)]
+pub fn asm_9_cycles_add_r2() {
+    unsafe {
+        asm!("movs r2, #1", ".rept 8", "adds r2, r2, #1", ".endr", out("r2") _);
+    }
+}
+
+/// 10 cycles, using r2 - 80ns on the Pico
+#[inline(always)]
+pub fn asm_10_cycles_add_r2() {
+    unsafe {
+        asm!("movs r2, #1", ".rept 9", "adds r2, r2, #1", ".endr", out("r2") _);
+    }
+}
+
+/// 9 nops - 72ns on the Pico
+#[inline(always)]
+pub fn asm_9_cycles_nop() {
+    unsafe {
+        asm!(".rept 9", "nop", ".endr");
+    }
+}
+
+/// 10 nops - 80ns on the Pico
+#[inline(always)]
+pub fn asm_10_cycles_nop() {
+    unsafe {
+        asm!(".rept 10", "nop", ".endr");
+    }
+}
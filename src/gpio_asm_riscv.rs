@@ -0,0 +1,114 @@
+//! RISC-V asm GPIO helpers, for the Pico 2's Hazard3 cores.
+//!
+//! The RV32 equivalents of the `gpio_asm` module's raw Thumb helpers, with
+//! the same split: [`asm_load_gpio_out_addr()`] loads the GPIO_OUT address
+//! into t0, and [`set_gpio_high()`] and [`set_gpio_low()`] store from it,
+//! passing it between separate asm blocks without the compiler knowing, so
+//! they are unsafe.  They're used by the `riscv-toggle` binary.
+//!
+//! Hazard3's instruction timings aren't characterised here, so unlike the
+//! Thumb helpers these make no cycle count promises - measure the result.
+
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+use core::arch::asm;
+
+use crate::sio::{GPIO_OUT, GPIO_OUT_CLR, GPIO_OUT_SET};
+
+/// Whether [`set_gpio_high()`] and [`set_gpio_low()`] store to GPIO_OUT_SET
+/// and GPIO_OUT_CLR, leaving other GPIOs untouched, as they do by default.
+/// With the raw-gpio-out feature they store to GPIO_OUT directly instead.
+pub const ATOMIC_SET_CLR: bool = !cfg!(feature = "raw-gpio-out");
+
+// Offsets from GPIO_OUT of the registers the set helpers store to.
+const SET_OFFSET: u32 = if ATOMIC_SET_CLR {
+    GPIO_OUT_SET - GPIO_OUT
+} else {
+    0
+};
+const CLR_OFFSET: u32 = if ATOMIC_SET_CLR {
+    GPIO_OUT_CLR - GPIO_OUT
+} else {
+    0
+};
+
+/// Loads the GPIO_OUT register address into register t0, for
+/// [`set_gpio_high()`] and [`set_gpio_low()`].  li assembles to a lui and
+/// an addi.
+///
+/// # Safety
+///
+/// t0 is passed to the set helpers without the compiler knowing, so nothing
+/// the compiler generates between this and them may change t0.  Only use
+/// these in a loop made up of nothing but these helpers, and check the
+/// generated code.
+#[inline(always)]
+pub unsafe fn asm_load_gpio_out_addr() {
+    unsafe {
+        asm!(
+            "li t0, {gpio_out}",
+            gpio_out = const GPIO_OUT,
+            out("t0") _,
+            options(nomem, nostack),
+        );
+    }
+}
+
+/// Sets GPIO PIN high, by writing its bit to GPIO_OUT_SET, which is at a
+/// fixed offset from GPIO_OUT, so other GPIOs are untouched.  The mask is
+/// computed from PIN at compile time, and loaded with li, which is a single
+/// instruction for every GPIO but 11, whose bit needs a lui and an addi.
+///
+/// With the raw-gpio-out feature, the bit is instead written to GPIO_OUT
+/// itself, which drives every other SIO output low.
+///
+/// # Safety
+///
+/// t0 must hold GPIO_OUT, from [`asm_load_gpio_out_addr()`] - see its
+/// safety section.
+#[inline(always)]
+pub unsafe fn set_gpio_high<const PIN: u8>() {
+    unsafe {
+        asm!(
+            "li t1, {mask}",        // Set t1 to PIN's bit
+            "sw t1, {set}(t0)",     // Store t1 to GPIO_OUT_SET, or GPIO_OUT
+            mask = const 1u32 << PIN,
+            set = const SET_OFFSET,
+            out("t1") _,
+            options(nostack),
+        );
+    }
+}
+
+/// Sets GPIO PIN low, by writing its bit to GPIO_OUT_CLR, which is at a
+/// fixed offset from GPIO_OUT, so other GPIOs are untouched.  The mask is
+/// loaded as for [`set_gpio_high()`].
+///
+/// With the raw-gpio-out feature, the zero register is instead stored to
+/// GPIO_OUT, driving every SIO output low.  That needs no mask, so is an
+/// instruction shorter than by default.
+///
+/// # Safety
+///
+/// t0 must hold GPIO_OUT, from [`asm_load_gpio_out_addr()`] - see its
+/// safety section.
+#[inline(always)]
+pub unsafe fn set_gpio_low<const PIN: u8>() {
+    unsafe {
+        asm!(
+            ".if {direct}",
+            "sw zero, 0(t0)",       // Store 0 to GPIO_OUT
+            ".else",
+            "li t1, {mask}",        // Set t1 to PIN's bit
+            "sw t1, {clr}(t0)",     // Store t1 to GPIO_OUT_CLR
+            ".endif",
+            direct = const !ATOMIC_SET_CLR as u32,
+            mask = const 1u32 << PIN,
+            clr = const CLR_OFFSET,
+            out("t1") _,
+            options(nostack),
+        );
+    }
+}
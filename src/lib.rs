@@ -0,0 +1,38 @@
+//! Reusable pieces of the timing tests.
+//!
+//! [`sio`] contains the SIO GPIO register addresses, and safe helpers to
+//! read and write them.  [`gpio_asm`] contains the hand counted asm GPIO
+//! toggling and padding helpers that the `timing` binary's asm tests are
+//! built from.  Both are specialised for the Pico (RP2040) or Pico 2
+//! (RP2350) by the `pico` and `pico2` features, exactly one of which must
+//! be enabled.  [`parse`] contains the frequency and period parser, and
+//! [`nec`] the NEC IR remote frame encoding.
+//!
+//! With the `riscv` feature, for the Pico 2's Hazard3 cores,
+//! `gpio_asm_riscv` contains RV32 equivalents of [`gpio_asm`]'s raw
+//! helpers, in place of it.
+//!
+//! [`parse`] and [`nec`] are plain Rust, so are unit tested on the host,
+//! with the host's target, such as:
+//!
+//! ```text
+//! cargo test --lib --target x86_64-unknown-linux-gnu
+//! ```
+//!
+//! The embedded dependencies are only used for bare metal targets, so
+//! aren't built for this, and [`gpio_asm`]'s Thumb asm is only built for
+//! Arm, as is `gpio_asm_riscv`'s for RISC-V.
+
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+#![cfg_attr(not(test), no_std)]
+
+#[cfg(target_arch = "arm")]
+pub mod gpio_asm;
+#[cfg(target_arch = "riscv32")]
+pub mod gpio_asm_riscv;
+pub mod nec;
+pub mod parse;
+pub mod sio;
@@ -0,0 +1,138 @@
+//! NEC IR remote frame and envelope encoding.
+//!
+//! An NEC frame is a 9ms mark and a 4.5ms space, then 32 bits (address,
+//! inverted address, command, inverted command, each LSB first), and
+//! finally a stop mark.  Every bit is a 562.5us mark, followed by a 562.5us
+//! space for a 0, or a 1687.5us space for a 1.
+//!
+//! [`nec_frame()`] builds the frame's bits, and [`nec_envelope()`] the
+//! marks (carrier on) and spaces (carrier off) to send them as - see the
+//! `timing` binary's NEC IR remote test, which sends them.
+
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+/// Leader mark
+pub const LEADER_MARK_NS: u32 = 9_000_000;
+/// Leader space
+pub const LEADER_SPACE_NS: u32 = 4_500_000;
+/// Mark at the start of every bit, and the stop mark
+pub const BIT_MARK_NS: u32 = 562_500;
+/// Space after a 0 bit's mark
+pub const ZERO_SPACE_NS: u32 = 562_500;
+/// Space after a 1 bit's mark
+pub const ONE_SPACE_NS: u32 = 1_687_500;
+
+/// Bits in a frame
+pub const FRAME_BITS: usize = 32;
+/// Pulses in an envelope - the leader, one per bit, and the stop mark
+pub const ENVELOPE_LEN: usize = FRAME_BITS + 2;
+
+/// A mark (carrier on) followed by a space (carrier off).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pulse {
+    pub mark_ns: u32,
+    pub space_ns: u32,
+}
+
+/// Builds the 32 bit NEC frame, in the order its bits are sent, LSB first.
+pub fn nec_frame(address: u8, command: u8) -> u32 {
+    u32::from_le_bytes([address, !address, command, !command])
+}
+
+/// Builds the envelope for a frame.  The stop mark has no space, as the gap
+/// to the next frame is up to the sender.
+pub fn nec_envelope(frame: u32) -> [Pulse; ENVELOPE_LEN] {
+    let mut envelope = [Pulse {
+        mark_ns: BIT_MARK_NS,
+        space_ns: 0,
+    }; ENVELOPE_LEN];
+    envelope[0] = Pulse {
+        mark_ns: LEADER_MARK_NS,
+        space_ns: LEADER_SPACE_NS,
+    };
+    for bit in 0..FRAME_BITS {
+        envelope[bit + 1].space_ns = if frame & (1 << bit) != 0 {
+            ONE_SPACE_NS
+        } else {
+            ZERO_SPACE_NS
+        };
+    }
+    envelope
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame() {
+        // Address, !address, command, !command, each sent LSB first.
+        assert_eq!(nec_frame(0x00, 0x45), 0xba45_ff00);
+        assert_eq!(nec_frame(0x12, 0x34), 0xcb34_ed12);
+    }
+
+    #[test]
+    fn leader() {
+        let envelope = nec_envelope(nec_frame(0x00, 0x45));
+        assert_eq!(
+            envelope[0],
+            Pulse {
+                mark_ns: 9_000_000,
+                space_ns: 4_500_000,
+            }
+        );
+    }
+
+    #[test]
+    fn bits() {
+        let frame = nec_frame(0x00, 0x45);
+        let envelope = nec_envelope(frame);
+        for bit in 0..FRAME_BITS {
+            let space_ns = if frame >> bit & 1 != 0 {
+                1_687_500
+            } else {
+                562_500
+            };
+            assert_eq!(
+                envelope[bit + 1],
+                Pulse {
+                    mark_ns: 562_500,
+                    space_ns,
+                },
+                "bit {}",
+                bit
+            );
+        }
+
+        // The address byte 0x00 is all 0s, and its inverse all 1s.
+        assert!(envelope[1..9].iter().all(|pulse| pulse.space_ns == 562_500));
+        assert!(
+            envelope[9..17]
+                .iter()
+                .all(|pulse| pulse.space_ns == 1_687_500)
+        );
+        // The command 0x45, 0b0100_0101, sent LSB first.
+        let command: [u32; 8] = core::array::from_fn(|i| envelope[17 + i].space_ns);
+        assert_eq!(
+            command,
+            [
+                1_687_500, 562_500, 1_687_500, 562_500, 562_500, 562_500, 1_687_500, 562_500
+            ]
+        );
+    }
+
+    #[test]
+    fn stop_mark() {
+        let envelope = nec_envelope(nec_frame(0x00, 0x45));
+        assert_eq!(envelope.len(), 34);
+        assert_eq!(
+            envelope[ENVELOPE_LEN - 1],
+            Pulse {
+                mark_ns: 562_500,
+                space_ns: 0,
+            }
+        );
+    }
+}
@@ -0,0 +1,311 @@
+//! Frequency and period parser, for human-entered values.
+//!
+//! Parses values such as "1kHz", "200us", "2.5MHz" or "34 ns", so a period
+//! can be set at runtime without recompiling (for example by a UART or USB
+//! command handler) - see the `timing` binary's variable period toggle
+//! test.
+//!
+//! A value is a decimal number, optionally with a fractional part, followed
+//! by a unit - Hz, kHz or MHz for a frequency, or s, ms, us (or µs) or ns
+//! for a period.  Units are case insensitive, so "mhz" is MHz (there is no
+//! use for millihertz here), and may be separated from the number by
+//! spaces.
+//!
+//! Values are held exactly, in fixed point - periods in ps and frequencies
+//! in mHz - so decimals never pick up float rounding.  Anything else is
+//! rejected: missing or unknown units, signs, exponents, more decimal
+//! places than the unit's resolution, zero, and values which overflow.
+//! Whether a value is achievable depends on what it is used for, so that
+//! is left to the caller.
+
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+/// A parsed frequency or period.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(target_os = "none", derive(defmt::Format))]
+pub enum PeriodSpec {
+    /// Frequency, in mHz.
+    Frequency { millihz: u64 },
+    /// Period, in ps.
+    Period { ps: u64 },
+}
+
+/// Reasons a value was rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(target_os = "none", derive(defmt::Format))]
+pub enum ParseError {
+    /// Nothing but whitespace.
+    Empty,
+    /// No digits, more than one decimal point, a sign, or an exponent.
+    InvalidNumber,
+    /// A number with no unit.
+    MissingUnit,
+    /// A unit other than those listed above.
+    UnknownUnit,
+    /// More decimal places than the unit's resolution holds.
+    TooPrecise,
+    /// A zero frequency or period.
+    Zero,
+    /// Too large to hold.
+    OutOfRange,
+}
+
+// Units, and the number of PeriodSpec base units (ps or mHz) in each.
+const PERIOD_UNITS: &[(&str, u64)] = &[
+    ("s", 1_000_000_000_000),
+    ("ms", 1_000_000_000),
+    ("us", 1_000_000),
+    ("µs", 1_000_000),
+    ("ns", 1_000),
+];
+const FREQUENCY_UNITS: &[(&str, u64)] =
+    &[("hz", 1_000), ("khz", 1_000_000), ("mhz", 1_000_000_000)];
+
+impl PeriodSpec {
+    /// Returns the period in clk_sys cycles at the given speed, rounded to
+    /// the nearest cycle.
+    pub fn cycles(self, speed: u32) -> u64 {
+        let speed = speed as u128;
+        let cycles = match self {
+            PeriodSpec::Period { ps } => (ps as u128 * speed + 500_000_000_000) / 1_000_000_000_000,
+            PeriodSpec::Frequency { millihz } => {
+                let millihz = millihz as u128;
+                (speed * 1000 + millihz / 2) / millihz
+            }
+        };
+        cycles.min(u64::MAX as u128) as u64
+    }
+}
+
+/// Parses a frequency or period, such as "1kHz", "200us" or "2.5MHz".
+pub fn parse_freq_or_period(s: &str) -> Result<PeriodSpec, ParseError> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    // Split the number from the unit at the first character which can't be
+    // part of the number.
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let unit = unit.trim_start();
+    // No unit has a digit or sign in it, so one there is from a sign, as in
+    // "1-us", or an exponent, as in "1e3Hz", rather than a bad unit.
+    if number.is_empty() || unit.contains(|c: char| c.is_ascii_digit() || c == '+' || c == '-') {
+        return Err(ParseError::InvalidNumber);
+    }
+    if unit.is_empty() {
+        return Err(ParseError::MissingUnit);
+    }
+
+    let (scale, is_period) = parse_unit(unit)?;
+    let value = parse_fixed(number, scale)?;
+    if value == 0 {
+        return Err(ParseError::Zero);
+    }
+
+    Ok(if is_period {
+        PeriodSpec::Period { ps: value }
+    } else {
+        PeriodSpec::Frequency { millihz: value }
+    })
+}
+
+// Looks up a unit, returning its scale and whether it is a period.
+fn parse_unit(unit: &str) -> Result<(u64, bool), ParseError> {
+    let find = |units: &[(&str, u64)]| {
+        units
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+            .map(|&(_, scale)| scale)
+    };
+    if let Some(scale) = find(PERIOD_UNITS) {
+        Ok((scale, true))
+    } else if let Some(scale) = find(FREQUENCY_UNITS) {
+        Ok((scale, false))
+    } else {
+        Err(ParseError::UnknownUnit)
+    }
+}
+
+// Converts a decimal number to an exact multiple of 1 / scale of its unit.
+fn parse_fixed(number: &str, scale: u64) -> Result<u64, ParseError> {
+    let (whole, frac) = match number.split_once('.') {
+        Some((whole, frac)) => (whole, frac),
+        None => (number, ""),
+    };
+    // A decimal point needs a digit on at least one side, and there can
+    // only be one.
+    if (whole.is_empty() && frac.is_empty()) || frac.contains('.') {
+        return Err(ParseError::InvalidNumber);
+    }
+
+    let mut value = 0u64;
+    for c in whole.bytes() {
+        value = value
+            .checked_mul(10)
+            .and_then(|v| v.checked_add((c - b'0') as u64))
+            .ok_or(ParseError::OutOfRange)?;
+    }
+    value = value.checked_mul(scale).ok_or(ParseError::OutOfRange)?;
+
+    // Each decimal place is worth a tenth of the last.  Trailing zeros are
+    // fine, beyond the unit's resolution.
+    let mut place = scale;
+    for c in frac.bytes() {
+        let digit = (c - b'0') as u64;
+        if !place.is_multiple_of(10) {
+            if digit != 0 {
+                return Err(ParseError::TooPrecise);
+            }
+            continue;
+        }
+        place /= 10;
+        value = value
+            .checked_add(digit * place)
+            .ok_or(ParseError::OutOfRange)?;
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn period(ps: u64) -> Result<PeriodSpec, ParseError> {
+        Ok(PeriodSpec::Period { ps })
+    }
+
+    fn frequency(millihz: u64) -> Result<PeriodSpec, ParseError> {
+        Ok(PeriodSpec::Frequency { millihz })
+    }
+
+    #[test]
+    fn examples() {
+        assert_eq!(parse_freq_or_period("1kHz"), frequency(1_000_000));
+        assert_eq!(parse_freq_or_period("200us"), period(200_000_000));
+        assert_eq!(parse_freq_or_period("2.5MHz"), frequency(2_500_000_000));
+        assert_eq!(parse_freq_or_period("34ns"), period(34_000));
+    }
+
+    #[test]
+    fn spaces_and_units() {
+        assert_eq!(parse_freq_or_period("34 ns"), period(34_000));
+        assert_eq!(parse_freq_or_period("  34ns  "), period(34_000));
+        assert_eq!(parse_freq_or_period("5µs"), period(5_000_000));
+        assert_eq!(parse_freq_or_period("5us"), period(5_000_000));
+        assert_eq!(parse_freq_or_period("2s"), period(2_000_000_000_000));
+        assert_eq!(parse_freq_or_period("3ms"), period(3_000_000_000));
+        assert_eq!(parse_freq_or_period("50Hz"), frequency(50_000));
+    }
+
+    #[test]
+    fn case_insensitive() {
+        assert_eq!(parse_freq_or_period("1mhz"), frequency(1_000_000_000));
+        assert_eq!(parse_freq_or_period("1MHZ"), frequency(1_000_000_000));
+        assert_eq!(parse_freq_or_period("1KHz"), frequency(1_000_000));
+        assert_eq!(parse_freq_or_period("10US"), period(10_000_000));
+    }
+
+    #[test]
+    fn exact_decimals() {
+        assert_eq!(parse_freq_or_period("0.1us"), period(100_000));
+        assert_eq!(parse_freq_or_period("1.234567ms"), period(1_234_567_000));
+        assert_eq!(parse_freq_or_period("0.001Hz"), frequency(1));
+        assert_eq!(parse_freq_or_period("33.333kHz"), frequency(33_333_000));
+        assert_eq!(parse_freq_or_period(".5us"), period(500_000));
+        assert_eq!(parse_freq_or_period("5.us"), period(5_000_000));
+        assert_eq!(parse_freq_or_period("1.500000ns"), period(1_500));
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(parse_freq_or_period(""), Err(ParseError::Empty));
+        assert_eq!(parse_freq_or_period("   "), Err(ParseError::Empty));
+    }
+
+    #[test]
+    fn invalid_number() {
+        assert_eq!(parse_freq_or_period("us"), Err(ParseError::InvalidNumber));
+        assert_eq!(parse_freq_or_period("-1us"), Err(ParseError::InvalidNumber));
+        assert_eq!(parse_freq_or_period("+1us"), Err(ParseError::InvalidNumber));
+        assert_eq!(parse_freq_or_period("1-us"), Err(ParseError::InvalidNumber));
+        assert_eq!(
+            parse_freq_or_period("1e3Hz"),
+            Err(ParseError::InvalidNumber)
+        );
+        assert_eq!(
+            parse_freq_or_period("1.5e-3s"),
+            Err(ParseError::InvalidNumber)
+        );
+        assert_eq!(
+            parse_freq_or_period("1.2.3us"),
+            Err(ParseError::InvalidNumber)
+        );
+        assert_eq!(parse_freq_or_period(".us"), Err(ParseError::InvalidNumber));
+    }
+
+    #[test]
+    fn missing_unit() {
+        assert_eq!(parse_freq_or_period("100"), Err(ParseError::MissingUnit));
+        assert_eq!(parse_freq_or_period("2.5 "), Err(ParseError::MissingUnit));
+    }
+
+    #[test]
+    fn unknown_unit() {
+        assert_eq!(parse_freq_or_period("100ks"), Err(ParseError::UnknownUnit));
+        assert_eq!(
+            parse_freq_or_period("10 furlongs"),
+            Err(ParseError::UnknownUnit)
+        );
+        assert_eq!(parse_freq_or_period("1 k Hz"), Err(ParseError::UnknownUnit));
+    }
+
+    #[test]
+    fn too_precise() {
+        assert_eq!(
+            parse_freq_or_period("1.0005ns"),
+            Err(ParseError::TooPrecise)
+        );
+        assert_eq!(
+            parse_freq_or_period("1.0001Hz"),
+            Err(ParseError::TooPrecise)
+        );
+    }
+
+    #[test]
+    fn zero() {
+        assert_eq!(parse_freq_or_period("0Hz"), Err(ParseError::Zero));
+        assert_eq!(parse_freq_or_period("0.000us"), Err(ParseError::Zero));
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(
+            parse_freq_or_period("100000000s"),
+            Err(ParseError::OutOfRange)
+        );
+        assert_eq!(
+            parse_freq_or_period("99999999999999999999ns"),
+            Err(ParseError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn cycles() {
+        assert_eq!(
+            PeriodSpec::Period { ps: 1_000_000 }.cycles(125_000_000),
+            125
+        );
+        assert_eq!(PeriodSpec::Period { ps: 8_004 }.cycles(125_000_000), 1);
+        assert_eq!(
+            PeriodSpec::Frequency { millihz: 1_000_000 }.cycles(125_000_000),
+            125_000
+        );
+    }
+}
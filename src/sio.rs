@@ -0,0 +1,86 @@
+//! SIO GPIO register addresses, and safe helpers to access them.
+//!
+//! The helpers each make a single volatile access, to a fixed register, so
+//! only affect the GPIOs given.  They take effect on GPIOs configured as
+//! SIO outputs, such as with embassy's `Output`.
+
+// Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT licensed - see https://opensource.org/license/MIT
+
+/// SIO base address
+pub const SIO_BASE: u32 = 0xd0000000;
+/// GPIO input register (reading returns the current level of each pin)
+pub const GPIO_IN: u32 = SIO_BASE + 0x004;
+/// GPIO output register (writing sets the level of every pin)
+pub const GPIO_OUT: u32 = SIO_BASE + 0x010;
+
+// GPIO atomic set, clear and XOR registers (writing 1 sets/clears/toggles
+// that pin only, leaving all other pins untouched).  The RP235X interleaves
+// the GPIO_HI_OUT_SET/CLR/XOR registers, so these are at different offsets
+// to the RP2040.
+
+/// GPIO output atomic set register
+#[cfg(feature = "pico")]
+pub const GPIO_OUT_SET: u32 = SIO_BASE + 0x014;
+/// GPIO output atomic clear register
+#[cfg(feature = "pico")]
+pub const GPIO_OUT_CLR: u32 = SIO_BASE + 0x018;
+/// GPIO output atomic XOR register
+#[cfg(feature = "pico")]
+pub const GPIO_OUT_XOR: u32 = SIO_BASE + 0x01c;
+/// GPIO output atomic set register
+#[cfg(feature = "pico2")]
+pub const GPIO_OUT_SET: u32 = SIO_BASE + 0x018;
+/// GPIO output atomic clear register
+#[cfg(feature = "pico2")]
+pub const GPIO_OUT_CLR: u32 = SIO_BASE + 0x020;
+/// GPIO output atomic XOR register
+#[cfg(feature = "pico2")]
+pub const GPIO_OUT_XOR: u32 = SIO_BASE + 0x028;
+
+/// Returns the current level of every GPIO, from GPIO_IN.
+#[inline(always)]
+pub fn read_gpios() -> u32 {
+    // Safety: GPIO_IN is always readable, with no side effects.
+    unsafe { core::ptr::read_volatile(GPIO_IN as *const u32) }
+}
+
+/// Sets every GPIO's output level, by writing value to GPIO_OUT.  Prefer
+/// [`set_gpios()`] and [`clear_gpios()`], which leave other GPIOs alone.
+#[inline(always)]
+pub fn write_gpios(value: u32) {
+    // Safety: Writing GPIO_OUT only changes the SIO output levels.
+    unsafe { core::ptr::write_volatile(GPIO_OUT as *mut u32, value) }
+}
+
+/// Drives the GPIOs in mask high, by writing it to GPIO_OUT_SET.
+#[inline(always)]
+pub fn set_gpios(mask: u32) {
+    // Safety: As for write_gpios().
+    unsafe { core::ptr::write_volatile(GPIO_OUT_SET as *mut u32, mask) }
+}
+
+/// Drives the GPIOs in mask low, by writing it to GPIO_OUT_CLR.
+#[inline(always)]
+pub fn clear_gpios(mask: u32) {
+    // Safety: As for write_gpios().
+    unsafe { core::ptr::write_volatile(GPIO_OUT_CLR as *mut u32, mask) }
+}
+
+/// Toggles the GPIOs in mask, by writing it to GPIO_OUT_XOR.
+#[inline(always)]
+pub fn toggle_gpios(mask: u32) {
+    // Safety: As for write_gpios().
+    unsafe { core::ptr::write_volatile(GPIO_OUT_XOR as *mut u32, mask) }
+}
+
+/// Returns the two GPIO_OUT values that drive GPIOs a and b to opposite
+/// levels - a high and b low, then a low and b high.  Alternating them with
+/// [`write_gpios()`] toggles the two in antiphase, with both edges from the
+/// same store, so they are simultaneous, and each store sets both levels,
+/// so they can't drift into phase.  All other SIO outputs are driven low.
+pub const fn antiphase_masks(a: u8, b: u8) -> [u32; 2] {
+    assert!(a != b, "Antiphase GPIOs must differ");
+    [1 << a, 1 << b]
+}
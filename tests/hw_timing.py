@@ -0,0 +1,136 @@
+#!/usr/bin/env python3
+"""Hardware timing regression test.
+
+Builds and flashes a single GPIO test with the measure feature, using cargo
+run and the probe-rs runner in .cargo/config.toml, and captures the defmt
+output.  Checks the periods the firmware measures, from its "timing: "
+lines (see src/bin/timing/period_measure.rs), are within a tolerance of the
+test's expected period.  Exits 0 if all pass, 1 on any failure.
+
+The firmware only builds for the Pico targets, so this is a host script
+rather than a cargo test.  It needs only Python 3 and a probe attached to
+the board.  For example, to check single GPIO test 2 on a Pico 2 within
+1%:
+
+    tests/hw_timing.py --board pico2 --test 2 --tolerance-ppm 10000
+
+The first report is skipped by default, as it includes the time to start
+the test.  Only tests with an expected period, and which use a software
+delay, so are measured, can be checked.
+"""
+
+# Copyright (c) 2025 Piers Finlayson <piers@piers.rocks>
+#
+# MIT licensed - see https://opensource.org/license/MIT
+
+import argparse
+import os
+import re
+import subprocess
+import sys
+import threading
+
+TARGETS = {
+    "pico": "thumbv6m-none-eabi",
+    "pico2": "thumbv8m.main-none-eabihf",
+}
+
+TIMING_LINE = re.compile(r"timing: (.*)$")
+
+
+def parse_kv(text):
+    """Returns the key=value pairs in text as a dict."""
+    return dict(pair.split("=", 1) for pair in text.split() if "=" in pair)
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("--board", choices=TARGETS, default="pico")
+    parser.add_argument("--test", type=int, required=True, help="single GPIO test number")
+    parser.add_argument("--tolerance-ppm", type=int, default=10_000)
+    parser.add_argument("--reports", type=int, default=5, help="reports to check")
+    parser.add_argument("--skip", type=int, default=1, help="initial reports to skip")
+    parser.add_argument("--timeout", type=float, default=120, help="seconds, including the build")
+    parser.add_argument("--release", action="store_true")
+    parser.add_argument("--features", default="", help="extra features, comma separated")
+    args = parser.parse_args()
+
+    features = [args.board, "rtt", "single-gpio", "measure", str(args.test)]
+    features += [f for f in args.features.split(",") if f]
+    cmd = [
+        "cargo", "run",
+        "--target", TARGETS[args.board],
+        "--no-default-features",
+        "--features", ",".join(features),
+    ]
+    if args.release:
+        cmd.append("--release")
+
+    root = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+    print("Running:", " ".join(cmd), flush=True)
+    proc = subprocess.Popen(
+        cmd, cwd=root, stdout=subprocess.PIPE, stderr=subprocess.STDOUT, text=True
+    )
+
+    expected_ns = None
+    skipped = 0
+    periods = []
+    failures = 0
+    timer = threading.Timer(args.timeout, proc.kill)
+    timer.start()
+    try:
+        for line in proc.stdout:
+            line = line.rstrip()
+            print(line, flush=True)
+
+            match = TIMING_LINE.search(line)
+            if not match:
+                continue
+            kv = parse_kv(match.group(1))
+
+            if "test" in kv:
+                if int(kv["test"]) != args.test:
+                    print(f"FAIL: firmware is running test {kv['test']}", flush=True)
+                    return 1
+                if "expected_ns" not in kv:
+                    print(f"FAIL: test {args.test} has no expected period", flush=True)
+                    return 1
+                expected_ns = int(kv["expected_ns"])
+                continue
+
+            if "period_ns" not in kv or expected_ns is None:
+                continue
+            if skipped < args.skip:
+                skipped += 1
+                continue
+
+            period_ns = int(kv["period_ns"])
+            error_ppm = abs(period_ns - expected_ns) * 1_000_000 // expected_ns
+            result = "PASS" if error_ppm <= args.tolerance_ppm else "FAIL"
+            if result == "FAIL":
+                failures += 1
+            print(
+                f"{result}: period {period_ns}ns, expected {expected_ns}ns, "
+                f"error {error_ppm}ppm",
+                flush=True,
+            )
+            periods.append(period_ns)
+            if len(periods) >= args.reports:
+                break
+        else:
+            print(f"FAIL: exited or timed out before {args.reports} reports")
+            return 1
+    finally:
+        timer.cancel()
+        proc.terminate()
+        proc.wait()
+
+    if failures:
+        print(f"FAIL: {failures}/{len(periods)} reports outside {args.tolerance_ppm}ppm")
+        return 1
+    print(f"PASS: {len(periods)} reports within {args.tolerance_ppm}ppm")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())